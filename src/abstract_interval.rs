@@ -2,12 +2,12 @@ use num_traits::Zero;
 use std::cmp::{Ordering, PartialOrd};
 use std::collections::HashSet;
 use std::fmt::{self, Debug};
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Rem, Shl, Shr, Sub};
 
 use crate::abstract_domain::{AbstractDomainOps, AbstractValue, ConcreteValue};
 use crate::ast::Op;
 use crate::N;
-use crate::{find_max, find_min, CONSTANTS_VECTOR, M};
+use crate::{find_max, find_min, M, THRESHOLDS};
 
 #[derive(Debug, Clone, Copy, Eq, Hash)]
 pub enum AbstractInterval {
@@ -147,6 +147,32 @@ impl AbstractInterval {
             }
         }
     }
+    // Machine values of the interval, bounds equal to m and n stand for -∞ and +∞, which no
+    // machine integer goes beyond
+    pub fn machine(&self) -> Option<(i128, i128)> {
+        let m = *M.lock().expect("failed to lock m mutex");
+        let n = *N.lock().expect("failed to lock n mutex");
+        match self {
+            Self::Bottom => None,
+            Self::Top => Some((i64::MIN as i128, i64::MAX as i128)),
+            Self::Bounded { lower, upper } => Some((
+                if *lower == m { i64::MIN as i128 } else { *lower as i128 },
+                if *upper == n { i64::MAX as i128 } else { *upper as i128 },
+            )),
+        }
+    }
+    // Interval of machine values, the bounds beyond m and n going back to -∞ and +∞
+    pub fn from_machine(lower: i128, upper: i128) -> Self {
+        let m = *M.lock().expect("failed to lock m mutex");
+        let n = *N.lock().expect("failed to lock n mutex");
+        if lower > upper {
+            return Self::Bottom;
+        }
+        Self::Bounded {
+            lower: lower.clamp(m as i128, n as i128) as i64,
+            upper: upper.clamp(m as i128, n as i128) as i64,
+        }
+    }
     pub fn get_low(i: AbstractInterval) -> i64 {
         match i {
             AbstractInterval::Bottom => unreachable!("tried to find lower of a bottom Interval"),
@@ -214,9 +240,7 @@ impl AbstractInterval {
             ) => {
                 let _m = *M.lock().expect("failed to lock m mutex");
                 let _n = *N.lock().expect("failed to lock n mutex");
-                let mut vec = CONSTANTS_VECTOR
-                    .lock()
-                    .expect("failed to lock constant vector");
+                let mut vec = THRESHOLDS.lock().expect("failed to lock thresholds");
 
                 let new_lower = if l1 <= &l2 {
                     *l1
@@ -344,8 +368,17 @@ impl AbstractInterval {
                 [lhs_ref, rhs_ref]
             }
             Op::Mul => {
-                let lhs_ref = lhs.intersect(&(result / rhs));
-                let rhs_ref = rhs.intersect(&(result / lhs));
+                // a factor that may be zero says nothing about the other one
+                let lhs_ref = if rhs.contains_zero() {
+                    lhs
+                } else {
+                    lhs.intersect(&(result / rhs))
+                };
+                let rhs_ref = if lhs.contains_zero() {
+                    rhs
+                } else {
+                    rhs.intersect(&(result / lhs))
+                };
                 [lhs_ref, rhs_ref]
             }
             Op::Div => {
@@ -360,6 +393,89 @@ impl AbstractInterval {
                 );
                 [lhs_ref, rhs_ref]
             }
+            Op::Mod => {
+                // the remainder has the sign of the dividend and is smaller than the divisor in
+                // magnitude
+                let (Some((lr, ur)), Some((l1, u1)), Some((l2, u2))) =
+                    (result.machine(), lhs.machine(), rhs.machine())
+                else {
+                    return [Self::Bottom, Self::Bottom];
+                };
+                let lhs_ref = if lr >= 1 {
+                    Self::from_machine(l1.max(lr), u1)
+                } else if ur <= -1 {
+                    Self::from_machine(l1, u1.min(ur))
+                } else {
+                    lhs
+                };
+                let least = if lr >= 1 { lr } else if ur <= -1 { -ur } else { 0 };
+                let rhs_ref = if l2 >= 0 {
+                    Self::from_machine(l2.max(least + 1), u2)
+                } else if u2 <= 0 {
+                    Self::from_machine(l2, u2.min(-least - 1))
+                } else {
+                    rhs
+                };
+                [lhs_ref, rhs_ref]
+            }
+            Op::BitAnd | Op::BitOr | Op::BitXor => {
+                let (Some((lr, ur)), Some(left), Some(right)) =
+                    (result.machine(), lhs.machine(), rhs.machine())
+                else {
+                    return [Self::Bottom, Self::Bottom];
+                };
+                let refine = |(l, u): (i128, i128), (other_l, other_u): (i128, i128)| {
+                    let (l, u) = match operator {
+                        // a negative conjunction has negative operands, none below it; it is
+                        // below a non-negative operand
+                        Op::BitAnd if ur <= -1 => (l.max(lr), u.min(-1)),
+                        Op::BitAnd if l >= 0 => (l.max(lr.max(0)), u),
+                        // a non-negative disjunction has non-negative operands, none above it; it
+                        // is above a negative operand
+                        Op::BitOr if lr >= 0 => (l.max(0), u.min(ur)),
+                        Op::BitOr if u <= -1 => (l, u.min(ur)),
+                        // the exclusive or is negative when the signs of the operands differ
+                        Op::BitXor if (lr >= 0 && other_l >= 0) || (ur <= -1 && other_u <= -1) => {
+                            (l.max(0), u)
+                        }
+                        Op::BitXor if (lr >= 0 && other_u <= -1) || (ur <= -1 && other_l >= 0) => {
+                            (l, u.min(-1))
+                        }
+                        _ => (l, u),
+                    };
+                    Self::from_machine(l, u)
+                };
+                [
+                    lhs.intersect(&refine(left, right)),
+                    rhs.intersect(&refine(right, left)),
+                ]
+            }
+            Op::Shl | Op::Shr => {
+                // the shift amount is within [0, 63], the invalid ones raise an alarm
+                let rhs_ref = rhs.intersect(&Self::new(0, 63));
+                let (Some((lr, ur)), Some((l1, u1)), Some((l2, u2))) =
+                    (result.machine(), lhs.machine(), rhs_ref.machine())
+                else {
+                    return [Self::Bottom, Self::Bottom];
+                };
+                let (least, most) = (1i128 << l2, 1i128 << u2);
+                let (lower, upper) = match operator {
+                    // an overflowing left shift fails, the result is exactly lhs * 2^rhs
+                    Op::Shl => (
+                        (lr / least).min(lr / most),
+                        (ur / least).max(ur / most),
+                    ),
+                    // lhs >> rhs is the floor of lhs / 2^rhs
+                    _ => (
+                        lr.saturating_mul(least).min(lr.saturating_mul(most)),
+                        (ur + 1)
+                            .saturating_mul(least)
+                            .max((ur + 1).saturating_mul(most))
+                            - 1,
+                    ),
+                };
+                [Self::from_machine(l1.max(lower), u1.min(upper)), rhs_ref]
+            }
             Op::Uminus => {
                 unreachable!("error in backward arithmetic binary operator ");
             }
@@ -381,6 +497,13 @@ impl AbstractInterval {
             _ => false,
         }
     }
+    pub fn contains_zero(&self) -> bool {
+        match self {
+            Self::Bottom => false,
+            Self::Top => true,
+            Self::Bounded { lower, upper } => *lower <= 0 && 0 <= *upper,
+        }
+    }
     pub fn is_bottom(&self) -> bool {
         match self {
             Self::Bottom => true,
@@ -758,6 +881,139 @@ impl Neg for AbstractInterval {
     }
 }
 
+// Least power of two greater than the non-negative `value`
+fn bit_ceiling(value: i128) -> i128 {
+    (value as u128 + 1).next_power_of_two() as i128
+}
+
+// Machine bounds of an interval whose values have all the same sign
+type SignPart = (i128, i128);
+
+// Bitwise operator computed on the sign parts of the operands: `part` bounds the result of
+// operands within the sign parts it gets
+fn bitwise(
+    lhs: AbstractInterval,
+    rhs: AbstractInterval,
+    part: fn(SignPart, SignPart) -> SignPart,
+) -> AbstractInterval {
+    let (Some(left), Some(right)) = (lhs.machine(), rhs.machine()) else {
+        return AbstractInterval::Bottom;
+    };
+    let signs = |(lower, upper): (i128, i128)| {
+        [
+            (lower < 0).then(|| (lower, upper.min(-1))),
+            (upper >= 0).then(|| (lower.max(0), upper)),
+        ]
+    };
+    let mut result = AbstractInterval::Bottom;
+    for left in signs(left).into_iter().flatten() {
+        for right in signs(right).into_iter().flatten() {
+            let (lower, upper) = part(left, right);
+            result = result.int_lub(&AbstractInterval::from_machine(lower, upper));
+        }
+    }
+    result
+}
+
+impl Rem for AbstractInterval {
+    type Output = Self;
+
+    // Truncated remainder: it has the sign of the dividend and is smaller than the divisor in
+    // magnitude
+    fn rem(self, other: Self) -> Self::Output {
+        let (Some((l1, u1)), Some((l2, u2))) = (self.machine(), other.machine()) else {
+            return Self::Bottom;
+        };
+        if l2 == 0 && u2 == 0 {
+            return Self::Bottom;
+        }
+        let largest = l2.abs().max(u2.abs()) - 1;
+        // dividends within one period of a single divisor keep their order
+        let single = l2 == u2 && l2.abs() < i64::MAX as i128;
+        let bounded = l1 > i64::MIN as i128 && u1 < i64::MAX as i128;
+        if single && bounded && l1 / l2.abs() == u1 / l2.abs() {
+            return Self::from_machine(l1 % l2.abs(), u1 % l2.abs());
+        }
+        let lower = if l1 >= 0 { 0 } else { l1.max(-largest) };
+        let upper = if u1 <= 0 { 0 } else { u1.min(largest) };
+        Self::from_machine(lower, upper)
+    }
+}
+
+impl BitAnd for AbstractInterval {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self::Output {
+        bitwise(self, other, |(l1, u1), (l2, u2)| match (l1 >= 0, l2 >= 0) {
+            // clearing bits of a non-negative operand keeps it between 0 and itself
+            (true, true) => (0, u1.min(u2)),
+            (true, false) => (0, u1),
+            (false, true) => (0, u2),
+            (false, false) => (-bit_ceiling((-l1).max(-l2) - 1), u1.min(u2)),
+        })
+    }
+}
+
+impl BitOr for AbstractInterval {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self::Output {
+        bitwise(self, other, |(l1, u1), (l2, u2)| match (l1 >= 0, l2 >= 0) {
+            // setting bits never goes below the operands nor beyond the highest bit
+            (true, true) => (l1.max(l2), bit_ceiling(u1.max(u2)) - 1),
+            (true, false) => (l2, -1),
+            (false, true) => (l1, -1),
+            (false, false) => (l1.max(l2), -1),
+        })
+    }
+}
+
+impl BitXor for AbstractInterval {
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self::Output {
+        bitwise(self, other, |(l1, u1), (l2, u2)| match (l1 >= 0, l2 >= 0) {
+            (true, true) => (0, bit_ceiling(u1.max(u2)) - 1),
+            (true, false) => (-bit_ceiling(u1.max(-l2 - 1)), -1),
+            (false, true) => (-bit_ceiling(u2.max(-l1 - 1)), -1),
+            (false, false) => (0, bit_ceiling((-l1 - 1).max(-l2 - 1)) - 1),
+        })
+    }
+}
+
+// Shift amounts within [0, 63], the invalid ones raise an alarm and have no result
+fn shift_amounts(amount: AbstractInterval) -> Option<(i128, i128)> {
+    amount.intersect(&AbstractInterval::new(0, 63)).machine()
+}
+
+impl Shl for AbstractInterval {
+    type Output = Self;
+
+    // lhs * 2^rhs, an overflow raises an alarm
+    fn shl(self, other: Self) -> Self::Output {
+        let (Some((l1, u1)), Some((l2, u2))) = (self.machine(), shift_amounts(other)) else {
+            return Self::Bottom;
+        };
+        let (least, most) = (1i128 << l2, 1i128 << u2);
+        Self::from_machine(
+            (l1 * least).min(l1 * most),
+            (u1 * least).max(u1 * most),
+        )
+    }
+}
+
+impl Shr for AbstractInterval {
+    type Output = Self;
+
+    // Arithmetic shift, the floor of lhs / 2^rhs
+    fn shr(self, other: Self) -> Self::Output {
+        let (Some((l1, u1)), Some((l2, u2))) = (self.machine(), shift_amounts(other)) else {
+            return Self::Bottom;
+        };
+        Self::from_machine((l1 >> l2).min(l1 >> u2), (u1 >> l2).max(u1 >> u2))
+    }
+}
+
 impl Ord for AbstractInterval {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match (self, other) {
@@ -1,11 +1,12 @@
 use std::{
     cmp::Ordering,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
 };
 // use crate::abstract_interval::AbstractInterval;
 use crate::{
     abstract_domain::{AbstractDomain, AbstractDomainOps, AbstractValue, ConcreteValue},
     abstract_interval::AbstractInterval,
+    booleans,
 };
 use std::fmt::{self, Debug};
 
@@ -13,6 +14,7 @@ use std::fmt::{self, Debug};
 pub struct AbstractState<Q: AbstractDomainOps + Clone> {
     pub is_bottom: bool, // Bottom flag ⊥
     pub variables: HashMap<String, AbstractDomain<Q>>,
+    pub relations: BTreeMap<String, usize>, // boolean variables related to their guard, see booleans.rs
 }
 
 impl<Q> PartialEq for AbstractDomain<Q>
@@ -33,6 +35,7 @@ where
         Self {
             is_bottom: false,
             variables: HashMap::new(),
+            relations: BTreeMap::new(),
         }
     }
     // Checks if the state contains a Top interval
@@ -46,6 +49,7 @@ where
         AbstractState {
             is_bottom: true,
             variables: self.variables.clone(),
+            relations: self.relations.clone(),
         }
     }
     // Checks if the state is ⊥
@@ -91,6 +95,19 @@ where
         // Restituisce lo stato aggiornato
         self.clone()
     }
+    // Drops the relations broken by assigning `variable`: its own and those of the guards reading it
+    pub fn forget(&mut self, variable: &str) {
+        self.relations
+            .retain(|boolean, relation| boolean != variable && !booleans::reads(*relation, variable));
+    }
+    // Relations holding in both states
+    fn common_relations(&self, other: &AbstractState<Q>) -> BTreeMap<String, usize> {
+        self.relations
+            .iter()
+            .filter(|(boolean, relation)| other.relations.get(*boolean) == Some(relation))
+            .map(|(boolean, relation)| (boolean.clone(), *relation))
+            .collect()
+    }
     // Least Upper Bound variable wise
     pub fn state_lub(&self, other: &AbstractState<Q>) -> AbstractState<Q> {
         // let self_flag= self.is_bottom();
@@ -130,6 +147,7 @@ where
         AbstractState {
             is_bottom: false,
             variables: new_variables,
+            relations: self.common_relations(other),
         }
     }
     pub fn state_glb(&self, other: &AbstractState<Q>) -> AbstractState<Q> {
@@ -161,9 +179,12 @@ where
             }
         }
 
+        let mut relations = other.relations.clone();
+        relations.extend(self.relations.clone());
         AbstractState {
             is_bottom: false,
             variables: new_variables,
+            relations,
         }
     }
     // Widening operator variable wise
@@ -180,8 +201,12 @@ where
 
         for (key, left_interval) in &self.variables {
             if let Some(right_interval) = other.variables.get(key) {
-                // Interval widening for every variable in both states
-                new_variables.insert(key.clone(), left_interval.widening(right_interval));
+                // Interval widening for every variable in both states, booleans are joined
+                if booleans::declared(key) {
+                    new_variables.insert(key.clone(), left_interval.lub(right_interval));
+                } else {
+                    new_variables.insert(key.clone(), left_interval.widening(right_interval));
+                }
             } else {
                 new_variables.insert(key.clone(), left_interval.clone());
             }
@@ -196,9 +221,16 @@ where
         let newstate = AbstractState {
             is_bottom: false,
             variables: new_variables,
+            relations: self.common_relations(other),
         };
         newstate
     }
+    // Variables ordered by name, for output that does not depend on the HashMap order
+    pub fn sorted_variables(&self) -> Vec<(&String, &AbstractDomain<Q>)> {
+        let mut variables: Vec<(&String, &AbstractDomain<Q>)> = self.variables.iter().collect();
+        variables.sort_by(|a, b| a.0.cmp(b.0));
+        variables
+    }
     pub fn _lookup(&mut self, var: &str) -> AbstractDomain<Q> {
         let res = self
             .variables
@@ -239,6 +271,7 @@ where
         let newstate = AbstractState {
             is_bottom: false, // Lo stato risultante non è Bottom
             variables: new_variables,
+            relations: self.relations.clone(),
         };
         newstate
     }
@@ -348,6 +381,7 @@ where
         Self {
             is_bottom: self.is_bottom.clone(),
             variables: self.variables.clone(),
+            relations: self.relations.clone(),
         }
     }
 }
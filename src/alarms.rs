@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::ast::Op;
+use crate::lexer::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AlarmKind {
+    DivisionByZero,
+    Overflow,
+    Assertion,
+    Uninitialised,
+    OutOfBounds,
+    InvalidShift,
+}
+
+impl AlarmKind {
+    pub fn rule_id(&self) -> &'static str {
+        match self {
+            AlarmKind::DivisionByZero => "division-by-zero",
+            AlarmKind::Overflow => "overflow",
+            AlarmKind::Assertion => "assertion",
+            AlarmKind::Uninitialised => "uninitialised-read",
+            AlarmKind::OutOfBounds => "out-of-bounds",
+            AlarmKind::InvalidShift => "invalid-shift",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            AlarmKind::DivisionByZero => "division by zero",
+            AlarmKind::Overflow => "integer overflow",
+            AlarmKind::Assertion => "assertion failure",
+            AlarmKind::Uninitialised => "read of a possibly uninitialised variable",
+            AlarmKind::OutOfBounds => "array index out of bounds",
+            AlarmKind::InvalidShift => "shift amount outside [0, 63]",
+        }
+    }
+}
+
+// Definite: every execution reaching the point fails, possible: some execution may fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Definite,
+    Possible,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Definite => write!(f, "definite"),
+            Severity::Possible => write!(f, "possible"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Alarm {
+    pub kind: AlarmKind,
+    pub severity: Severity,
+    pub span: Span,
+    pub expression: String,
+    pub state: AbstractState<AbstractInterval>,
+}
+
+impl fmt::Display for Alarm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} in {} at {}",
+            self.severity,
+            self.kind.rule_id(),
+            self.expression,
+            self.span
+        )
+    }
+}
+
+// Raised alarms by site
+pub type Snapshot = HashMap<(Span, AlarmKind, String), Alarm>;
+
+lazy_static! {
+    // One entry per alarm site, the last visit of the site decides whether it is raised
+    static ref ALARMS: Mutex<Snapshot> = Mutex::new(HashMap::new());
+}
+
+pub fn snapshot() -> Snapshot {
+    ALARMS.lock().expect("failed to lock alarms").clone()
+}
+
+pub fn restore(snapshot: Snapshot) {
+    *ALARMS.lock().expect("failed to lock alarms") = snapshot;
+}
+
+// Alarms of runs over different partitions of the same states: an alarm raised by any run is
+// raised, and it is definite only when every run found it definite
+pub fn merge(runs: Vec<Snapshot>) -> Snapshot {
+    let count = runs.len();
+    let mut merged: Snapshot = HashMap::new();
+    let mut raised_by: HashMap<(Span, AlarmKind, String), usize> = HashMap::new();
+    for run in runs {
+        for (key, alarm) in run {
+            *raised_by.entry(key.clone()).or_insert(0) += 1;
+            match merged.get_mut(&key) {
+                Some(joined) => {
+                    if alarm.severity == Severity::Possible {
+                        joined.severity = Severity::Possible;
+                    }
+                    joined.state = joined.state.state_lub(&alarm.state);
+                }
+                None => {
+                    merged.insert(key, alarm);
+                }
+            }
+        }
+    }
+    for (key, alarm) in merged.iter_mut() {
+        if raised_by[key] < count {
+            alarm.severity = Severity::Possible;
+        }
+    }
+    merged
+}
+
+// Raises (or withdraws, when severity is None or the state is unreachable) the alarm of a site
+pub fn report(
+    span: Span,
+    kind: AlarmKind,
+    expression: String,
+    severity: Option<Severity>,
+    state: &AbstractState<AbstractInterval>,
+) {
+    let mut alarms = ALARMS.lock().expect("failed to lock alarms");
+    let key = (span, kind, expression.clone());
+    match severity {
+        Some(severity) if !state.is_bottom() => {
+            alarms.insert(
+                key,
+                Alarm {
+                    kind,
+                    severity,
+                    span,
+                    expression,
+                    state: state.clone(),
+                },
+            );
+        }
+        _ => {
+            alarms.remove(&key);
+        }
+    }
+}
+
+// Raised alarms in source order
+pub fn all() -> Vec<Alarm> {
+    let mut alarms: Vec<Alarm> = ALARMS
+        .lock()
+        .expect("failed to lock alarms")
+        .values()
+        .cloned()
+        .collect();
+    alarms.sort_by(|a, b| {
+        (a.span, a.kind, &a.expression).cmp(&(b.span, b.kind, &b.expression))
+    });
+    alarms
+}
+
+// Divisor containing zero: possible alarm, divisor equal to zero: definite alarm
+pub fn division_by_zero(divisor: AbstractInterval) -> Option<Severity> {
+    match divisor {
+        AbstractInterval::Bottom => None,
+        AbstractInterval::Top => Some(Severity::Possible),
+        AbstractInterval::Bounded { lower: 0, upper: 0 } => Some(Severity::Definite),
+        AbstractInterval::Bounded { lower, upper } if lower <= 0 && upper >= 0 => {
+            Some(Severity::Possible)
+        }
+        _ => None,
+    }
+}
+
+// Shift amount partly outside [0, 63]: possible alarm, entirely outside: definite alarm
+pub fn invalid_shift(amount: AbstractInterval) -> Option<Severity> {
+    let (lower, upper) = amount.machine()?;
+    if upper < 0 || lower > 63 {
+        Some(Severity::Definite)
+    } else if lower < 0 || upper > 63 {
+        Some(Severity::Possible)
+    } else {
+        None
+    }
+}
+
+// Checks whether the exact result of `lhs op rhs` (rhs is ignored for unary minus) leaves i64
+pub fn overflow(op: Op, lhs: AbstractInterval, rhs: AbstractInterval) -> Option<Severity> {
+    let (l1, u1) = lhs.machine()?;
+    let (lower, upper) = match op {
+        Op::Uminus => (-u1, -l1),
+        _ => {
+            let (l2, u2) = rhs.machine()?;
+            match op {
+                Op::Add => (l1 + l2, u1 + u2),
+                Op::Sub => (l1 - u2, u1 - l2),
+                Op::Mul => {
+                    let products = [l1 * l2, l1 * u2, u1 * l2, u1 * u2];
+                    (
+                        *products.iter().min().unwrap(),
+                        *products.iter().max().unwrap(),
+                    )
+                }
+                // lhs << rhs is lhs * 2^rhs, the invalid amounts raise their own alarm
+                Op::Shl => {
+                    let amounts = rhs.intersect(&AbstractInterval::new(0, 63));
+                    let (l2, u2) = amounts.machine()?;
+                    let (least, most) = (1i128 << l2, 1i128 << u2);
+                    (
+                        (l1 * least).min(l1 * most),
+                        (u1 * least).max(u1 * most),
+                    )
+                }
+                // the only overflowing division is i64::MIN / -1
+                Op::Div => {
+                    if l1 == i64::MIN as i128 && l2 <= -1 && u2 >= -1 {
+                        if u1 == l1 && l2 == u2 {
+                            return Some(Severity::Definite);
+                        }
+                        return Some(Severity::Possible);
+                    }
+                    return None;
+                }
+                // remainders, bitwise operations and right shifts stay within their operands
+                _ => return None,
+            }
+        }
+    };
+    if lower > i64::MAX as i128 || upper < i64::MIN as i128 {
+        Some(Severity::Definite)
+    } else if lower < i64::MIN as i128 || upper > i64::MAX as i128 {
+        Some(Severity::Possible)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,88 @@
+use crate::lexer::{Span, Token, TokenType};
+use crate::syntax_error;
+
+// In-program analysis annotation `@name(arg, ...)` with integer arguments, e.g. `@thresholds(0, 100)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub name: String,
+    pub args: Vec<i64>,
+    pub span: Span,   // position of the '@'
+    pub target: Span, // first token after the annotation, the statement it refers to
+}
+
+// Removes the annotations from the tokens of the program, the spans of the remaining tokens are
+// those of the source
+pub fn extract(tokens: Vec<Token>) -> (Vec<Token>, Vec<Annotation>) {
+    let mut remaining = Vec::with_capacity(tokens.len());
+    let mut annotations = Vec::new();
+    let mut pending: Vec<Annotation> = Vec::new();
+    let mut tokens = tokens.into_iter();
+    while let Some(token) = tokens.next() {
+        if token.token_ty == TokenType::At {
+            pending.push(parse(&mut tokens, token.span));
+            continue;
+        }
+        for mut annotation in pending.drain(..) {
+            annotation.target = token.span;
+            annotations.push(annotation);
+        }
+        remaining.push(token);
+    }
+    // annotations at the end of the program refer to nothing in particular
+    annotations.extend(pending);
+    (remaining, annotations)
+}
+
+// `name(arg, ...)` after the '@' at `span`, the arguments are integer literals
+fn parse(tokens: &mut impl Iterator<Item = Token>, span: Span) -> Annotation {
+    let name = match tokens.next() {
+        Some(Token {
+            token_ty: TokenType::Identifier(name),
+            ..
+        }) => name,
+        other => malformed(span, other),
+    };
+    match tokens.next() {
+        Some(token) if token.token_ty == TokenType::Bra => {}
+        other => malformed(span, other),
+    }
+    let mut args = Vec::new();
+    loop {
+        let token = tokens.next();
+        match token.as_ref().map(|token| &token.token_ty) {
+            Some(TokenType::Ket) if args.is_empty() => break,
+            Some(TokenType::Number(value)) => args.push(*value),
+            Some(TokenType::Minus) => match tokens.next() {
+                Some(Token {
+                    token_ty: TokenType::Number(value),
+                    ..
+                }) => args.push(-value),
+                other => malformed(span, other),
+            },
+            _ => malformed(span, token),
+        }
+        let token = tokens.next();
+        match token.as_ref().map(|token| &token.token_ty) {
+            Some(TokenType::Comma) => {}
+            Some(TokenType::Ket) => break,
+            _ => malformed(span, token),
+        }
+    }
+    Annotation {
+        name,
+        args,
+        span,
+        target: Span::default(),
+    }
+}
+
+fn malformed(span: Span, found: Option<Token>) -> ! {
+    let found = match found {
+        Some(token) => format!("{:?} at {}", token.value, token.span),
+        None => "the end of the program".to_string(),
+    };
+    syntax_error(format!(
+        "annotation at {} is not @name(n, ...) with integer arguments, found {}",
+        span, found
+    ))
+}
@@ -0,0 +1,105 @@
+// Fixed-size integer arrays: declaration `a[10]`, read `a[i]`, write `a[i] := e`
+//
+// Arrays are declared once, with a constant size, and their elements start at 0. The concrete
+// state holds every element under its own name (a[0], a[1], ...). The abstract state smashes the
+// elements of an array into one summary variable named as the array: reads give its interval,
+// writes join into it (weak update) unless the array has a single element. Every access checks
+// the index interval against [0, size - 1].
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::alarms::Severity;
+use crate::ast::arithmetic::ArithmeticExpression;
+use crate::lexer::Span;
+use crate::{runtime_error, syntax_error};
+
+lazy_static! {
+    static ref SIZES: Mutex<BTreeMap<String, i64>> = Mutex::new(BTreeMap::new());
+}
+
+pub fn declare(name: &str, size: i64, span: Span) {
+    if size <= 0 {
+        syntax_error(format!(
+            "array {} declared at {} with size {}, sizes are positive",
+            name, span, size
+        ));
+    }
+    let mut sizes = SIZES.lock().expect("failed to lock array sizes");
+    if sizes.insert(name.to_string(), size).is_some() {
+        syntax_error(format!("array {} declared again at {}", name, span));
+    }
+}
+
+pub fn declared(name: &str) -> bool {
+    SIZES
+        .lock()
+        .expect("failed to lock array sizes")
+        .contains_key(name)
+}
+
+pub fn size(name: &str) -> i64 {
+    SIZES
+        .lock()
+        .expect("failed to lock array sizes")
+        .get(name)
+        .copied()
+        .unwrap_or_else(|| unreachable!("array {} used without being declared", name))
+}
+
+// Name of an element in the concrete state
+pub fn element(name: &str, index: i64) -> String {
+    format!("{}[{}]", name, index)
+}
+
+// Concrete access: the index must be within the array
+pub fn check_index(name: &str, index: i64) {
+    let size = size(name);
+    if index < 0 || index >= size {
+        runtime_error(format!(
+            "index {} out of the bounds [0, {}] of array {}",
+            index,
+            size - 1,
+            name
+        ));
+    }
+}
+
+// Indexes of the interval that are within the array, ⊥ when none is
+pub fn within(name: &str, index: AbstractInterval) -> AbstractInterval {
+    index.intersect(&AbstractInterval::new(0, size(name) - 1))
+}
+
+// States of `state` where `index` is within the array, refining the variables of the index
+pub fn refine_index(
+    state: &AbstractState<AbstractInterval>,
+    name: &str,
+    index: &dyn ArithmeticExpression<Q = AbstractInterval>,
+) -> AbstractState<AbstractInterval> {
+    let mut var_leaves = HashMap::new();
+    let tree = index.to_ast(&mut state.clone(), &mut var_leaves);
+    if !tree.backward_analysis(within(name, tree.get_value()), &mut var_leaves) {
+        return state.bottom();
+    }
+    let mut refined = state.clone();
+    for (variable, value) in var_leaves {
+        refined = refined.update_interval(&variable, value);
+    }
+    refined
+}
+
+// Index interval disjoint from the array: definite alarm, not included in it: possible alarm
+pub fn out_of_bounds(name: &str, index: AbstractInterval) -> Option<Severity> {
+    if index.is_bottom() {
+        return None;
+    }
+    if within(name, index).is_bottom() {
+        Some(Severity::Definite)
+    } else if within(name, index) != index {
+        Some(Severity::Possible)
+    } else {
+        None
+    }
+}
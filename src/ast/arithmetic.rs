@@ -1,8 +1,14 @@
 use crate::abstract_domain::{AbstractDomain, AbstractDomainOps};
 use crate::abstract_interval::AbstractInterval;
 use crate::abstract_state::AbstractState;
+use crate::alarms::{self, AlarmKind};
+use crate::arrays;
+use crate::ast::boolean::BooleanExpression;
 use crate::ast::State;
-use crate::{M, N};
+use crate::lexer::Span;
+use crate::procedures;
+use crate::random;
+use crate::{runtime_error, M, N};
 use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::Debug;
@@ -24,6 +30,49 @@ pub trait ArithmeticExpression: Debug {
         abs_state: &mut AbstractState<Self::Q>,
         var_leaves: &mut HashMap<String, AbstractInterval>,
     ) -> Node;
+    // Reports the alarms of the expression evaluated in abs_state at the statement `span`,
+    // numerals and variables cannot fail
+    fn check_alarms(&self, _abs_state: &mut AbstractState<Self::Q>, _span: Span) {}
+}
+
+// Checks both operands (the left one's side effects happen first) and then the operation itself
+fn check_binary(
+    op: Op,
+    text: String,
+    left: &dyn ArithmeticExpression<Q = AbstractInterval>,
+    right: &dyn ArithmeticExpression<Q = AbstractInterval>,
+    abs_state: &mut AbstractState<AbstractInterval>,
+    span: Span,
+) {
+    left.check_alarms(&mut abs_state.clone(), span);
+    let lhs = left.abs_evaluate(abs_state);
+    right.check_alarms(&mut abs_state.clone(), span);
+    let rhs = right.abs_evaluate(abs_state);
+    if let Op::Div | Op::Mod = op {
+        alarms::report(
+            span,
+            AlarmKind::DivisionByZero,
+            text.clone(),
+            alarms::division_by_zero(rhs),
+            abs_state,
+        );
+    }
+    if let Op::Shl | Op::Shr = op {
+        alarms::report(
+            span,
+            AlarmKind::InvalidShift,
+            text.clone(),
+            alarms::invalid_shift(rhs),
+            abs_state,
+        );
+    }
+    alarms::report(
+        span,
+        AlarmKind::Overflow,
+        text,
+        alarms::overflow(op, lhs, rhs),
+        abs_state,
+    );
 }
 
 #[derive(Debug)]
@@ -83,9 +132,13 @@ impl ArithmeticExpression for Variable {
         Node::VarLeaf(self.value.clone(), value)
     }
     fn evaluate(&self, state: &mut State) -> i64 {
-        *state
-            .get(&self.value)
-            .expect("Variable  not found in the state!")
+        match state.get(&self.value) {
+            Some(value) => *value,
+            None => runtime_error(format!(
+                "variable {} read before being assigned while applying denotational semantics",
+                self.value
+            )),
+        }
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -106,10 +159,13 @@ impl ArithmeticExpression for Variable {
     fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
         // println!("STATE SITUATION {}", abs_state);
         // println!("SEARCH FOR {}", self.value);
-        let res = *abs_state
-            .variables
-            .get(&self.value)
-            .expect("Variable not found in the abstract state!");
+        // an uninitialised variable can hold any value, the read is reported by the
+        // initialisation checker
+        let Some(res) = abs_state.variables.get(&self.value) else {
+            let m = *M.lock().expect("failed to lock m mutex");
+            let n = *N.lock().expect("failed to lock n mutex");
+            return AbstractInterval::new(m, n);
+        };
         // println!("VALUE {}", res.value);
         return res.value;
     }
@@ -123,6 +179,318 @@ impl ArithmeticExpression for Variable {
     }
 }
 
+// Element of an array, `array` names its summary in the abstract state
+#[derive(Debug)]
+pub struct ArrayRead {
+    pub array: Variable,
+    pub index: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+}
+
+impl ArithmeticExpression for ArrayRead {
+    type Q = AbstractInterval;
+    fn to_ast(
+        &self,
+        abs_state: &mut AbstractState<Self::Q>,
+        _var_leaves: &mut HashMap<String, AbstractInterval>,
+    ) -> Node {
+        // refining one element says nothing of the summary of the others
+        Node::ConstantLeaf(self.abs_evaluate(abs_state))
+    }
+    fn evaluate(&self, state: &mut State) -> i64 {
+        let index = self.index.evaluate(state);
+        arrays::check_index(&self.array.value, index);
+        match state.get(&arrays::element(&self.array.value, index)) {
+            Some(value) => *value,
+            None => runtime_error(format!(
+                "array {} read before its declaration while applying denotational semantics",
+                self.array.value
+            )),
+        }
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn ArithmeticExpression<Q = Self::Q>> {
+        Box::new(ArrayRead {
+            array: Variable {
+                value: self.array.value.clone(),
+            },
+            index: self.index.clone_box(),
+        })
+    }
+    fn as_variable(&self) -> Option<&Variable> {
+        None
+    }
+    fn to_string(&self) -> String {
+        format!("{}[{}]", self.array.value, self.index.to_string())
+    }
+    fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
+        // no element is read when the index is out of the array, the alarm is raised by
+        // check_alarms
+        let index = self.index.abs_evaluate(abs_state);
+        if arrays::within(&self.array.value, index).is_bottom() {
+            return AbstractInterval::Bottom;
+        }
+        self.array.abs_evaluate(abs_state)
+    }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        self.index.check_alarms(&mut abs_state.clone(), span);
+        let index = self.index.abs_evaluate(abs_state);
+        alarms::report(
+            span,
+            AlarmKind::OutOfBounds,
+            self.to_string(),
+            arrays::out_of_bounds(&self.array.value, index),
+            abs_state,
+        );
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = vec![&self.array];
+        vars.extend(self.index.extract_variables());
+        vars
+    }
+}
+
+// Call of a procedure, the arguments are evaluated from left to right
+#[derive(Debug)]
+pub struct Call {
+    pub procedure: String,
+    pub arguments: Vec<Box<dyn ArithmeticExpression<Q = AbstractInterval>>>,
+}
+
+impl ArithmeticExpression for Call {
+    type Q = AbstractInterval;
+    fn to_ast(
+        &self,
+        abs_state: &mut AbstractState<Self::Q>,
+        _var_leaves: &mut HashMap<String, AbstractInterval>,
+    ) -> Node {
+        // the result says nothing of the arguments
+        Node::ConstantLeaf(self.abs_evaluate(abs_state))
+    }
+    fn evaluate(&self, state: &mut State) -> i64 {
+        let arguments: Vec<i64> = self
+            .arguments
+            .iter()
+            .map(|argument| argument.evaluate(state))
+            .collect();
+        procedures::call(&self.procedure, &arguments)
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn ArithmeticExpression<Q = Self::Q>> {
+        Box::new(Call {
+            procedure: self.procedure.clone(),
+            arguments: self
+                .arguments
+                .iter()
+                .map(|argument| argument.clone_box())
+                .collect(),
+        })
+    }
+    fn as_variable(&self) -> Option<&Variable> {
+        None
+    }
+    fn to_string(&self) -> String {
+        let arguments: Vec<String> = self
+            .arguments
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect();
+        format!("{}({})", self.procedure, arguments.join(", "))
+    }
+    fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
+        let arguments = self
+            .arguments
+            .iter()
+            .map(|argument| argument.abs_evaluate(abs_state))
+            .collect();
+        procedures::abs_call(&self.procedure, arguments)
+    }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        // the alarms of the body are raised at its statements while the call is analysed
+        for argument in &self.arguments {
+            argument.check_alarms(&mut abs_state.clone(), span);
+            argument.abs_evaluate(abs_state);
+        }
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        self.arguments
+            .iter()
+            .flat_map(|argument| argument.extract_variables())
+            .collect()
+    }
+}
+
+// Least and greatest value of `random(a, b)`
+pub type Range = (
+    Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+);
+
+// Nondeterministic value: `?` is any value, `random(a, b)` one from a to b
+#[derive(Debug)]
+pub struct Random {
+    pub range: Option<Range>,
+}
+
+impl ArithmeticExpression for Random {
+    type Q = AbstractInterval;
+    fn to_ast(
+        &self,
+        abs_state: &mut AbstractState<Self::Q>,
+        _var_leaves: &mut HashMap<String, AbstractInterval>,
+    ) -> Node {
+        // the value drawn says nothing of the bounds
+        Node::ConstantLeaf(self.abs_evaluate(abs_state))
+    }
+    fn evaluate(&self, state: &mut State) -> i64 {
+        match &self.range {
+            None => random::any(&self.to_string()),
+            Some((lower, upper)) => {
+                let lower = lower.evaluate(state);
+                let upper = upper.evaluate(state);
+                random::draw(lower, upper, &self.to_string())
+            }
+        }
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn ArithmeticExpression<Q = Self::Q>> {
+        Box::new(Random {
+            range: self
+                .range
+                .as_ref()
+                .map(|(lower, upper)| (lower.clone_box(), upper.clone_box())),
+        })
+    }
+    fn as_variable(&self) -> Option<&Variable> {
+        None
+    }
+    fn to_string(&self) -> String {
+        match &self.range {
+            None => "?".to_string(),
+            Some((lower, upper)) => {
+                format!("random({}, {})", lower.to_string(), upper.to_string())
+            }
+        }
+    }
+    fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
+        let m = *M.lock().expect("failed to lock m mutex");
+        let n = *N.lock().expect("failed to lock n mutex");
+        let Some((lower, upper)) = &self.range else {
+            return AbstractInterval::new(m, n);
+        };
+        // an empty range has no value, the concrete run stops there
+        let least = match lower.abs_evaluate(abs_state) {
+            AbstractInterval::Bottom => return AbstractInterval::Bottom,
+            AbstractInterval::Top => m,
+            AbstractInterval::Bounded { lower, .. } => lower,
+        };
+        let greatest = match upper.abs_evaluate(abs_state) {
+            AbstractInterval::Bottom => return AbstractInterval::Bottom,
+            AbstractInterval::Top => n,
+            AbstractInterval::Bounded { upper, .. } => upper,
+        };
+        AbstractInterval::new(least, greatest)
+    }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        if let Some((lower, upper)) = &self.range {
+            lower.check_alarms(&mut abs_state.clone(), span);
+            lower.abs_evaluate(abs_state);
+            upper.check_alarms(&mut abs_state.clone(), span);
+        }
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        match &self.range {
+            None => Vec::new(),
+            Some((lower, upper)) => {
+                let mut vars = lower.extract_variables();
+                vars.extend(upper.extract_variables());
+                vars
+            }
+        }
+    }
+}
+
+// Conditional value `b ? e1 : e2`: e1 where b holds, e2 where it fails. Only the branch selected
+// runs, the abstract value joins both branches evaluated where the guard selects them
+#[derive(Debug)]
+pub struct Conditional {
+    pub guard: Box<dyn BooleanExpression<Q = AbstractInterval>>,
+    pub then: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    pub otherwise: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+}
+
+impl ArithmeticExpression for Conditional {
+    type Q = AbstractInterval;
+    fn to_ast(
+        &self,
+        abs_state: &mut AbstractState<Self::Q>,
+        _var_leaves: &mut HashMap<String, AbstractInterval>,
+    ) -> Node {
+        // the value joins both branches, it says nothing of either
+        Node::ConstantLeaf(self.abs_evaluate(abs_state))
+    }
+    fn evaluate(&self, state: &mut State) -> i64 {
+        if self.guard.evaluate(state) {
+            self.then.evaluate(state)
+        } else {
+            self.otherwise.evaluate(state)
+        }
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn ArithmeticExpression<Q = Self::Q>> {
+        Box::new(Conditional {
+            guard: self.guard.clone_box(),
+            then: self.then.clone_box(),
+            otherwise: self.otherwise.clone_box(),
+        })
+    }
+    fn as_variable(&self) -> Option<&Variable> {
+        None
+    }
+    fn to_string(&self) -> String {
+        format!(
+            "({} ? {} : {})",
+            self.guard.to_string(),
+            self.then.to_string(),
+            self.otherwise.to_string()
+        )
+    }
+    fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
+        // each branch in the cases where the guard selects it, side effects joined as well
+        let mut value = AbstractInterval::Bottom;
+        let mut after = abs_state.bottom();
+        for (branch, negated) in [(&self.then, false), (&self.otherwise, true)] {
+            for mut case in self.guard.abs_cases(&mut abs_state.clone(), negated) {
+                value = value.int_lub(&branch.abs_evaluate(&mut case));
+                after = after.state_lub(&case);
+            }
+        }
+        *abs_state = after;
+        value
+    }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        self.guard.check_alarms(&mut abs_state.clone(), span);
+        for (branch, negated) in [(&self.then, false), (&self.otherwise, true)] {
+            for mut case in self.guard.abs_cases(&mut abs_state.clone(), negated) {
+                branch.check_alarms(&mut case, span);
+            }
+        }
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = self.guard.extract_variables();
+        vars.extend(self.then.extract_variables());
+        vars.extend(self.otherwise.extract_variables());
+        vars
+    }
+}
+
 #[derive(Debug)]
 pub struct Add {
     pub left: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
@@ -167,6 +535,16 @@ impl ArithmeticExpression for Add {
         // println!("add result {}", result);
         result
     }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        check_binary(
+            Op::Add,
+            self.to_string(),
+            self.left.as_ref(),
+            self.right.as_ref(),
+            abs_state,
+            span,
+        );
+    }
     fn extract_variables(&self) -> Vec<&Variable> {
         let mut vars = Vec::new();
         // Se il lato sinistro è una variabile o contiene variabili
@@ -228,6 +606,16 @@ impl ArithmeticExpression for Product {
     fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
         self.left.abs_evaluate(abs_state) * self.right.abs_evaluate(abs_state)
     }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        check_binary(
+            Op::Mul,
+            self.to_string(),
+            self.left.as_ref(),
+            self.right.as_ref(),
+            abs_state,
+            span,
+        );
+    }
     fn extract_variables(&self) -> Vec<&Variable> {
         let mut vars = Vec::new();
         // Se il lato sinistro è una variabile o contiene variabili
@@ -293,6 +681,16 @@ impl ArithmeticExpression for Minus {
         // println!("{} - {} sub result {}",lhs, rhs, result);
         result
     }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        check_binary(
+            Op::Sub,
+            self.to_string(),
+            self.left.as_ref(),
+            self.right.as_ref(),
+            abs_state,
+            span,
+        );
+    }
     fn extract_variables(&self) -> Vec<&Variable> {
         let mut vars = Vec::new();
         // Se il lato sinistro è una variabile o contiene variabili
@@ -324,7 +722,7 @@ impl ArithmeticExpression for Uminus {
         var_leaves: &mut HashMap<String, AbstractInterval>,
     ) -> Node {
         let sub_tree = Box::new(self.right.to_ast(abs_state, var_leaves));
-        Node::UInternal(Op::Uminus, -self.abs_evaluate(abs_state), sub_tree)
+        Node::UInternal(Op::Uminus, self.abs_evaluate(abs_state), sub_tree)
     }
     fn as_any(&self) -> &dyn Any {
         self
@@ -346,6 +744,17 @@ impl ArithmeticExpression for Uminus {
     fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
         -self.right.abs_evaluate(abs_state)
     }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        self.right.check_alarms(&mut abs_state.clone(), span);
+        let value = self.right.abs_evaluate(abs_state);
+        alarms::report(
+            span,
+            AlarmKind::Overflow,
+            self.to_string(),
+            alarms::overflow(Op::Uminus, value, value),
+            abs_state,
+        );
+    }
     fn extract_variables(&self) -> Vec<&Variable> {
         let mut vars = Vec::new();
         // Se il lato sinistro è una variabile o contiene variabili
@@ -403,12 +812,20 @@ impl ArithmeticExpression for Divide {
         format!("({} / {})", self.left.to_string(), self.right.to_string())
     }
     fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
-        let zero_int = AbstractInterval::new(0, 0);
-        if self.right.abs_evaluate(abs_state) != zero_int {
-            self.left.abs_evaluate(abs_state) / self.right.abs_evaluate(abs_state)
-        } else {
-            unreachable!("**RUNTIME ERROR, division by zero found while interpreting**")
-        }
+        // a division by [0, 0] has no result, the alarm is raised by check_alarms
+        let lhs = self.left.abs_evaluate(abs_state);
+        let rhs = self.right.abs_evaluate(abs_state);
+        lhs / rhs
+    }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        check_binary(
+            Op::Div,
+            self.to_string(),
+            self.left.as_ref(),
+            self.right.as_ref(),
+            abs_state,
+            span,
+        );
     }
     fn extract_variables(&self) -> Vec<&Variable> {
         let mut vars = Vec::new();
@@ -429,6 +846,435 @@ impl ArithmeticExpression for Divide {
     }
 }
 
+// Amount of a concrete shift, the ones outside [0, 63] are a runtime error
+fn shift_amount(amount: i64, expression: &dyn ArithmeticExpression<Q = AbstractInterval>) -> u32 {
+    if !(0..=63).contains(&amount) {
+        runtime_error(format!(
+            "shift amount {} outside [0, 63] in {}",
+            amount,
+            expression.to_string()
+        ));
+    }
+    amount as u32
+}
+
+#[derive(Debug)]
+pub struct Modulo {
+    pub left: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    pub right: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+}
+impl ArithmeticExpression for Modulo {
+    type Q = AbstractInterval;
+    fn to_ast(
+        &self,
+        abs_state: &mut AbstractState<Self::Q>,
+        var_leaves: &mut HashMap<String, AbstractInterval>,
+    ) -> Node {
+        Node::Internal(
+            Op::Mod,
+            self.abs_evaluate(abs_state),
+            Box::new(self.left.to_ast(abs_state, var_leaves)),
+            Box::new(self.right.to_ast(abs_state, var_leaves)),
+        )
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn ArithmeticExpression<Q = Self::Q>> {
+        Box::new(Modulo {
+            left: self.left.clone_box(),
+            right: self.right.clone_box(),
+        })
+    }
+    fn as_variable(&self) -> Option<&Variable> {
+        None
+    }
+    fn evaluate(&self, state: &mut State) -> i64 {
+        let lhs = self.left.evaluate(state);
+        let rhs = self.right.evaluate(state);
+        if rhs == 0 {
+            runtime_error(format!("division by zero in {}", self.to_string()));
+        }
+        // the remainder of i64::MIN by -1 is 0, not an overflow
+        lhs.wrapping_rem(rhs)
+    }
+    fn to_string(&self) -> String {
+        format!("({} % {})", self.left.to_string(), self.right.to_string())
+    }
+    fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
+        // a remainder by [0, 0] has no result, the alarm is raised by check_alarms
+        let lhs = self.left.abs_evaluate(abs_state);
+        let rhs = self.right.abs_evaluate(abs_state);
+        lhs % rhs
+    }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        check_binary(
+            Op::Mod,
+            self.to_string(),
+            self.left.as_ref(),
+            self.right.as_ref(),
+            abs_state,
+            span,
+        );
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = Vec::new();
+        if let Some(v) = self.left.as_variable() {
+            vars.push(v);
+        } else {
+            vars.extend(self.left.extract_variables());
+        }
+        if let Some(v) = self.right.as_variable() {
+            vars.push(v);
+        } else {
+            vars.extend(self.right.extract_variables());
+        }
+        vars
+    }
+}
+
+#[derive(Debug)]
+pub struct BitwiseAnd {
+    pub left: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    pub right: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+}
+impl ArithmeticExpression for BitwiseAnd {
+    type Q = AbstractInterval;
+    fn to_ast(
+        &self,
+        abs_state: &mut AbstractState<Self::Q>,
+        var_leaves: &mut HashMap<String, AbstractInterval>,
+    ) -> Node {
+        Node::Internal(
+            Op::BitAnd,
+            self.abs_evaluate(abs_state),
+            Box::new(self.left.to_ast(abs_state, var_leaves)),
+            Box::new(self.right.to_ast(abs_state, var_leaves)),
+        )
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn ArithmeticExpression<Q = Self::Q>> {
+        Box::new(BitwiseAnd {
+            left: self.left.clone_box(),
+            right: self.right.clone_box(),
+        })
+    }
+    fn as_variable(&self) -> Option<&Variable> {
+        None
+    }
+    fn evaluate(&self, state: &mut State) -> i64 {
+        self.left.evaluate(state) & self.right.evaluate(state)
+    }
+    fn to_string(&self) -> String {
+        format!("({} & {})", self.left.to_string(), self.right.to_string())
+    }
+    fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
+        self.left.abs_evaluate(abs_state) & self.right.abs_evaluate(abs_state)
+    }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        check_binary(
+            Op::BitAnd,
+            self.to_string(),
+            self.left.as_ref(),
+            self.right.as_ref(),
+            abs_state,
+            span,
+        );
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = Vec::new();
+        if let Some(v) = self.left.as_variable() {
+            vars.push(v);
+        } else {
+            vars.extend(self.left.extract_variables());
+        }
+        if let Some(v) = self.right.as_variable() {
+            vars.push(v);
+        } else {
+            vars.extend(self.right.extract_variables());
+        }
+        vars
+    }
+}
+
+#[derive(Debug)]
+pub struct BitwiseOr {
+    pub left: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    pub right: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+}
+impl ArithmeticExpression for BitwiseOr {
+    type Q = AbstractInterval;
+    fn to_ast(
+        &self,
+        abs_state: &mut AbstractState<Self::Q>,
+        var_leaves: &mut HashMap<String, AbstractInterval>,
+    ) -> Node {
+        Node::Internal(
+            Op::BitOr,
+            self.abs_evaluate(abs_state),
+            Box::new(self.left.to_ast(abs_state, var_leaves)),
+            Box::new(self.right.to_ast(abs_state, var_leaves)),
+        )
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn ArithmeticExpression<Q = Self::Q>> {
+        Box::new(BitwiseOr {
+            left: self.left.clone_box(),
+            right: self.right.clone_box(),
+        })
+    }
+    fn as_variable(&self) -> Option<&Variable> {
+        None
+    }
+    fn evaluate(&self, state: &mut State) -> i64 {
+        self.left.evaluate(state) | self.right.evaluate(state)
+    }
+    fn to_string(&self) -> String {
+        format!("({} | {})", self.left.to_string(), self.right.to_string())
+    }
+    fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
+        self.left.abs_evaluate(abs_state) | self.right.abs_evaluate(abs_state)
+    }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        check_binary(
+            Op::BitOr,
+            self.to_string(),
+            self.left.as_ref(),
+            self.right.as_ref(),
+            abs_state,
+            span,
+        );
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = Vec::new();
+        if let Some(v) = self.left.as_variable() {
+            vars.push(v);
+        } else {
+            vars.extend(self.left.extract_variables());
+        }
+        if let Some(v) = self.right.as_variable() {
+            vars.push(v);
+        } else {
+            vars.extend(self.right.extract_variables());
+        }
+        vars
+    }
+}
+
+#[derive(Debug)]
+pub struct BitwiseXor {
+    pub left: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    pub right: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+}
+impl ArithmeticExpression for BitwiseXor {
+    type Q = AbstractInterval;
+    fn to_ast(
+        &self,
+        abs_state: &mut AbstractState<Self::Q>,
+        var_leaves: &mut HashMap<String, AbstractInterval>,
+    ) -> Node {
+        Node::Internal(
+            Op::BitXor,
+            self.abs_evaluate(abs_state),
+            Box::new(self.left.to_ast(abs_state, var_leaves)),
+            Box::new(self.right.to_ast(abs_state, var_leaves)),
+        )
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn ArithmeticExpression<Q = Self::Q>> {
+        Box::new(BitwiseXor {
+            left: self.left.clone_box(),
+            right: self.right.clone_box(),
+        })
+    }
+    fn as_variable(&self) -> Option<&Variable> {
+        None
+    }
+    fn evaluate(&self, state: &mut State) -> i64 {
+        self.left.evaluate(state) ^ self.right.evaluate(state)
+    }
+    fn to_string(&self) -> String {
+        format!("({} ^ {})", self.left.to_string(), self.right.to_string())
+    }
+    fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
+        self.left.abs_evaluate(abs_state) ^ self.right.abs_evaluate(abs_state)
+    }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        check_binary(
+            Op::BitXor,
+            self.to_string(),
+            self.left.as_ref(),
+            self.right.as_ref(),
+            abs_state,
+            span,
+        );
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = Vec::new();
+        if let Some(v) = self.left.as_variable() {
+            vars.push(v);
+        } else {
+            vars.extend(self.left.extract_variables());
+        }
+        if let Some(v) = self.right.as_variable() {
+            vars.push(v);
+        } else {
+            vars.extend(self.right.extract_variables());
+        }
+        vars
+    }
+}
+
+#[derive(Debug)]
+pub struct ShiftLeft {
+    pub left: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    pub right: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+}
+impl ArithmeticExpression for ShiftLeft {
+    type Q = AbstractInterval;
+    fn to_ast(
+        &self,
+        abs_state: &mut AbstractState<Self::Q>,
+        var_leaves: &mut HashMap<String, AbstractInterval>,
+    ) -> Node {
+        Node::Internal(
+            Op::Shl,
+            self.abs_evaluate(abs_state),
+            Box::new(self.left.to_ast(abs_state, var_leaves)),
+            Box::new(self.right.to_ast(abs_state, var_leaves)),
+        )
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn ArithmeticExpression<Q = Self::Q>> {
+        Box::new(ShiftLeft {
+            left: self.left.clone_box(),
+            right: self.right.clone_box(),
+        })
+    }
+    fn as_variable(&self) -> Option<&Variable> {
+        None
+    }
+    fn evaluate(&self, state: &mut State) -> i64 {
+        let lhs = self.left.evaluate(state);
+        let amount = shift_amount(self.right.evaluate(state), self);
+        let result = lhs << amount;
+        if result >> amount != lhs {
+            runtime_error(format!("overflow in {}", self.to_string()));
+        }
+        result
+    }
+    fn to_string(&self) -> String {
+        format!("({} << {})", self.left.to_string(), self.right.to_string())
+    }
+    fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
+        // invalid amounts and overflows have no result, the alarms are raised by check_alarms
+        let lhs = self.left.abs_evaluate(abs_state);
+        let rhs = self.right.abs_evaluate(abs_state);
+        lhs << rhs
+    }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        check_binary(
+            Op::Shl,
+            self.to_string(),
+            self.left.as_ref(),
+            self.right.as_ref(),
+            abs_state,
+            span,
+        );
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = Vec::new();
+        if let Some(v) = self.left.as_variable() {
+            vars.push(v);
+        } else {
+            vars.extend(self.left.extract_variables());
+        }
+        if let Some(v) = self.right.as_variable() {
+            vars.push(v);
+        } else {
+            vars.extend(self.right.extract_variables());
+        }
+        vars
+    }
+}
+
+#[derive(Debug)]
+pub struct ShiftRight {
+    pub left: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    pub right: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+}
+impl ArithmeticExpression for ShiftRight {
+    type Q = AbstractInterval;
+    fn to_ast(
+        &self,
+        abs_state: &mut AbstractState<Self::Q>,
+        var_leaves: &mut HashMap<String, AbstractInterval>,
+    ) -> Node {
+        Node::Internal(
+            Op::Shr,
+            self.abs_evaluate(abs_state),
+            Box::new(self.left.to_ast(abs_state, var_leaves)),
+            Box::new(self.right.to_ast(abs_state, var_leaves)),
+        )
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn clone_box(&self) -> Box<dyn ArithmeticExpression<Q = Self::Q>> {
+        Box::new(ShiftRight {
+            left: self.left.clone_box(),
+            right: self.right.clone_box(),
+        })
+    }
+    fn as_variable(&self) -> Option<&Variable> {
+        None
+    }
+    fn evaluate(&self, state: &mut State) -> i64 {
+        let lhs = self.left.evaluate(state);
+        lhs >> shift_amount(self.right.evaluate(state), self)
+    }
+    fn to_string(&self) -> String {
+        format!("({} >> {})", self.left.to_string(), self.right.to_string())
+    }
+    fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
+        let lhs = self.left.abs_evaluate(abs_state);
+        let rhs = self.right.abs_evaluate(abs_state);
+        lhs >> rhs
+    }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        check_binary(
+            Op::Shr,
+            self.to_string(),
+            self.left.as_ref(),
+            self.right.as_ref(),
+            abs_state,
+            span,
+        );
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = Vec::new();
+        if let Some(v) = self.left.as_variable() {
+            vars.push(v);
+        } else {
+            vars.extend(self.left.extract_variables());
+        }
+        if let Some(v) = self.right.as_variable() {
+            vars.push(v);
+        } else {
+            vars.extend(self.right.extract_variables());
+        }
+        vars
+    }
+}
+
 #[derive(Debug)]
 pub struct PlusPlus {
     pub var: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
@@ -475,6 +1321,7 @@ impl ArithmeticExpression for PlusPlus {
     fn abs_evaluate(&self, abs_state: &mut AbstractState<Self::Q>) -> AbstractInterval {
         let m = *M.lock().unwrap();
         let n = *N.lock().unwrap();
+        abs_state.forget(&self.var.to_string());
         let value = self.var.abs_evaluate(abs_state);
         match value {
             AbstractInterval::Bottom => AbstractInterval::Bottom,
@@ -507,6 +1354,16 @@ impl ArithmeticExpression for PlusPlus {
             }
         }
     }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        let value = self.var.abs_evaluate(abs_state);
+        alarms::report(
+            span,
+            AlarmKind::Overflow,
+            self.to_string(),
+            alarms::overflow(Op::Add, value, AbstractInterval::new(1, 1)),
+            abs_state,
+        );
+    }
     fn extract_variables(&self) -> Vec<&Variable> {
         let mut vars = Vec::new();
         // Se il lato sinistro è una variabile o contiene variabili
@@ -563,6 +1420,7 @@ impl ArithmeticExpression for MinusMinus {
         // println!("minus minus evaluation");
         let m = *M.lock().unwrap();
         let n = *N.lock().unwrap();
+        abs_state.forget(&self.var.to_string());
         let value = self.var.abs_evaluate(abs_state);
         match value {
             AbstractInterval::Bottom => AbstractInterval::Bottom,
@@ -575,11 +1433,8 @@ impl ArithmeticExpression for MinusMinus {
                         // println!("newlower in minus minus {}", newlower);
                         let new_interval = AbstractInterval::new(newlower, upper);
                         // print!("new interval in minus minus {}", new_interval);
-                        //let new_value = AbstractDomain::new(new_interval);
-                        // abs_state
-                        //     .variables
-                        //     .insert(self.var.to_string(), new_value);
-                        abs_state.update_interval(self.var.to_string().as_str(), new_interval);
+                        let new_value = AbstractDomain::new(new_interval);
+                        abs_state.variables.insert(self.var.to_string(), new_value);
                         new_interval
                     } else {
                         let new_int = AbstractInterval::Bounded { lower, upper: n };
@@ -598,6 +1453,16 @@ impl ArithmeticExpression for MinusMinus {
             }
         }
     }
+    fn check_alarms(&self, abs_state: &mut AbstractState<Self::Q>, span: Span) {
+        let value = self.var.abs_evaluate(abs_state);
+        alarms::report(
+            span,
+            AlarmKind::Overflow,
+            self.to_string(),
+            alarms::overflow(Op::Sub, value, AbstractInterval::new(1, 1)),
+            abs_state,
+        );
+    }
     fn extract_variables(&self) -> Vec<&Variable> {
         let mut vars = Vec::new();
         // Se il lato sinistro è una variabile o contiene variabili
@@ -1,11 +1,14 @@
 use crate::abstract_domain::AbstractDomainOps;
 use crate::ast::arithmetic::ArithmeticExpression;
 use crate::ast::State;
+use crate::booleans;
+use crate::lexer::Span;
+use crate::runtime_error;
 use crate::{abstract_interval::AbstractInterval, abstract_state::AbstractState};
 use std::collections::HashMap;
 use std::fmt::Debug;
 
-use super::arithmetic::{Add, Minus, Numeral};
+use super::arithmetic::{Add, Minus, Numeral, Variable};
 use crate::M;
 // use super::{BooleanAST, RelOp};
 
@@ -20,6 +23,75 @@ pub trait BooleanExpression: Debug {
     ) -> AbstractState<Self::Q>;
     fn to_string(&self) -> String;
     fn negate(&self) -> Box<dyn BooleanExpression<Q= Self::Q>>;
+    // Variables read by the guard, in evaluation order
+    fn extract_variables(&self) -> Vec<&Variable>;
+    // Sides (lower, upper) of the comparisons of the guard: upper - lower is positive while
+    // the comparison holds, a candidate ranking function of a loop running while it holds. The
+    // third element is the least value of upper - lower wherever the whole guard holds, if any.
+    fn ranking_sides(&self) -> Sides<'_>;
+    // Reports the alarms of the arithmetic operands evaluated in state at the statement `span`
+    fn check_alarms(&self, _state: &mut AbstractState<Self::Q>, _span: Span) {}
+    // The states abs_evaluate filters, as the cases it joins: x != 0 holds in [-5, -1] and in
+    // [1, 5] but not in all of [-5, 5]. The connectives keep the cases of their operands apart,
+    // any other guard is one case. No case is left where the guard never holds
+    fn abs_cases(
+        &self,
+        state: &mut AbstractState<Self::Q>,
+        flag: bool,
+    ) -> Vec<AbstractState<Self::Q>> {
+        let filtered = self.abs_evaluate(state, flag);
+        if filtered.is_bottom() {
+            Vec::new()
+        } else {
+            vec![filtered]
+        }
+    }
+    // The boolean variable, when the expression is one
+    fn as_variable(&self) -> Option<&Variable> {
+        None
+    }
+}
+
+// Pairs of comparison sides, see BooleanExpression::ranking_sides
+pub type Sides<'a> = Vec<(
+    &'a dyn ArithmeticExpression<Q = AbstractInterval>,
+    &'a dyn ArithmeticExpression<Q = AbstractInterval>,
+    Option<i64>,
+)>;
+
+// Text of an operand of a connective, in parentheses when one of `connectives` at its top level
+// would bind looser than the connective
+fn grouped(operand: &dyn BooleanExpression<Q = AbstractInterval>, connectives: &[&str]) -> String {
+    let text = operand.to_string();
+    let mut depth = 0;
+    let loose = text.char_indices().any(|(position, character)| {
+        match character {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        depth == 0
+            && connectives
+                .iter()
+                .any(|connective| text[position..].starts_with(connective))
+    });
+    if loose {
+        format!("({})", text)
+    } else {
+        text
+    }
+}
+
+// Both operands of a comparison are evaluated, the left one first
+fn check_operands(
+    left: &dyn ArithmeticExpression<Q = AbstractInterval>,
+    right: &dyn ArithmeticExpression<Q = AbstractInterval>,
+    state: &mut AbstractState<AbstractInterval>,
+    span: Span,
+) {
+    left.check_alarms(&mut state.clone(), span);
+    left.abs_evaluate(state);
+    right.check_alarms(&mut state.clone(), span);
 }
 
 #[derive(Debug)]
@@ -54,6 +126,12 @@ impl BooleanExpression for Boolean {
     fn to_string(&self) -> String {
         self.0.to_string()
     }
+    fn ranking_sides(&self) -> Sides<'_> {
+        Vec::new()
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        Vec::new()
+    }
     fn negate(&self) -> Box<dyn BooleanExpression<Q= Self::Q>> {
         if self.0 {
             return Box::new(Boolean(false))
@@ -64,12 +142,93 @@ impl BooleanExpression for Boolean {
     }
 }
 
+// Boolean variable, 1 (true) or 0 (false) in the state
+#[derive(Debug)]
+pub struct BoolVariable {
+    pub var: Variable,
+}
+
+impl BooleanExpression for BoolVariable {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn BooleanExpression<Q = Self::Q>> {
+        Box::new(BoolVariable {
+            var: Variable {
+                value: self.var.value.clone(),
+            },
+        })
+    }
+    fn evaluate(&self, state: &mut State) -> bool {
+        match state.get(&self.var.value) {
+            Some(value) => *value != 0,
+            None => runtime_error(format!(
+                "variable {} read before being assigned while applying denotational semantics",
+                self.var.value
+            )),
+        }
+    }
+    fn abs_evaluate(
+        &self,
+        state: &mut AbstractState<Self::Q>,
+        flag: bool,
+    ) -> AbstractState<Self::Q> {
+        // flag asks for the states where the variable is false
+        let value = booleans::value(!flag, flag);
+        let refined = state.clone().update_interval(&self.var.value, value);
+        if refined.is_bottom() {
+            return refined;
+        }
+        // the guard the variable was assigned holds exactly where the variable does
+        match state.relations.get(&self.var.value) {
+            Some(relation) => booleans::guard(*relation).abs_evaluate(&mut refined.clone(), flag),
+            None => refined,
+        }
+    }
+    fn to_string(&self) -> String {
+        self.var.value.clone()
+    }
+    fn ranking_sides(&self) -> Sides<'_> {
+        Vec::new()
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        vec![&self.var]
+    }
+    fn negate(&self) -> Box<dyn BooleanExpression<Q = Self::Q>> {
+        Box::new(Not {
+            expression: self.clone_box(),
+        })
+    }
+    fn as_variable(&self) -> Option<&Variable> {
+        Some(&self.var)
+    }
+}
+
 #[derive(Debug)]
 pub struct Equal {
     pub left: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
     pub right: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
 }
 
+impl Equal {
+    // (a - b <= 0) and (b - a <= 0)
+    fn canonical(&self) -> And {
+        let left = Box::new(LessEqual {
+            left: Box::new(Minus {
+                left: self.left.clone_box(),
+                right: self.right.clone_box(),
+            }),
+            right: Box::new(Numeral(0)),
+        });
+        let right = Box::new(LessEqual {
+            left: Box::new(Minus {
+                left: self.right.clone_box(),
+                right: self.left.clone_box(),
+            }),
+            right: Box::new(Numeral(0)),
+        });
+        And { left, right }
+    }
+}
+
 impl BooleanExpression for Equal {
     type Q = AbstractInterval;
     fn evaluate(&self, state: &mut State) -> bool {
@@ -91,28 +250,34 @@ impl BooleanExpression for Equal {
         if self.left.abs_evaluate(state).is_bottom() || self.right.abs_evaluate(state).is_bottom() {
             AbstractState::bottom(state)
         } else {
-            //(a - b <= 0) and (b - a <=0)
-            let left = Box::new(LessEqual {
-                left: Box::new(Minus {
-                    left: self.left.clone_box(),
-                    right: self.right.clone_box(),
-                }),
-                right: Box::new(Numeral(0)),
-            });
-            let right = Box::new(LessEqual {
-                left: Box::new(Minus {
-                    left: self.right.clone_box(),
-                    right: self.left.clone_box(),
-                }),
-                right: Box::new(Numeral(0)),
-            });
-            let canonical = Box::new(And { left, right });
-            canonical.abs_evaluate(state, flag)
+            self.canonical().abs_evaluate(state, flag)
+        }
+    }
+    fn abs_cases(
+        &self,
+        state: &mut AbstractState<Self::Q>,
+        flag: bool,
+    ) -> Vec<AbstractState<Self::Q>> {
+        if self.left.abs_evaluate(state).is_bottom() || self.right.abs_evaluate(state).is_bottom() {
+            Vec::new()
+        } else {
+            self.canonical().abs_cases(state, flag)
         }
     }
     fn to_string(&self) -> String {
         format!("{} = {}", self.left.to_string(), self.right.to_string())
     }
+    fn check_alarms(&self, state: &mut AbstractState<Self::Q>, span: Span) {
+        check_operands(self.left.as_ref(), self.right.as_ref(), state, span);
+    }
+    fn ranking_sides(&self) -> Sides<'_> {
+        Vec::new()
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = self.left.extract_variables();
+        vars.extend(self.right.extract_variables());
+        vars
+    }
     fn negate(&self) -> Box<dyn BooleanExpression<Q= Self::Q>> {
         return Box::new(NotEqual{left: self.left.clone_box(), right: self.right.clone_box()})
     }
@@ -123,25 +288,9 @@ pub struct NotEqual {
     pub right: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
 }
 
-impl BooleanExpression for NotEqual {
-    type Q = AbstractInterval;
-    fn clone_box(&self) -> Box<dyn BooleanExpression<Q = Self::Q>> {
-        Box::new(NotEqual {
-            left: self.left.clone_box(),
-            right: self.right.clone_box(),
-        })
-    }
-
-    fn evaluate(&self, state: &mut State) -> bool {
-        self.left.evaluate(state) != self.right.evaluate(state)
-    }
-
-    fn abs_evaluate(
-        &self,
-        state: &mut AbstractState<Self::Q>,
-        flag: bool,
-    ) -> AbstractState<Self::Q> {
-        // (b - a + 1 <=0) or (a - b + 1 <=0)
+impl NotEqual {
+    // (b - a + 1 <= 0) or (a - b + 1 <= 0)
+    fn canonical(&self) -> Or {
         let left = Box::new(LessEqual {
             left: Box::new(Add {
                 left: Box::new(Minus {
@@ -162,12 +311,54 @@ impl BooleanExpression for NotEqual {
             }),
             right: Box::new(Numeral(0)),
         });
-        let canonical = Box::new(Or { left, right });
-        canonical.abs_evaluate(state, flag)
+        Or { left, right }
+    }
+}
+
+impl BooleanExpression for NotEqual {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn BooleanExpression<Q = Self::Q>> {
+        Box::new(NotEqual {
+            left: self.left.clone_box(),
+            right: self.right.clone_box(),
+        })
+    }
+
+    fn evaluate(&self, state: &mut State) -> bool {
+        self.left.evaluate(state) != self.right.evaluate(state)
+    }
+
+    fn abs_evaluate(
+        &self,
+        state: &mut AbstractState<Self::Q>,
+        flag: bool,
+    ) -> AbstractState<Self::Q> {
+        self.canonical().abs_evaluate(state, flag)
+    }
+    fn abs_cases(
+        &self,
+        state: &mut AbstractState<Self::Q>,
+        flag: bool,
+    ) -> Vec<AbstractState<Self::Q>> {
+        self.canonical().abs_cases(state, flag)
     }
     fn to_string(&self) -> String {
         format!("{} != {}", self.left.to_string(), self.right.to_string())
     }
+    fn check_alarms(&self, state: &mut AbstractState<Self::Q>, span: Span) {
+        check_operands(self.left.as_ref(), self.right.as_ref(), state, span);
+    }
+    fn ranking_sides(&self) -> Sides<'_> {
+        vec![
+            (self.left.as_ref(), self.right.as_ref(), None),
+            (self.right.as_ref(), self.left.as_ref(), None),
+        ]
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = self.left.extract_variables();
+        vars.extend(self.right.extract_variables());
+        vars
+    }
     fn negate(&self) -> Box<dyn BooleanExpression<Q= Self::Q>> {
         return Box::new(Equal{left: self.left.clone_box(), right: self.right.clone_box()})
     }
@@ -223,6 +414,17 @@ impl BooleanExpression for GreatEqual {
     fn to_string(&self) -> String {
         format!("{} >= {}", self.left.to_string(), self.right.to_string())
     }
+    fn check_alarms(&self, state: &mut AbstractState<Self::Q>, span: Span) {
+        check_operands(self.left.as_ref(), self.right.as_ref(), state, span);
+    }
+    fn ranking_sides(&self) -> Sides<'_> {
+        vec![(self.right.as_ref(), self.left.as_ref(), Some(0))]
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = self.left.extract_variables();
+        vars.extend(self.right.extract_variables());
+        vars
+    }
     fn negate(&self) -> Box<dyn BooleanExpression<Q= Self::Q>> {
         return Box::new(Less{left: self.left.clone_box(), right: self.right.clone_box()})
     }
@@ -281,6 +483,17 @@ impl BooleanExpression for Great {
     fn to_string(&self) -> String {
         format!("{} > {}", self.left.to_string(), self.right.to_string())
     }
+    fn check_alarms(&self, state: &mut AbstractState<Self::Q>, span: Span) {
+        check_operands(self.left.as_ref(), self.right.as_ref(), state, span);
+    }
+    fn ranking_sides(&self) -> Sides<'_> {
+        vec![(self.right.as_ref(), self.left.as_ref(), Some(1))]
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = self.left.extract_variables();
+        vars.extend(self.right.extract_variables());
+        vars
+    }
     fn negate(&self) -> Box<dyn BooleanExpression<Q= Self::Q>> {
         return Box::new(LessEqual{left: self.left.clone_box(), right: self.right.clone_box()})
     }
@@ -390,14 +603,18 @@ impl BooleanExpression for LessEqual {
                         // If the lhs is not a straight variable execute the propagation algorithm
                         let m: i64 = *M.lock().expect("failed to lock m mutex");
                         let mut var_leaves = HashMap::new();
-                        // Build the AST representation of the test (canonical form lhs <=0)
-                        let tree = self.left.to_ast(state, &mut var_leaves);
+                        // Build the AST representation of the test (canonical form lhs - rhs <= 0)
+                        let canonical = Minus {
+                            left: self.left.clone_box(),
+                            right: self.right.clone_box(),
+                        };
+                        let tree = canonical.to_ast(state, &mut var_leaves);
                         // Find the refinement intersecting with [-∞; 0]
                         let refinement = tree
                             .get_value()
                             .intersect(&AbstractInterval::Bounded { lower: m, upper: 0 });
                         // Propagate the refinement found with the backward operators
-                        let sat = tree.backward_analysis(refinement);
+                        let sat = tree.backward_analysis(refinement, &mut var_leaves);
                         if !sat {
                             return AbstractState::bottom(&state);
                         }
@@ -440,6 +657,17 @@ impl BooleanExpression for LessEqual {
     fn to_string(&self) -> String {
         format!("{} <= {}", self.left.to_string(), self.right.to_string())
     }
+    fn check_alarms(&self, state: &mut AbstractState<Self::Q>, span: Span) {
+        check_operands(self.left.as_ref(), self.right.as_ref(), state, span);
+    }
+    fn ranking_sides(&self) -> Sides<'_> {
+        vec![(self.left.as_ref(), self.right.as_ref(), Some(0))]
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = self.left.extract_variables();
+        vars.extend(self.right.extract_variables());
+        vars
+    }
     fn negate(&self) -> Box<dyn BooleanExpression<Q= Self::Q>> {
         return Box::new(Great{left: self.left.clone_box(), right: self.right.clone_box()})
     }
@@ -511,6 +739,17 @@ impl BooleanExpression for Less {
     fn to_string(&self) -> String {
         format!("{} < {}", self.left.to_string(), self.right.to_string())
     }
+    fn check_alarms(&self, state: &mut AbstractState<Self::Q>, span: Span) {
+        check_operands(self.left.as_ref(), self.right.as_ref(), state, span);
+    }
+    fn ranking_sides(&self) -> Sides<'_> {
+        vec![(self.left.as_ref(), self.right.as_ref(), Some(1))]
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = self.left.extract_variables();
+        vars.extend(self.right.extract_variables());
+        vars
+    }
     fn negate(&self) -> Box<dyn BooleanExpression<Q= Self::Q>> {
         return Box::new(GreatEqual{left: self.left.clone_box(), right: self.right.clone_box()})
     }
@@ -531,6 +770,7 @@ impl BooleanExpression for And {
         })
     }
     fn evaluate(&self, state: &mut State) -> bool {
+        // the right operand runs only when the left one holds
         self.left.evaluate(state) && self.right.evaluate(state)
     }
     fn abs_evaluate(
@@ -538,34 +778,60 @@ impl BooleanExpression for And {
         state: &mut AbstractState<Self::Q>,
         flag: bool,
     ) -> AbstractState<Self::Q> {
+        // the right operand is evaluated only in the states where the left one holds
+        let mut holds = self.left.abs_evaluate(&mut state.clone(), false);
         if !flag {
-            let mut x = state.clone();
-            let left_eval = self.left.abs_evaluate(&mut x, false);
-            let right_eval = self.right.abs_evaluate(&mut x, false);
-            if left_eval.is_bottom() || right_eval.is_bottom() {
-                return AbstractState::bottom(state);
-            }
-            let current = left_eval.state_glb(&right_eval);
-            state.variables.extend(current.variables.clone());
-            current
+            self.right.abs_evaluate(&mut holds, false)
         } else {
-            let mut x = state.clone();
-
-            let left_eval = self.left.abs_evaluate(&mut x, false);
-            let right_eval = self.right.abs_evaluate(&mut x, false);
-            if left_eval.is_bottom() || right_eval.is_bottom() {
-                return AbstractState::bottom(state);
-            }
-            let current = left_eval.state_lub(&right_eval);
-            state.variables.extend(current.variables.clone());
-            current
+            let fails = self.left.abs_evaluate(&mut state.clone(), true);
+            fails.state_lub(&self.right.abs_evaluate(&mut holds, true))
         }
     }
     fn to_string(&self) -> String {
-        format!("{} && {}", self.left.to_string(), self.right.to_string())
+        format!(
+            "{} && {}",
+            grouped(self.left.as_ref(), &["||"]),
+            grouped(self.right.as_ref(), &["||"])
+        )
+    }
+    fn check_alarms(&self, state: &mut AbstractState<Self::Q>, span: Span) {
+        self.left.check_alarms(&mut state.clone(), span);
+        for mut holds in self.left.abs_cases(state, false) {
+            self.right.check_alarms(&mut holds, span);
+        }
+    }
+    fn abs_cases(
+        &self,
+        state: &mut AbstractState<Self::Q>,
+        flag: bool,
+    ) -> Vec<AbstractState<Self::Q>> {
+        let holds = self.left.abs_cases(&mut state.clone(), false);
+        let mut cases = if flag {
+            self.left.abs_cases(&mut state.clone(), true)
+        } else {
+            Vec::new()
+        };
+        for mut holds in holds {
+            cases.extend(self.right.abs_cases(&mut holds, flag));
+        }
+        cases
+    }
+    fn ranking_sides(&self) -> Sides<'_> {
+        let mut sides = self.left.ranking_sides();
+        sides.extend(self.right.ranking_sides());
+        sides
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = self.left.extract_variables();
+        vars.extend(self.right.extract_variables());
+        vars
     }
     fn negate(&self) -> Box<dyn BooleanExpression<Q= Self::Q>> {
-        return Box::new(Or{left:self.left.clone_box(), right: self.right.clone_box()})
+        // De Morgan: !(a && b) is !a || !b
+        Box::new(Or {
+            left: self.left.negate(),
+            right: self.right.negate(),
+        })
     }
 }
 
@@ -584,6 +850,7 @@ impl BooleanExpression for Or {
         })
     }
     fn evaluate(&self, state: &mut State) -> bool {
+        // the right operand runs only when the left one fails
         self.left.evaluate(state) || self.right.evaluate(state)
     }
     fn abs_evaluate(
@@ -591,32 +858,57 @@ impl BooleanExpression for Or {
         state: &mut AbstractState<Self::Q>,
         flag: bool,
     ) -> AbstractState<Self::Q> {
+        // the right operand is evaluated only in the states where the left one fails
+        let mut fails = self.left.abs_evaluate(&mut state.clone(), true);
         if !flag {
-            let left_eval = self.left.abs_evaluate(state, false);
-            let right_eval = self.right.abs_evaluate(state, false);
-            if left_eval.is_bottom() || right_eval.is_bottom() {
-                return AbstractState::bottom(state);
-            }
-            let ret = left_eval.state_lub(&right_eval);
-
-            state.variables.extend(ret.variables.clone());
-            ret
+            let holds = self.left.abs_evaluate(&mut state.clone(), false);
+            holds.state_lub(&self.right.abs_evaluate(&mut fails, false))
         } else {
-            let left_eval = self.left.abs_evaluate(state, false);
-            let right_eval = self.right.abs_evaluate(state, false);
-            if left_eval.is_bottom() || right_eval.is_bottom() {
-                return AbstractState::bottom(state);
-            }
-            let ret = left_eval.state_glb(&right_eval);
-            state.variables.extend(ret.variables.clone());
-            ret
+            self.right.abs_evaluate(&mut fails, true)
         }
     }
     fn to_string(&self) -> String {
         format!("{} || {}", self.left.to_string(), self.right.to_string())
     }
+    fn check_alarms(&self, state: &mut AbstractState<Self::Q>, span: Span) {
+        self.left.check_alarms(&mut state.clone(), span);
+        for mut fails in self.left.abs_cases(state, true) {
+            self.right.check_alarms(&mut fails, span);
+        }
+    }
+    fn abs_cases(
+        &self,
+        state: &mut AbstractState<Self::Q>,
+        flag: bool,
+    ) -> Vec<AbstractState<Self::Q>> {
+        let fails = self.left.abs_cases(&mut state.clone(), true);
+        let mut cases = if flag {
+            Vec::new()
+        } else {
+            self.left.abs_cases(&mut state.clone(), false)
+        };
+        for mut fails in fails {
+            cases.extend(self.right.abs_cases(&mut fails, flag));
+        }
+        cases
+    }
+    fn ranking_sides(&self) -> Sides<'_> {
+        // either side may hold, neither implies a least difference
+        let mut sides = self.left.ranking_sides();
+        sides.extend(self.right.ranking_sides());
+        sides.into_iter().map(|(lower, upper, _)| (lower, upper, None)).collect()
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        let mut vars = self.left.extract_variables();
+        vars.extend(self.right.extract_variables());
+        vars
+    }
     fn negate(&self) -> Box<dyn BooleanExpression<Q= Self::Q>> {
-        return Box::new(And{left: self.left.clone_box(), right: self.right.clone_box()})
+        // De Morgan: !(a || b) is !a && !b
+        Box::new(And {
+            left: self.left.negate(),
+            right: self.right.negate(),
+        })
     }
 }
 
@@ -642,18 +934,34 @@ impl BooleanExpression for Not {
         state: &mut AbstractState<Self::Q>,
         flag: bool,
     ) -> AbstractState<Self::Q> {
-        if self.expression.abs_evaluate(state, flag).is_bottom() {
-            AbstractState::bottom(state);
-        }
-        let myflag = !flag;
-        let expr_eval = self.expression.abs_evaluate(state, myflag);
-        expr_eval
+        self.expression.abs_evaluate(state, !flag)
     }
 
     fn to_string(&self) -> String {
-        format!("! {}", self.expression.to_string())
+        format!("! {}", grouped(self.expression.as_ref(), &["&&", "||"]))
+    }
+    fn check_alarms(&self, state: &mut AbstractState<Self::Q>, span: Span) {
+        self.expression.check_alarms(state, span);
+    }
+    fn abs_cases(
+        &self,
+        state: &mut AbstractState<Self::Q>,
+        flag: bool,
+    ) -> Vec<AbstractState<Self::Q>> {
+        self.expression.abs_cases(state, !flag)
+    }
+    fn ranking_sides(&self) -> Sides<'_> {
+        // !(l < r) is r <= l
+        self.expression
+            .ranking_sides()
+            .into_iter()
+            .map(|(lower, upper, _)| (upper, lower, None))
+            .collect()
+    }
+    fn extract_variables(&self) -> Vec<&Variable> {
+        self.expression.extract_variables()
     }
     fn negate(&self) -> Box<dyn BooleanExpression<Q= Self::Q>> {
-      return self.expression.negate().negate()
+      return self.expression.clone_box()
     }
 }
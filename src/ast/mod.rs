@@ -13,6 +13,12 @@ pub enum Op {
     Sub,
     Mul,
     Div,
+    Mod,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
     Uminus,
 }
 
@@ -48,6 +54,12 @@ impl Node {
                 Op::Sub => "-".to_string(),
                 Op::Mul => "*".to_string(),
                 Op::Div => "/".to_string(),
+                Op::Mod => "%".to_string(),
+                Op::BitAnd => "&".to_string(),
+                Op::BitOr => "|".to_string(),
+                Op::BitXor => "^".to_string(),
+                Op::Shl => "<<".to_string(),
+                Op::Shr => ">>".to_string(),
                 Op::Uminus => "u-".to_string(),
             },
             Node::ConstantLeaf(value) => value.to_string(),
@@ -57,6 +69,12 @@ impl Node {
                 Op::Sub => "-".to_string(),
                 Op::Mul => "*".to_string(),
                 Op::Div => "/".to_string(),
+                Op::Mod => "%".to_string(),
+                Op::BitAnd => "&".to_string(),
+                Op::BitOr => "|".to_string(),
+                Op::BitXor => "^".to_string(),
+                Op::Shl => "<<".to_string(),
+                Op::Shr => ">>".to_string(),
                 Op::Uminus => "u-".to_string(),
             },
         };
@@ -84,38 +102,43 @@ impl Node {
         self._inner_pretty_print("".to_string(), matches!(self, Node::Internal(_, _, _, _)));
     }
 
-    pub fn backward_analysis(&self, refinement: AbstractInterval) -> bool {
+    // Propagates the refinement of this node's value down to the leaves, every variable leaf
+    // intersects what it is refined to into `var_leaves`; false when the refinement is unsatisfiable
+    pub fn backward_analysis(
+        &self,
+        refinement: AbstractInterval,
+        var_leaves: &mut HashMap<String, AbstractInterval>,
+    ) -> bool {
+        let refined = refinement.intersect(&self.get_value());
+        if refined.is_bottom() {
+            return false;
+        }
         match self {
-            Node::Internal(op, mut abstract_interval, left, right) => {
-                abstract_interval = refinement;
-
+            Node::Internal(op, _, left, right) => {
                 let refinements = AbstractInterval::backward_arithmetic_operator(
                     left.get_value(),
                     right.get_value(),
-                    abstract_interval,
+                    refined,
                     op.clone(),
                 );
-                left.backward_analysis(refinements[0]) && right.backward_analysis(refinements[1])
+                left.backward_analysis(refinements[0], var_leaves)
+                    && right.backward_analysis(refinements[1], var_leaves)
             }
-            Node::UInternal(op, mut abstract_interval, node) => {
-                abstract_interval = refinement;
-
+            Node::UInternal(op, _, node) => {
                 let refinements = AbstractInterval::backward_unary_arithmetic_operator(
                     op.clone(),
                     node.get_value(),
-                    abstract_interval,
+                    refined,
                 );
-                node.backward_analysis(refinements[0])
-            }
-            Node::ConstantLeaf(abstract_interval) => {
-                refinement.intersect(abstract_interval) != AbstractInterval::Bottom
+                node.backward_analysis(refinements[0], var_leaves)
             }
-            Node::VarLeaf(_, mut abstract_interval) => {
-                let value = refinement.intersect(&abstract_interval);
-                if value != AbstractInterval::Bottom {
-                    abstract_interval = refinement;
-                }
-                value != AbstractInterval::Bottom
+            Node::ConstantLeaf(_) => true,
+            Node::VarLeaf(name, _) => {
+                let value = var_leaves
+                    .get(name)
+                    .map_or(refined, |current| current.intersect(&refined));
+                var_leaves.insert(name.clone(), value);
+                !value.is_bottom()
             }
         }
     }
@@ -1,8 +1,30 @@
 use crate::abstract_domain::{AbstractDomain, AbstractDomainOps};
 use crate::abstract_interval::AbstractInterval;
 use crate::abstract_state::AbstractState;
+use crate::alarms::{self, AlarmKind, Severity};
+use crate::arrays;
+use crate::backward;
+use crate::booleans;
+use crate::cost::{self, Counter};
+use crate::initialisation::{self, Assigned, UninitialisedRead};
 use crate::ast::{arithmetic::*, boolean::*, State};
-use crate::{NARROWING_FLAG, WIDENING_FLAG};
+use crate::json::Json;
+use crate::jumps::{self, Jump, Jumps};
+use crate::lexer::Span;
+use crate::listing::Listing;
+use crate::output;
+use crate::partitioning::{self, Partition};
+use crate::program_points::{self, DescendingStep, PointStates};
+use crate::random;
+use crate::reachability::{self, Reachability, Unreachable};
+use crate::scopes::Scoped;
+use crate::switch;
+use crate::termination::{self, Changes};
+use crate::unrolling;
+use crate::{
+    runtime_error, text_output, DescendingMode, DESCENDING_MODE, DESCENDING_STEPS, MAX_ITERATIONS,
+    NARROWING_FLAG, WIDENING_DELAY, WIDENING_FLAG,
+};
 use std::fmt::Debug;
 
 pub trait Statement: Debug {
@@ -10,13 +32,71 @@ pub trait Statement: Debug {
     fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>>;
     fn evaluate(&self, state: &mut State) -> State;
     fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q>;
+    // Definite assignment: the variables assigned after the statement given those assigned
+    // before it, collecting in `reads` the reads of variables that may be unassigned
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned;
+    // Unreachable code: reports the statements whose entry state is ⊥ in the forward run,
+    // `reached` tells whether the statement is reached; returns whether its exit is
+    // (by default the statement always completes once reached)
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            return Reachability::Unreachable(cause);
+        }
+        Reachability::Reachable
+    }
+    // Termination: change of the variables over the statement run inside a loop iteration,
+    // from the states recorded by the forward run; None when no execution gets past it
+    // (by default every variable the statement may assign changes by an unknown amount)
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        Some(termination::opaque(changes?, self))
+    }
+    // Termination: records a verdict for every loop of the statement, none for simple statements
+    fn check_termination(&self) {}
+    // Cost: records the iteration counter of every loop of the statement, run `enclosing` times
+    fn check_bounds(&self, _enclosing: &Counter) {}
     fn to_string(&self) -> String;
+    // Program point of the statement (span of its leading token)
+    fn span(&self) -> Span;
+    // Annotated listing: simple statements are one line followed by their exit state
+    fn write_listing(&self, listing: &mut Listing, indent: usize) {
+        listing.code(self.to_string(), indent);
+        listing.state_after(self.span(), indent);
+    }
+    // Node of the "program" tree of the JSON report
+    fn to_json(&self) -> Json;
+    // Backward semantics: the states before the statement from which `post` can be reached,
+    // `goal` is the assertion whose failure is the target of the analysis, if any
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        goal: Option<Span>,
+    ) -> AbstractState<Self::Q>;
+    // Abstract semantics under trace partitioning, statements that do not split or thread
+    // partitions run on each of them separately
+    fn abs_evaluate_partitioned(
+        &self,
+        partitions: Vec<Partition<Self::Q>>,
+    ) -> Vec<Partition<Self::Q>> {
+        partitioning::for_each(partitions, |partition| {
+            let state = self.abs_evaluate(&mut partition.state.clone());
+            vec![partition.with_state(state)]
+        })
+    }
 }
 
 #[derive(Debug)]
 pub struct Assign {
     pub var_name: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
     pub expr: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    pub span: Span,
 }
 
 impl Statement for Assign {
@@ -25,6 +105,7 @@ impl Statement for Assign {
         Box::new(Assign {
             var_name: self.var_name.clone_box(),
             expr: self.expr.clone_box(),
+            span: self.span,
         })
     }
 
@@ -34,6 +115,9 @@ impl Statement for Assign {
         state.clone()
     }
     fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        *state = backward::refine(self.span, state);
+        let pre = state.clone();
+        self.expr.check_alarms(&mut pre.clone(), self.span);
         let mut new_state = state.clone();
         let value = self.expr.abs_evaluate(&mut new_state);
         // println!(
@@ -45,122 +129,1658 @@ impl Statement for Assign {
             self.var_name.as_variable().unwrap().to_string(),
             AbstractDomain::new(value),
         );
+        state.forget(&self.var_name.to_string());
         // println!("state after assingment {}", state);
         state.is_bottom = new_state.is_bottom;
         // println!("flag after assignment {}", state.is_bottom);
+        program_points::record(self.span, &pre, state);
         state.clone()
     }
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        _goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        let variable = self.var_name.as_variable().unwrap().to_string();
+        let pre = backward::assign(&variable, self.expr.as_ref(), self.span, post);
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        assigned.read(self.expr.extract_variables(), self.span, reads);
+        assigned.assign(&self.var_name.to_string())
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            return Reachability::Unreachable(cause);
+        }
+        reachability::exit(self.span, || {
+            format!("the assignment {} at {} always fails", self.to_string(), self.span)
+        })
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        let pre = program_points::lookup(self.span)
+            .map(|point| point.pre)
+            .filter(|pre| !pre.is_bottom())?;
+        let variable = self.var_name.to_string();
+        Some(termination::assign(changes?, &variable, self.expr.as_ref(), &pre))
+    }
     fn to_string(&self) -> String {
         format!("{} := {}", self.var_name.to_string(), self.expr.to_string())
     }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "assign",
+            self,
+            vec![
+                ("variable", Json::Str(self.var_name.to_string())),
+                ("expression", Json::Str(self.expr.to_string())),
+            ],
+        )
+    }
+}
+
+// Assignment `b := guard` of a boolean variable
+#[derive(Debug)]
+pub struct BoolAssign {
+    pub var: Variable,
+    pub guard: Box<dyn BooleanExpression<Q = AbstractInterval>>,
+    pub relation: Option<usize>, // see booleans::relate
+    pub span: Span,
+}
+
+impl Statement for BoolAssign {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
+        Box::new(BoolAssign {
+            var: Variable {
+                value: self.var.value.clone(),
+            },
+            guard: self.guard.clone_box(),
+            relation: self.relation,
+            span: self.span,
+        })
+    }
+
+    fn evaluate(&self, state: &mut State) -> State {
+        let value = self.guard.evaluate(&mut state.clone());
+        state.insert(self.var.value.clone(), i64::from(value));
+        state.clone()
+    }
+    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        *state = backward::refine(self.span, state);
+        let pre = state.clone();
+        self.guard.check_alarms(&mut pre.clone(), self.span);
+        let holds = !self.guard.abs_evaluate(&mut pre.clone(), false).is_bottom();
+        let fails = !self.guard.abs_evaluate(&mut pre.clone(), true).is_bottom();
+        let value = booleans::value(holds, fails);
+        state
+            .variables
+            .insert(self.var.value.clone(), AbstractDomain::new(value));
+        state.forget(&self.var.value);
+        if let Some(relation) = self.relation {
+            // a guard reading the variable itself is about its value before the assignment
+            if !booleans::reads(relation, &self.var.value) {
+                state.relations.insert(self.var.value.clone(), relation);
+            }
+        }
+        if value.is_bottom() {
+            state.is_bottom = true;
+        }
+        program_points::record(self.span, &pre, state);
+        state.clone()
+    }
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        _goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        let target = post
+            .variables
+            .get(&self.var.value)
+            .map_or(AbstractInterval::new(0, 1), |domain| domain.value);
+        let mut pre = post.clone();
+        pre.variables.remove(&self.var.value);
+        pre.forget(&self.var.value);
+        let pre = backward::meet_forward(self.span, &pre);
+        // the states before the assignment where the guard takes a value the variable has after it
+        let can_hold = !target.intersect(&AbstractInterval::new(1, 1)).is_bottom();
+        let can_fail = !target.intersect(&AbstractInterval::new(0, 0)).is_bottom();
+        let pre = match (can_hold, can_fail) {
+            (true, true) => pre,
+            (true, false) => self.guard.abs_evaluate(&mut pre.clone(), false),
+            (false, true) => self.guard.abs_evaluate(&mut pre.clone(), true),
+            (false, false) => pre.bottom(),
+        };
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        assigned.read(self.guard.extract_variables(), self.span, reads);
+        assigned.assign(&self.var.value)
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            return Reachability::Unreachable(cause);
+        }
+        reachability::exit(self.span, || {
+            format!("the assignment {} at {} always fails", self.to_string(), self.span)
+        })
+    }
+    fn to_string(&self) -> String {
+        format!("{} := {}", self.var.value, self.guard.to_string())
+    }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "assign",
+            self,
+            vec![
+                ("variable", Json::Str(self.var.value.clone())),
+                ("expression", Json::Str(self.guard.to_string())),
+            ],
+        )
+    }
+}
+
+// Declaration `a[size]` of an array, its elements start at 0
+#[derive(Debug)]
+pub struct ArrayDeclaration {
+    pub array: String,
+    pub size: i64,
+    pub span: Span,
+}
+
+impl Statement for ArrayDeclaration {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
+        Box::new(ArrayDeclaration {
+            array: self.array.clone(),
+            size: self.size,
+            span: self.span,
+        })
+    }
+
+    fn evaluate(&self, state: &mut State) -> State {
+        for index in 0..self.size {
+            state.insert(arrays::element(&self.array, index), 0);
+        }
+        state.clone()
+    }
+    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        *state = backward::refine(self.span, state);
+        let pre = state.clone();
+        state
+            .variables
+            .insert(self.array.clone(), AbstractDomain::new(AbstractInterval::new(0, 0)));
+        state.forget(&self.array);
+        program_points::record(self.span, &pre, state);
+        state.clone()
+    }
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        _goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        let zero = post
+            .variables
+            .get(&self.array)
+            .is_none_or(|domain| domain.value.contains_zero());
+        let mut pre = post.clone();
+        pre.variables.remove(&self.array);
+        let mut pre = backward::meet_forward(self.span, &pre);
+        if !zero {
+            pre = pre.bottom();
+        }
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        _reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        assigned.assign(&self.array)
+    }
+    fn to_string(&self) -> String {
+        format!("{}[{}]", self.array, self.size)
+    }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "array",
+            self,
+            vec![
+                ("array", Json::Str(self.array.clone())),
+                ("size", Json::Int(self.size)),
+            ],
+        )
+    }
+}
+
+// Write `a[index] := expr` of an array element, a weak update of the summary of the array
+#[derive(Debug)]
+pub struct ArrayAssign {
+    pub array: Variable,
+    pub index: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    pub expr: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    pub span: Span,
+}
+
+impl ArrayAssign {
+    fn element(&self) -> String {
+        format!("{}[{}]", self.array.value, self.index.to_string())
+    }
+}
+
+impl Statement for ArrayAssign {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
+        Box::new(ArrayAssign {
+            array: Variable {
+                value: self.array.value.clone(),
+            },
+            index: self.index.clone_box(),
+            expr: self.expr.clone_box(),
+            span: self.span,
+        })
+    }
+
+    fn evaluate(&self, state: &mut State) -> State {
+        let index = self.index.evaluate(&mut state.clone());
+        let value = self.expr.evaluate(&mut state.clone());
+        arrays::check_index(&self.array.value, index);
+        state.insert(arrays::element(&self.array.value, index), value);
+        state.clone()
+    }
+    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        *state = backward::refine(self.span, state);
+        let pre = state.clone();
+        self.index.check_alarms(&mut pre.clone(), self.span);
+        let index = self.index.abs_evaluate(&mut pre.clone());
+        alarms::report(
+            self.span,
+            AlarmKind::OutOfBounds,
+            self.element(),
+            arrays::out_of_bounds(&self.array.value, index),
+            &pre,
+        );
+        self.expr.check_alarms(&mut pre.clone(), self.span);
+        let mut new_state = state.clone();
+        let value = self.expr.abs_evaluate(&mut new_state);
+        let within = arrays::within(&self.array.value, index);
+        if within.is_bottom() {
+            // every write is out of the array
+            *state = state.bottom();
+        } else {
+            let summary = if arrays::size(&self.array.value) == 1 {
+                value
+            } else {
+                self.array.abs_evaluate(state).int_lub(&value)
+            };
+            state
+                .variables
+                .insert(self.array.value.clone(), AbstractDomain::new(summary));
+            state.forget(&self.array.value);
+            // executions go on only with an index within the array
+            let is_bottom = state.is_bottom || new_state.is_bottom;
+            *state = arrays::refine_index(state, &self.array.value, self.index.as_ref());
+            state.is_bottom = state.is_bottom || is_bottom;
+        }
+        program_points::record(self.span, &pre, state);
+        state.clone()
+    }
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        _goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        // the value written is within the summary after the write, and so is the summary before
+        // it unless the update is strong
+        let name = &self.array.value;
+        let mut pre = backward::assign(name, self.expr.as_ref(), self.span, post);
+        if arrays::size(name) > 1 {
+            if let Some(summary) = post.variables.get(name) {
+                pre = pre.update_interval(name, summary.value);
+            }
+        }
+        let pre = arrays::refine_index(&pre, name, self.index.as_ref());
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        assigned.read(self.index.extract_variables(), self.span, reads);
+        assigned.read(self.expr.extract_variables(), self.span, reads);
+        assigned.assign(&self.array.value)
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            return Reachability::Unreachable(cause);
+        }
+        reachability::exit(self.span, || {
+            format!("the write {} at {} is always out of bounds", self.element(), self.span)
+        })
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        match program_points::lookup(self.span) {
+            Some(point) if point.post.is_bottom() => None,
+            _ => Some(termination::opaque(changes?, self)),
+        }
+    }
+    fn to_string(&self) -> String {
+        format!("{} := {}", self.element(), self.expr.to_string())
+    }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "array-assign",
+            self,
+            vec![
+                ("array", Json::Str(self.array.value.clone())),
+                ("index", Json::Str(self.index.to_string())),
+                ("expression", Json::Str(self.expr.to_string())),
+            ],
+        )
+    }
+}
+
+// Skip statements built by the parser (missing else, empty bodies) have a synthetic span
+#[derive(Debug)]
+pub struct Skip {
+    pub span: Span,
+}
+
+impl Statement for Skip {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
+        Box::new(Skip { span: self.span })
+    }
+
+    fn evaluate(&self, state: &mut State) -> State {
+        state.clone()
+    }
+    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        *state = backward::refine(self.span, state);
+        program_points::record(self.span, state, state);
+        state.clone()
+    }
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        _goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        if self.span.is_synthetic() {
+            return post.clone();
+        }
+        let pre = backward::meet_forward(self.span, post);
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        _reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        assigned.clone()
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        changes
+    }
+    fn to_string(&self) -> String {
+        format!("skip")
+    }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn write_listing(&self, listing: &mut Listing, indent: usize) {
+        if self.span.is_synthetic() {
+            return;
+        }
+        listing.code(self.to_string(), indent);
+        listing.state_after(self.span, indent);
+    }
+    fn to_json(&self) -> Json {
+        Json::statement("skip", self, Vec::new())
+    }
+}
+
+// `print(e1, e2, ...)`, see output.rs
+#[derive(Debug)]
+pub struct Print {
+    pub arguments: Vec<Box<dyn ArithmeticExpression<Q = AbstractInterval>>>,
+    pub span: Span,
+}
+
+impl Statement for Print {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
+        Box::new(Print {
+            arguments: self.arguments.iter().map(|argument| argument.clone_box()).collect(),
+            span: self.span,
+        })
+    }
+
+    fn evaluate(&self, state: &mut State) -> State {
+        let values = self
+            .arguments
+            .iter()
+            .map(|argument| argument.evaluate(&mut state.clone()))
+            .collect();
+        output::write(self.span, values);
+        state.clone()
+    }
+    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        *state = backward::refine(self.span, state);
+        let pre = state.clone();
+        for argument in &self.arguments {
+            argument.check_alarms(&mut pre.clone(), self.span);
+            // an argument without a value, e.g. a division by zero, stops the run
+            if argument.abs_evaluate(&mut pre.clone()).is_bottom() {
+                state.is_bottom = true;
+            }
+        }
+        program_points::record(self.span, &pre, state);
+        state.clone()
+    }
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        _goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        let pre = backward::meet_forward(self.span, post);
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        for argument in &self.arguments {
+            assigned.read(argument.extract_variables(), self.span, reads);
+        }
+        assigned.clone()
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            return Reachability::Unreachable(cause);
+        }
+        reachability::exit(self.span, || {
+            format!("the print {} at {} always fails", self.to_string(), self.span)
+        })
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        changes
+    }
+    fn to_string(&self) -> String {
+        let arguments: Vec<String> =
+            self.arguments.iter().map(|argument| argument.to_string()).collect();
+        format!("print({})", arguments.join(", "))
+    }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "print",
+            self,
+            vec![(
+                "arguments",
+                Json::Array(
+                    self.arguments
+                        .iter()
+                        .map(|argument| Json::Str(argument.to_string()))
+                        .collect(),
+                ),
+            )],
+        )
+    }
+}
+
+// `break` or `continue`, the parser checks that it is inside a loop
+#[derive(Debug)]
+pub struct LoopJump {
+    pub jump: Jump,
+    pub span: Span,
+}
+
+impl Statement for LoopJump {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
+        Box::new(LoopJump {
+            jump: self.jump,
+            span: self.span,
+        })
+    }
+
+    fn evaluate(&self, state: &mut State) -> State {
+        jumps::take(self.jump);
+        state.clone()
+    }
+    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        *state = backward::refine(self.span, state);
+        jumps::reach(self.jump, self.span, state);
+        let after = state.bottom();
+        program_points::record(self.span, state, &after);
+        *state = after.clone();
+        after
+    }
+    fn abs_backward(
+        &self,
+        _post: &AbstractState<Self::Q>,
+        _goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        let pre = backward::meet_forward(self.span, &jumps::target(self.jump, self.span));
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        _reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        jumps::reach(self.jump, self.span, assigned);
+        // the walk goes on as if the jump fell through, which only makes the reads after it less
+        // precise
+        assigned.clone()
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            return Reachability::Unreachable(cause);
+        }
+        let target = match self.jump {
+            Jump::Break => "leaves the loop",
+            Jump::Continue => "goes on with the next iteration",
+        };
+        Reachability::Unreachable(format!("the {} at {} {}", self.jump, self.span, target))
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        // a break ends the last iteration, only continue gets to the end of the body
+        if let (Jump::Continue, Some(changes)) = (self.jump, &changes) {
+            jumps::reach(self.jump, self.span, changes);
+        }
+        None
+    }
+    fn to_string(&self) -> String {
+        self.jump.to_string()
+    }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(&self.jump.to_string(), self, Vec::new())
+    }
+}
+
+#[derive(Debug)]
+pub struct Concat {
+    pub first: Box<dyn Statement<Q = AbstractInterval>>,
+    pub second: Box<dyn Statement<Q = AbstractInterval>>,
+}
+
+impl Statement for Concat {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
+        Box::new(Concat {
+            first: self.first.clone_box(),
+            second: self.second.clone_box(),
+        })
+    }
+
+    fn evaluate(&self, state: &mut State) -> State {
+        let mut state_after_first = self.first.evaluate(state);
+        if jumps::pending() {
+            return state_after_first;
+        }
+        let state_after_second = self.second.evaluate(&mut state_after_first);
+        state_after_second
+    }
+    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        let new_state = self
+            .second
+            .abs_evaluate(&mut self.first.abs_evaluate(state));
+        state.is_bottom = new_state.is_bottom;
+        state.variables.extend(new_state.variables.clone());
+        state.relations = new_state.relations.clone();
+        new_state
+    }
+    fn abs_evaluate_partitioned(
+        &self,
+        partitions: Vec<Partition<Self::Q>>,
+    ) -> Vec<Partition<Self::Q>> {
+        self.second
+            .abs_evaluate_partitioned(self.first.abs_evaluate_partitioned(partitions))
+    }
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        self.first
+            .abs_backward(&self.second.abs_backward(post, goal), goal)
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        let assigned = self.first.check_initialisation(assigned, reads);
+        self.second.check_initialisation(&assigned, reads)
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        let after_first = self.first.check_reachability(reached, reports);
+        self.second.check_reachability(&after_first, reports)
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        self.second
+            .iteration_changes(self.first.iteration_changes(changes))
+    }
+    fn check_termination(&self) {
+        self.first.check_termination();
+        self.second.check_termination();
+    }
+    fn check_bounds(&self, enclosing: &Counter) {
+        self.first.check_bounds(enclosing);
+        self.second.check_bounds(enclosing);
+    }
+    fn to_string(&self) -> String {
+        format!("{} ; {}", self.first.to_string(), self.second.to_string())
+    }
+    fn span(&self) -> Span {
+        self.first.span()
+    }
+    fn write_listing(&self, listing: &mut Listing, indent: usize) {
+        self.first.write_listing(listing, indent);
+        listing.terminate();
+        self.second.write_listing(listing, indent);
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "sequence",
+            self,
+            vec![("first", self.first.to_json()), ("second", self.second.to_json())],
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct IfThenElse {
+    pub guard: Box<dyn BooleanExpression<Q = AbstractInterval>>,
+    pub true_expr: Box<dyn Statement<Q = AbstractInterval>>,
+    pub false_expr: Box<dyn Statement<Q = AbstractInterval>>,
+    pub span: Span,
+}
+
+impl Statement for IfThenElse {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
+        Box::new(IfThenElse {
+            guard: self.guard.clone_box(),
+            true_expr: self.true_expr.clone_box(),
+            false_expr: self.false_expr.clone_box(),
+            span: self.span,
+        })
+    }
+
+    fn evaluate(&self, state: &mut State) -> State {
+        if self.guard.evaluate(state) {
+            let state_after_true = self.true_expr.evaluate(state);
+            state.extend(state_after_true.clone());
+            state.clone()
+        } else {
+            let state_after_false = self.false_expr.evaluate(state);
+            state.extend(state_after_false.clone());
+            state.clone()
+        }
+    }
+
+    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        // println!("if eval");
+        *state = backward::refine(self.span, state);
+        let pre = state.clone();
+        self.guard.check_alarms(&mut pre.clone(), self.span);
+        let then_state = self
+            .true_expr
+            .abs_evaluate(&mut self.guard.abs_evaluate(&mut pre.clone(), false));
+        let else_state = self
+            .false_expr
+            .abs_evaluate(&mut self.guard.abs_evaluate(&mut pre.clone(), true));
+        // println!("then state {}", then_state);
+        // println!("else state {}", else_state);
+        let final_state = AbstractState::state_lub(&then_state, &else_state);
+        // println!("state lub {}", final_state);
+        state.is_bottom = final_state.is_bottom;
+        state.variables.extend(final_state.variables.clone());
+        state.relations = final_state.relations.clone();
+        // println!("REAL STATE AFTER IF {}", state);
+        program_points::record(self.span, &pre, state);
+        state.clone()
+    }
+    fn abs_evaluate_partitioned(
+        &self,
+        partitions: Vec<Partition<Self::Q>>,
+    ) -> Vec<Partition<Self::Q>> {
+        partitioning::for_each(partitions, |partition| {
+            let pre = backward::refine(self.span, &partition.state);
+            self.guard.check_alarms(&mut pre.clone(), self.span);
+            let mut branches = partitioning::branch(
+                self.true_expr.as_ref(),
+                &partition,
+                format!("then@{}", self.span),
+                self.guard.abs_evaluate(&mut pre.clone(), false),
+            );
+            branches.extend(partitioning::branch(
+                self.false_expr.as_ref(),
+                &partition,
+                format!("else@{}", self.span),
+                self.guard.abs_evaluate(&mut pre.clone(), true),
+            ));
+            program_points::record(self.span, &pre, &partitioning::join(&branches));
+            branches
+        })
+    }
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        let then_pre = self.true_expr.abs_backward(post, goal);
+        let else_pre = self.false_expr.abs_backward(post, goal);
+        let pre = self
+            .guard
+            .abs_evaluate(&mut then_pre.clone(), false)
+            .state_lub(&self.guard.abs_evaluate(&mut else_pre.clone(), true));
+        let pre = backward::meet_forward(self.span, &pre);
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        assigned.read(self.guard.extract_variables(), self.span, reads);
+        self.true_expr
+            .check_initialisation(assigned, reads)
+            .join(&self.false_expr.check_initialisation(assigned, reads))
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            let unreachable = Reachability::Unreachable(cause);
+            self.true_expr.check_reachability(&unreachable, reports);
+            self.false_expr.check_reachability(&unreachable, reports);
+            return unreachable;
+        }
+        let pre = program_points::lookup(self.span).map(|point| point.pre);
+        let branch = |negated: bool| match &pre {
+            Some(pre) => reachability::filter(pre, self.guard.as_ref(), negated, "if", self.span),
+            None => Reachability::Reachable,
+        };
+        self.true_expr.check_reachability(&branch(false), reports);
+        self.false_expr.check_reachability(&branch(true), reports);
+        reachability::exit(self.span, || format!("no branch of the if at {} completes", self.span))
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        let changes = changes?;
+        let pre = program_points::lookup(self.span)
+            .map(|point| point.pre)
+            .filter(|pre| !pre.is_bottom())?;
+        let branch = |negated: bool| {
+            let filtered = self.guard.abs_evaluate(&mut pre.clone(), negated);
+            (!filtered.is_bottom()).then(|| changes.clone())
+        };
+        termination::join(
+            self.true_expr.iteration_changes(branch(false)),
+            self.false_expr.iteration_changes(branch(true)),
+        )
+    }
+    fn check_termination(&self) {
+        self.true_expr.check_termination();
+        self.false_expr.check_termination();
+    }
+    fn check_bounds(&self, enclosing: &Counter) {
+        self.true_expr.check_bounds(enclosing);
+        self.false_expr.check_bounds(enclosing);
+    }
+    fn to_string(&self) -> String {
+        format!(
+            "if ({}) then  {{{}}}  else {{{}}}",
+            self.guard.to_string(),
+            self.true_expr.to_string(),
+            self.false_expr.to_string()
+        )
+    }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn write_listing(&self, listing: &mut Listing, indent: usize) {
+        listing.code(format!("if {} then {{", self.guard.to_string()), indent);
+        listing.block(self.true_expr.as_ref(), indent + 1);
+        if !self.false_expr.span().is_synthetic() {
+            listing.code("} else {".to_string(), indent);
+            listing.block(self.false_expr.as_ref(), indent + 1);
+        }
+        listing.code("}".to_string(), indent);
+        listing.state_after(self.span, indent);
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "if",
+            self,
+            vec![
+                ("guard", Json::Str(self.guard.to_string())),
+                ("then", self.true_expr.to_json()),
+                ("else", self.false_expr.to_json()),
+            ],
+        )
+    }
+}
+
+// `switch (e) { case 1: S1; ...; default: S }`, see switch.rs; the default is a synthetic skip
+// when missing
+#[derive(Debug)]
+pub struct Switch {
+    pub selector: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    pub cases: Vec<Case>,
+    pub default: Box<dyn Statement<Q = AbstractInterval>>,
+    pub span: Span,
+}
+
+// Label and body of a case
+pub type Case = (i64, Box<dyn Statement<Q = AbstractInterval>>);
+
+impl Switch {
+    fn labels(&self) -> Vec<i64> {
+        self.cases.iter().map(|(label, _)| *label).collect()
+    }
+
+    // Branches taken from `state` with their entry states, the cases in order and then the default
+    fn branches(
+        &self,
+        state: &AbstractState<AbstractInterval>,
+    ) -> Vec<(&dyn Statement<Q = AbstractInterval>, AbstractState<AbstractInterval>)> {
+        let mut branches: Vec<_> = self
+            .cases
+            .iter()
+            .map(|(label, body)| {
+                (body.as_ref(), switch::case(state, self.selector.as_ref(), *label))
+            })
+            .collect();
+        branches.push((
+            self.default.as_ref(),
+            switch::default(state, self.selector.as_ref(), &self.labels()),
+        ));
+        branches
+    }
+
+    // Tag of the partitions going through the branch `index`
+    fn tag(&self, index: usize) -> String {
+        match self.cases.get(index) {
+            Some((label, _)) => format!("case@{}#{}", self.span, label),
+            None => format!("default@{}", self.span),
+        }
+    }
+}
+
+impl Statement for Switch {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
+        Box::new(Switch {
+            selector: self.selector.clone_box(),
+            cases: self
+                .cases
+                .iter()
+                .map(|(label, body)| (*label, body.clone_box()))
+                .collect(),
+            default: self.default.clone_box(),
+            span: self.span,
+        })
+    }
+
+    fn evaluate(&self, state: &mut State) -> State {
+        let value = self.selector.evaluate(&mut state.clone());
+        let branch = self
+            .cases
+            .iter()
+            .find(|(label, _)| *label == value)
+            .map_or(&self.default, |(_, body)| body);
+        let state_after = branch.evaluate(state);
+        state.extend(state_after);
+        state.clone()
+    }
+    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        *state = backward::refine(self.span, state);
+        let pre = state.clone();
+        self.selector.check_alarms(&mut pre.clone(), self.span);
+        let final_state = self
+            .branches(&pre)
+            .into_iter()
+            .map(|(body, mut entry)| body.abs_evaluate(&mut entry))
+            .reduce(|joined, exit| joined.state_lub(&exit))
+            .expect("a switch has a default branch");
+        state.is_bottom = final_state.is_bottom;
+        state.variables.extend(final_state.variables);
+        state.relations = final_state.relations.clone();
+        program_points::record(self.span, &pre, state);
+        state.clone()
+    }
+    fn abs_evaluate_partitioned(
+        &self,
+        partitions: Vec<Partition<Self::Q>>,
+    ) -> Vec<Partition<Self::Q>> {
+        partitioning::for_each(partitions, |partition| {
+            let pre = backward::refine(self.span, &partition.state);
+            self.selector.check_alarms(&mut pre.clone(), self.span);
+            let mut branches = Vec::new();
+            for (index, (body, entry)) in self.branches(&pre).into_iter().enumerate() {
+                branches.extend(partitioning::branch(body, &partition, self.tag(index), entry));
+            }
+            program_points::record(self.span, &pre, &partitioning::join(&branches));
+            branches
+        })
+    }
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        let labels = self.labels();
+        let pre = self
+            .cases
+            .iter()
+            .map(|(label, body)| {
+                switch::case(&body.abs_backward(post, goal), self.selector.as_ref(), *label)
+            })
+            .fold(
+                switch::default(
+                    &self.default.abs_backward(post, goal),
+                    self.selector.as_ref(),
+                    &labels,
+                ),
+                |joined, pre| joined.state_lub(&pre),
+            );
+        let pre = backward::meet_forward(self.span, &pre);
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        assigned.read(self.selector.extract_variables(), self.span, reads);
+        self.cases.iter().fold(
+            self.default.check_initialisation(assigned, reads),
+            |joined, (_, body)| joined.join(&body.check_initialisation(assigned, reads)),
+        )
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            let unreachable = Reachability::Unreachable(cause);
+            for (_, body) in &self.cases {
+                body.check_reachability(&unreachable, reports);
+            }
+            self.default.check_reachability(&unreachable, reports);
+            return unreachable;
+        }
+        let pre = program_points::lookup(self.span).map(|point| point.pre);
+        let branches = pre.as_ref().map(|pre| self.branches(pre));
+        let reached = |index: usize, cause: String| match &branches {
+            Some(branches) if branches[index].1.is_bottom() => Reachability::Unreachable(cause),
+            _ => Reachability::Reachable,
+        };
+        for (index, (label, body)) in self.cases.iter().enumerate() {
+            let cause = format!("the switch at {} never selects the case {}", self.span, label);
+            body.check_reachability(&reached(index, cause), reports);
+        }
+        let cause = format!("the cases of the switch at {} cover every value", self.span);
+        self.default
+            .check_reachability(&reached(self.cases.len(), cause), reports);
+        reachability::exit(self.span, || {
+            format!("no branch of the switch at {} completes", self.span)
+        })
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        let changes = changes?;
+        let pre = program_points::lookup(self.span)
+            .map(|point| point.pre)
+            .filter(|pre| !pre.is_bottom())?;
+        self.branches(&pre)
+            .into_iter()
+            .map(|(body, entry)| body.iteration_changes((!entry.is_bottom()).then(|| changes.clone())))
+            .reduce(termination::join)
+            .flatten()
+    }
+    fn check_termination(&self) {
+        for (_, body) in &self.cases {
+            body.check_termination();
+        }
+        self.default.check_termination();
+    }
+    fn check_bounds(&self, enclosing: &Counter) {
+        for (_, body) in &self.cases {
+            body.check_bounds(enclosing);
+        }
+        self.default.check_bounds(enclosing);
+    }
+    fn to_string(&self) -> String {
+        let mut branches: Vec<String> = self
+            .cases
+            .iter()
+            .map(|(label, body)| format!("case {}: {}", label, body.to_string()))
+            .collect();
+        if !self.default.span().is_synthetic() {
+            branches.push(format!("default: {}", self.default.to_string()));
+        }
+        format!("switch ({}) {{{}}}", self.selector.to_string(), branches.join("; "))
+    }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn write_listing(&self, listing: &mut Listing, indent: usize) {
+        listing.code(format!("switch {} {{", self.selector.to_string()), indent);
+        for (label, body) in &self.cases {
+            listing.code(format!("case {}:", label), indent + 1);
+            listing.block(body.as_ref(), indent + 2);
+        }
+        if !self.default.span().is_synthetic() {
+            listing.code("default:".to_string(), indent + 1);
+            listing.block(self.default.as_ref(), indent + 2);
+        }
+        listing.code("}".to_string(), indent);
+        listing.state_after(self.span, indent);
+    }
+    fn to_json(&self) -> Json {
+        let cases = self
+            .cases
+            .iter()
+            .map(|(label, body)| {
+                Json::object(vec![("label", Json::Int(*label)), ("body", body.to_json())])
+            })
+            .collect();
+        Json::statement(
+            "switch",
+            self,
+            vec![
+                ("selector", Json::Str(self.selector.to_string())),
+                ("cases", Json::Array(cases)),
+                ("default", self.default.to_json()),
+            ],
+        )
+    }
+}
+
+// Nondeterministic choice `either { S1 } or { S2 }`: a concrete run takes one branch, the
+// abstract one joins both like an if without a guard
+#[derive(Debug)]
+pub struct Either {
+    pub first: Box<dyn Statement<Q = AbstractInterval>>,
+    pub second: Box<dyn Statement<Q = AbstractInterval>>,
+    pub span: Span,
+}
+
+impl Statement for Either {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
+        Box::new(Either {
+            first: self.first.clone_box(),
+            second: self.second.clone_box(),
+            span: self.span,
+        })
+    }
+    fn evaluate(&self, state: &mut State) -> State {
+        let branch = if random::choose() {
+            &self.first
+        } else {
+            &self.second
+        };
+        let state_after = branch.evaluate(state);
+        state.extend(state_after);
+        state.clone()
+    }
+    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        *state = backward::refine(self.span, state);
+        let pre = state.clone();
+        let first_state = self.first.abs_evaluate(&mut pre.clone());
+        let second_state = self.second.abs_evaluate(&mut pre.clone());
+        let final_state = first_state.state_lub(&second_state);
+        state.is_bottom = final_state.is_bottom;
+        state.variables.extend(final_state.variables);
+        state.relations = final_state.relations.clone();
+        program_points::record(self.span, &pre, state);
+        state.clone()
+    }
+    fn abs_evaluate_partitioned(
+        &self,
+        partitions: Vec<Partition<Self::Q>>,
+    ) -> Vec<Partition<Self::Q>> {
+        partitioning::for_each(partitions, |partition| {
+            let pre = backward::refine(self.span, &partition.state);
+            let mut branches = partitioning::branch(
+                self.first.as_ref(),
+                &partition,
+                format!("either@{}", self.span),
+                pre.clone(),
+            );
+            branches.extend(partitioning::branch(
+                self.second.as_ref(),
+                &partition,
+                format!("or@{}", self.span),
+                pre.clone(),
+            ));
+            program_points::record(self.span, &pre, &partitioning::join(&branches));
+            branches
+        })
+    }
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        let pre = self
+            .first
+            .abs_backward(post, goal)
+            .state_lub(&self.second.abs_backward(post, goal));
+        let pre = backward::meet_forward(self.span, &pre);
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        self.first
+            .check_initialisation(assigned, reads)
+            .join(&self.second.check_initialisation(assigned, reads))
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            let unreachable = Reachability::Unreachable(cause);
+            self.first.check_reachability(&unreachable, reports);
+            self.second.check_reachability(&unreachable, reports);
+            return unreachable;
+        }
+        self.first.check_reachability(&Reachability::Reachable, reports);
+        self.second.check_reachability(&Reachability::Reachable, reports);
+        reachability::exit(self.span, || {
+            format!("no branch of the either at {} completes", self.span)
+        })
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        termination::join(
+            self.first.iteration_changes(changes.clone()),
+            self.second.iteration_changes(changes),
+        )
+    }
+    fn check_termination(&self) {
+        self.first.check_termination();
+        self.second.check_termination();
+    }
+    fn check_bounds(&self, enclosing: &Counter) {
+        self.first.check_bounds(enclosing);
+        self.second.check_bounds(enclosing);
+    }
+    fn to_string(&self) -> String {
+        format!(
+            "either {{{}}} or {{{}}}",
+            self.first.to_string(),
+            self.second.to_string()
+        )
+    }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn write_listing(&self, listing: &mut Listing, indent: usize) {
+        listing.code("either {".to_string(), indent);
+        listing.block(self.first.as_ref(), indent + 1);
+        listing.code("} or {".to_string(), indent);
+        listing.block(self.second.as_ref(), indent + 1);
+        listing.code("}".to_string(), indent);
+        listing.state_after(self.span, indent);
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "either",
+            self,
+            vec![
+                ("first", self.first.to_json()),
+                ("second", self.second.to_json()),
+            ],
+        )
+    }
+}
+
+// Block `var x := e in { S }` with a local x, dropped at the exit of the block, see scopes.rs
+#[derive(Debug)]
+pub struct Local {
+    pub var: String,
+    pub init: Box<dyn Statement<Q = AbstractInterval>>, // the assignment of the local
+    pub body: Box<dyn Statement<Q = AbstractInterval>>,
+    pub span: Span,
+}
+
+impl Statement for Local {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
+        Box::new(Local {
+            var: self.var.clone(),
+            init: self.init.clone_box(),
+            body: self.body.clone_box(),
+            span: self.span,
+        })
+    }
+    fn evaluate(&self, state: &mut State) -> State {
+        let outer = state.clone();
+        let mut inner = self.init.evaluate(state);
+        let after = self.body.evaluate(&mut inner);
+        *state = after.drop_local(&self.var, &outer);
+        state.clone()
+    }
+    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        *state = backward::refine(self.span, state);
+        let pre = state.clone();
+        let after = jumps::scope(
+            |jumped: &AbstractState<Self::Q>| jumped.drop_local(&self.var, &pre),
+            || {
+                self.body
+                    .abs_evaluate(&mut self.init.abs_evaluate(&mut pre.clone()))
+            },
+        );
+        *state = after.drop_local(&self.var, &pre);
+        program_points::record(self.span, &pre, state);
+        state.clone()
+    }
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        // inside the block the local is unconstrained, the outer variable keeps its value
+        let unconstrained = |state: &AbstractState<Self::Q>| {
+            let mut state = state.clone();
+            state.variables.remove(&self.var);
+            state.forget(&self.var);
+            state
+        };
+        let body_pre = jumps::backward_scope(unconstrained, || {
+            self.body.abs_backward(&unconstrained(post), goal)
+        });
+        let mut pre = self.init.abs_backward(&body_pre, goal);
+        if let Some(outer) = post.variables.get(&self.var) {
+            let value = match pre.variables.get(&self.var) {
+                Some(current) => current.value.intersect(&outer.value),
+                None => outer.value,
+            };
+            pre.variables.insert(self.var.clone(), AbstractDomain::new(value));
+        }
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        let after = jumps::scope(
+            |jumped: &Assigned| jumped.drop_local(&self.var, assigned),
+            || {
+                let inner = self.init.check_initialisation(assigned, reads);
+                self.body.check_initialisation(&inner, reads)
+            },
+        );
+        after.drop_local(&self.var, assigned)
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            let unreachable = Reachability::Unreachable(cause);
+            self.init.check_reachability(&unreachable, reports);
+            self.body.check_reachability(&unreachable, reports);
+            return unreachable;
+        }
+        let after_init = self.init.check_reachability(&Reachability::Reachable, reports);
+        self.body.check_reachability(&after_init, reports)
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        let outer = changes.clone()?;
+        let after = jumps::scope(
+            |jumped: &Changes| jumped.drop_local(&self.var, &outer),
+            || {
+                self.body
+                    .iteration_changes(self.init.iteration_changes(changes))
+            },
+        );
+        Some(after?.drop_local(&self.var, &outer))
+    }
+    fn check_termination(&self) {
+        self.body.check_termination();
+    }
+    fn check_bounds(&self, enclosing: &Counter) {
+        self.body.check_bounds(enclosing);
+    }
+    fn to_string(&self) -> String {
+        format!(
+            "var {} in {{{}}}",
+            self.init.to_string(),
+            self.body.to_string()
+        )
+    }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn write_listing(&self, listing: &mut Listing, indent: usize) {
+        listing.code(format!("var {} in {{", self.init.to_string()), indent);
+        listing.block(self.body.as_ref(), indent + 1);
+        listing.code("}".to_string(), indent);
+        listing.state_after(self.span, indent);
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "local",
+            self,
+            vec![
+                ("variable", Json::Str(self.var.clone())),
+                ("init", self.init.to_json()),
+                ("body", self.body.to_json()),
+            ],
+        )
+    }
+}
+
+// Loop head fixpoint shared by the loops, `step` runs one iteration (guard filter, body and
+// increment) from the head state. The ascending phase joins the first WIDENING_DELAY iterations
+// and widens afterwards; past MAX_ITERATIONS widening is forced so the analysis cannot hang.
+// The descending phase, when enabled, runs at most DESCENDING_STEPS narrowing or plain decreasing
+// iterations and records what every step refined.
+fn loop_invariant(
+    span: Span,
+    entry: &AbstractState<AbstractInterval>,
+    step: &dyn Fn(&AbstractState<AbstractInterval>) -> AbstractState<AbstractInterval>,
+) -> AbstractState<AbstractInterval> {
+    if entry.is_bottom() {
+        return entry.clone();
+    }
+    // flags are copied out: nested loops lock them again
+    let widening = *WIDENING_FLAG.lock().expect("failed to read widening flag");
+    let narrowing = *NARROWING_FLAG.lock().expect("failed to read narrowing flag");
+    let delay = *WIDENING_DELAY.lock().expect("failed to read widening delay");
+    let cap = *MAX_ITERATIONS.lock().expect("failed to read iteration cap");
+    let mode = *DESCENDING_MODE.lock().expect("failed to read descending mode");
+    let descending_steps = *DESCENDING_STEPS.lock().expect("failed to read descending steps");
+
+    let mut head = entry.clone();
+    let mut iterations: u64 = 0;
+    loop {
+        iterations += 1;
+        program_points::update_statistics(|statistics| statistics.loop_iterations += 1);
+        let joined = head.state_lub(&step(&head));
+        if iterations == cap + 1 {
+            program_points::record_cap_hit(span);
+            eprintln!(
+                "warning: the loop at {} did not stabilize in {} iterations, widening is forced",
+                span, cap
+            );
+        }
+        let next = if (widening && iterations > delay) || iterations > cap {
+            program_points::update_statistics(|statistics| statistics.widenings += 1);
+            head.state_widening(&joined)
+        } else {
+            joined
+        };
+        // within the states that can reach the goal of the forward-backward refinement
+        let next = backward::refine_head(span, &next);
+        if next == head {
+            break;
+        }
+        head = next;
+    }
+    if narrowing {
+        let mut descending = Vec::new();
+        for _ in 0..descending_steps {
+            program_points::update_statistics(|statistics| statistics.loop_iterations += 1);
+            let refined = backward::refine_head(span, &entry.state_lub(&step(&head)));
+            let next = match mode {
+                DescendingMode::Narrowing => {
+                    program_points::update_statistics(|statistics| statistics.narrowings += 1);
+                    head.state_narrowing(&refined)
+                }
+                DescendingMode::Decreasing => head.state_glb(&refined),
+            };
+            if next == head {
+                break;
+            }
+            let recovered = DescendingStep::between(&head, &next);
+            if text_output() {
+                for (name, before, after) in &recovered.refined {
+                    println!(
+                        "DESCENDING STEP {} ({}) AT {}: {} {} -> {}",
+                        descending.len() + 1,
+                        mode.name(),
+                        span,
+                        name,
+                        before,
+                        after
+                    );
+                }
+            }
+            descending.push(recovered);
+            head = next;
+        }
+        program_points::record_descending(span, descending);
+    }
+    head
 }
 
-#[derive(Debug)]
-pub struct Skip;
-
-impl Statement for Skip {
-    type Q = AbstractInterval;
-    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
-        Box::new(Skip {})
+// Loop unrolling: the first `iterations` iterations are analysed one at a time from `entry`.
+// Returns the head state of each of them and the state the remaining iterations start from.
+fn unroll(
+    entry: &AbstractState<AbstractInterval>,
+    iterations: u64,
+    step: &dyn Fn(&AbstractState<AbstractInterval>) -> AbstractState<AbstractInterval>,
+) -> (Vec<AbstractState<AbstractInterval>>, AbstractState<AbstractInterval>) {
+    let mut heads = Vec::new();
+    let mut head = entry.clone();
+    for _ in 0..iterations {
+        if head.is_bottom() {
+            break;
+        }
+        program_points::update_statistics(|statistics| statistics.loop_iterations += 1);
+        let next = step(&head);
+        heads.push(head);
+        head = next;
     }
+    (heads, head)
+}
 
-    fn evaluate(&self, state: &mut State) -> State {
-        state.clone()
-    }
-    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
-        state.clone()
+// Prints and records the invariant of a loop, after the heads of its unrolled iterations
+fn report_invariant(
+    span: Span,
+    unrolled: &[AbstractState<AbstractInterval>],
+    invariant: &AbstractState<AbstractInterval>,
+) {
+    if text_output() {
+        for (i, head) in unrolled.iter().enumerate() {
+            println!("CYCLE INVARIANT (ITERATION {}): {}", i + 1, head);
+        }
+        if unrolled.is_empty() {
+            println!("CYCLE INVARIANT: {}", invariant);
+        } else {
+            println!("CYCLE INVARIANT (ITERATIONS > {}): {}", unrolled.len(), invariant);
+        }
     }
-    fn to_string(&self) -> String {
-        format!("skip")
+    program_points::record_invariant(span, unrolled, invariant);
+}
+
+// Loop exits: the states leaving the unrolled iterations, tagged by iteration, the one leaving
+// the invariant and the one leaving through the break statements of the body
+fn loop_exits(
+    span: Span,
+    unrolled: &[AbstractState<AbstractInterval>],
+    invariant: &AbstractState<AbstractInterval>,
+    exit: &dyn Fn(&AbstractState<AbstractInterval>) -> AbstractState<AbstractInterval>,
+    jumps: Jumps<AbstractState<AbstractInterval>>,
+) -> Vec<Partition<AbstractInterval>> {
+    let mut exits: Vec<Partition<AbstractInterval>> = unrolled
+        .iter()
+        .enumerate()
+        .map(|(i, head)| Partition {
+            trace: vec![format!("exit@{}#{}", span, i + 1)],
+            state: exit(head),
+        })
+        .collect();
+    exits.push(Partition {
+        trace: vec![format!("exit@{}", span)],
+        state: exit(invariant),
+    });
+    if let Some(breaks) = jumps.breaks {
+        exits.push(Partition {
+            trace: vec![format!("break@{}", span)],
+            state: breaks,
+        });
     }
+    exits
 }
 
-#[derive(Debug)]
-pub struct Concat {
-    pub first: Box<dyn Statement<Q = AbstractInterval>>,
-    pub second: Box<dyn Statement<Q = AbstractInterval>>,
+// Abstract run of a loop body from `entry`: the state at its end, the continue statements
+// included, and the states reaching its jumps
+fn run_body(
+    body: &dyn Statement<Q = AbstractInterval>,
+    entry: AbstractState<AbstractInterval>,
+) -> (AbstractState<AbstractInterval>, Jumps<AbstractState<AbstractInterval>>) {
+    let (end, jumps) = jumps::collect(|| partitioning::block(body, entry));
+    (jumps.continued(&end), jumps)
 }
 
-impl Statement for Concat {
-    type Q = AbstractInterval;
-    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
-        Box::new(Concat {
-            first: self.first.clone_box(),
-            second: self.second.clone_box(),
-        })
-    }
+// Changes of the variables over an iteration of `body`, the ones ended by continue included
+fn body_changes(body: &dyn Statement<Q = AbstractInterval>) -> Option<Changes> {
+    let (end, jumps) = jumps::collect(|| body.iteration_changes(Some(Changes::new())));
+    termination::join(end, jumps.continues)
+}
 
-    fn evaluate(&self, state: &mut State) -> State {
-        let mut state_after_first = self.first.evaluate(state);
-        let state_after_second = self.second.evaluate(&mut state_after_first);
-        state_after_second
-    }
-    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
-        let new_state = self
-            .second
-            .abs_evaluate(&mut self.first.abs_evaluate(state));
-        state.is_bottom = new_state.is_bottom;
-        state.variables.extend(new_state.variables.clone());
-        new_state
-    }
-    fn to_string(&self) -> String {
-        format!("{} ; {}", self.first.to_string(), self.second.to_string())
-    }
+// Every state the loop head is reached in: the unrolled heads and the invariant
+fn loop_heads(
+    unrolled: &[AbstractState<AbstractInterval>],
+    invariant: &AbstractState<AbstractInterval>,
+) -> AbstractState<AbstractInterval> {
+    unrolled
+        .iter()
+        .fold(invariant.clone(), |joined, head| joined.state_lub(head))
 }
 
-#[derive(Debug)]
-pub struct IfThenElse {
-    pub guard: Box<dyn BooleanExpression<Q = AbstractInterval>>,
-    pub true_expr: Box<dyn Statement<Q = AbstractInterval>>,
-    pub false_expr: Box<dyn Statement<Q = AbstractInterval>>,
+// Every state the head of the loop at `span` was reached in by the forward analysis
+fn recorded_heads(span: Span) -> Option<AbstractState<AbstractInterval>> {
+    program_points::invariant(span).map(|forward| loop_heads(&forward.unrolled, &forward.invariant))
 }
 
-impl Statement for IfThenElse {
-    type Q = AbstractInterval;
-    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
-        Box::new(IfThenElse {
-            guard: self.guard.clone_box(),
-            true_expr: self.true_expr.clone_box(),
-            false_expr: self.false_expr.clone_box(),
-        })
-    }
+// Entry point and loop heads of the loop at `span` recorded by the forward run, None when the loop
+// is not reached
+fn recorded_loop(span: Span) -> Option<(PointStates, AbstractState<AbstractInterval>)> {
+    let point = program_points::lookup(span).filter(|point| !point.pre.is_bottom())?;
+    Some((point, recorded_heads(span)?))
+}
 
-    fn evaluate(&self, state: &mut State) -> State {
-        if self.guard.evaluate(state) {
-            let state_after_true = self.true_expr.evaluate(state);
-            state.extend(state_after_true.clone());
-            state.clone()
+// Cause of the code after a while or for loop being unreachable
+fn never_exits(guard: &dyn BooleanExpression<Q = AbstractInterval>, span: Span) -> String {
+    format!(
+        "the loop at {} never exits, its guard {} is never false",
+        span,
+        guard.to_string()
+    )
+}
+
+// Backward loop fixpoint from the `exit` states: the head states that leave the loop into the
+// goal, or go through one iteration (`step`, from the states after it to those before it) to one
+// of them. Widened like the forward ascending phase and kept within the forward invariant.
+fn backward_invariant(
+    span: Span,
+    exit: &AbstractState<AbstractInterval>,
+    step: &dyn Fn(&AbstractState<AbstractInterval>) -> AbstractState<AbstractInterval>,
+) -> AbstractState<AbstractInterval> {
+    let widening = *WIDENING_FLAG.lock().expect("failed to read widening flag");
+    let delay = *WIDENING_DELAY.lock().expect("failed to read widening delay");
+    let cap = *MAX_ITERATIONS.lock().expect("failed to read iteration cap");
+    let forward = recorded_heads(span);
+    let within_forward = |state: AbstractState<AbstractInterval>| match &forward {
+        Some(forward) => backward::meet(forward, &state),
+        None => state,
+    };
+    let mut head = within_forward(exit.clone());
+    let mut iterations: u64 = 0;
+    loop {
+        iterations += 1;
+        let joined = head.state_lub(&step(&head));
+        let next = if (widening && iterations > delay) || iterations > cap {
+            head.state_widening(&joined)
         } else {
-            let state_after_false = self.false_expr.evaluate(state);
-            state.extend(state_after_false.clone());
-            state.clone()
+            joined
+        };
+        let next = within_forward(next);
+        if next == head {
+            backward::record_head(span, &head);
+            return head;
         }
-    }
-
-    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
-        // println!("if eval");
-        let then_state = self
-            .guard
-            .abs_evaluate(&mut self.true_expr.abs_evaluate(state), false);
-        let else_state = self
-            .guard
-            .abs_evaluate(&mut self.false_expr.abs_evaluate(state), true);
-        // println!("then state {}", then_state);
-        // println!("else state {}", else_state);
-        let final_state = AbstractState::state_lub(&then_state, &else_state);
-        // println!("state lub {}", final_state);
-        state.is_bottom = final_state.is_bottom;
-        state.variables.extend(final_state.variables.clone());
-        // println!("REAL STATE AFTER IF {}", state);
-        state.clone()
-    }
-    fn to_string(&self) -> String {
-        format!(
-            "if ({}) then  {{{}}}  else {{{}}}",
-            self.guard.to_string(),
-            self.true_expr.to_string(),
-            self.false_expr.to_string()
-        )
+        head = next;
     }
 }
 
@@ -168,6 +1788,45 @@ impl Statement for IfThenElse {
 pub struct While {
     pub guard: Box<dyn BooleanExpression<Q = AbstractInterval>>,
     pub body: Box<dyn Statement<Q = AbstractInterval>>,
+    pub span: Span,
+}
+
+impl While {
+    // Analyses the loop from `precondition`, returns its exit states
+    fn exits(&self, precondition: &AbstractState<AbstractInterval>) -> Vec<Partition<AbstractInterval>> {
+        let precondition = &backward::refine(self.span, precondition);
+        if text_output() {
+            println!("PRECONDITION: {}", precondition);
+        }
+        let iteration = |head: &AbstractState<AbstractInterval>| {
+            run_body(
+                self.body.as_ref(),
+                self.guard.abs_evaluate(&mut head.clone(), false),
+            )
+        };
+        let step = |head: &AbstractState<AbstractInterval>| iteration(head).0;
+        let (unrolled, rest) = unroll(precondition, unrolling::iterations(self.span), &step);
+        let invariant = loop_invariant(self.span, &rest, &step);
+        report_invariant(self.span, &unrolled, &invariant);
+        let heads = loop_heads(&unrolled, &invariant);
+        self.guard.check_alarms(&mut heads.clone(), self.span);
+        // the body states recorded last are those of every iteration, unrolled ones included
+        let (_, jumps) = iteration(&heads);
+        // filtering with !guard, joined with the break states
+        let exits = loop_exits(
+            self.span,
+            &unrolled,
+            &invariant,
+            &|head| self.guard.abs_evaluate(&mut head.clone(), true),
+            jumps,
+        );
+        let postcondition = partitioning::join(&exits);
+        if text_output() {
+            println!("CYCLE POSTCONDITION: {}", postcondition);
+        }
+        program_points::record(self.span, precondition, &postcondition);
+        exits
+    }
 }
 
 impl Statement for While {
@@ -176,6 +1835,7 @@ impl Statement for While {
         Box::new(While {
             guard: self.guard.clone_box(),
             body: self.body.clone_box(),
+            span: self.span,
         })
     }
 
@@ -187,6 +1847,9 @@ impl Statement for While {
             prev_state = current_state.clone();
             if self.guard.evaluate(&mut current_state) {
                 current_state = self.body.evaluate(&mut current_state);
+                if jumps::land() == Some(Jump::Break) {
+                    break;
+                }
             } else {
             }
             if current_state == prev_state {
@@ -200,53 +1863,100 @@ impl Statement for While {
     }
 
     fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
-        let wid = WIDENING_FLAG
-            .lock()
-            .expect("failed to read widening flag in while loop");
-        let narrow = NARROWING_FLAG
-            .lock()
-            .expect("failed to read narrowing flag in while loop");
-        let precondition = state.clone();
-        println!("PRECONDITION: {}", precondition);
-        let mut _guard_result = AbstractState::new();
-        let mut _body_result = AbstractState::new();
-        let mut _prev_state = state.clone();
-        let mut current_state = state.clone();
-        loop {
-            _guard_result = self.guard.abs_evaluate(&mut current_state.clone(), false);
-            _body_result = self.body.abs_evaluate(&mut _guard_result.clone());
-            _body_result = _prev_state.state_lub(&_body_result.clone());
-            if *wid == true {
-                current_state = _prev_state.state_widening(&_body_result.clone());
-            }
-            if current_state.clone() == _prev_state.clone() {
-                break;
-            }
-            _prev_state = current_state.clone();
-        }
-        let invariant = current_state.clone();
-        println!("CYCLE INVARIANT: {}", invariant);
-        _prev_state = invariant.clone();
-        if *narrow == true {
-            loop {
-                _guard_result = self.guard.abs_evaluate(&mut current_state.clone(), false);
-                _body_result = self.body.abs_evaluate(&mut _guard_result.clone());
-                _body_result = precondition.state_lub(&_body_result.clone());
-                current_state = _prev_state.clone().state_narrowing(&_body_result.clone());
-                if current_state.clone() == _prev_state.clone() {
-                    break;
-                }
-                _prev_state = current_state.clone();
-            }
-        }
-        // filtering with !guard
-        let postcondition = self.guard.abs_evaluate(&mut current_state.clone(), true);
+        let postcondition = partitioning::join(&self.exits(state));
+        state.is_bottom = postcondition.is_bottom;
         state.variables.extend(postcondition.variables.clone());
-
-        println!("CYCLE POSTCONDITION: {}", postcondition);
+        state.relations = postcondition.relations.clone();
         postcondition
     }
+    fn abs_evaluate_partitioned(
+        &self,
+        partitions: Vec<Partition<Self::Q>>,
+    ) -> Vec<Partition<Self::Q>> {
+        partitioning::for_each(partitions, |partition| {
+            partitioning::split(&partition, self.exits(&partition.state))
+        })
+    }
 
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        let exit = self.guard.abs_evaluate(&mut post.clone(), true);
+        let head = backward_invariant(self.span, &exit, &|head| {
+            let body_pre = jumps::backward(post, head, || self.body.abs_backward(head, goal));
+            self.guard.abs_evaluate(&mut body_pre.clone(), false)
+        });
+        let pre = backward::meet_forward(self.span, &head);
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        let head = initialisation::loop_head(assigned, |head, reads| {
+            head.read(self.guard.extract_variables(), self.span, reads);
+            let (end, jumps) = jumps::collect(|| self.body.check_initialisation(head, reads));
+            jumps.continued(&end)
+        });
+        head.read(self.guard.extract_variables(), self.span, reads);
+        let (_, jumps) = jumps::collect(|| self.body.check_initialisation(&head, reads));
+        // the body may not run at all
+        jumps.broken(&head)
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            let unreachable = Reachability::Unreachable(cause);
+            self.body.check_reachability(&unreachable, reports);
+            return unreachable;
+        }
+        let body = match recorded_heads(self.span) {
+            Some(heads) => reachability::filter(&heads, self.guard.as_ref(), false, "loop", self.span),
+            None => Reachability::Reachable,
+        };
+        self.body.check_reachability(&body, reports);
+        reachability::exit(self.span, || never_exits(self.guard.as_ref(), self.span))
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        let point = program_points::lookup(self.span)?;
+        if point.post.is_bottom() {
+            return None;
+        }
+        // nested loops are not followed
+        Some(termination::opaque(changes?, self))
+    }
+    fn check_termination(&self) {
+        self.body.check_termination();
+        let Some((point, heads)) = recorded_loop(self.span) else {
+            return;
+        };
+        let changes = body_changes(self.body.as_ref());
+        let exits = !point.post.is_bottom();
+        let verdict = termination::verdict(self.guard.as_ref(), &heads, changes, exits);
+        termination::record(self.span, verdict);
+    }
+    fn check_bounds(&self, enclosing: &Counter) {
+        let Some((point, heads)) = recorded_loop(self.span) else {
+            return;
+        };
+        let changes = body_changes(self.body.as_ref());
+        let iterations = cost::counter(
+            self.guard.as_ref(),
+            &point.pre,
+            &heads,
+            changes,
+            !point.post.is_bottom(),
+            false,
+        );
+        self.body.check_bounds(&cost::record(self.span, iterations, enclosing));
+    }
     fn to_string(&self) -> String {
         format!(
             "while ({}) {{{}}} ",
@@ -254,6 +1964,25 @@ impl Statement for While {
             self.body.to_string()
         )
     }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn write_listing(&self, listing: &mut Listing, indent: usize) {
+        listing.code(format!("while ({}) {{", self.guard.to_string()), indent);
+        listing.block(self.body.as_ref(), indent + 1);
+        listing.code("}".to_string(), indent);
+        listing.state_after(self.span, indent);
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "while",
+            self,
+            vec![
+                ("guard", Json::Str(self.guard.to_string())),
+                ("body", self.body.to_json()),
+            ],
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -262,6 +1991,92 @@ pub struct For {
     pub guard: Box<dyn BooleanExpression<Q = AbstractInterval>>,
     pub increment: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
     pub body: Box<dyn Statement<Q = AbstractInterval>>,
+    pub span: Span,
+}
+
+impl For {
+    // States before the increment leading to `post`, increments other than `i++` and `i--` do
+    // not change the state
+    fn increment_backward(
+        &self,
+        post: &AbstractState<AbstractInterval>,
+    ) -> AbstractState<AbstractInterval> {
+        let increment = self.increment.as_any();
+        let (variable, step): (&Variable, Box<dyn ArithmeticExpression<Q = AbstractInterval>>) =
+            if let Some(plus_plus) = increment.downcast_ref::<PlusPlus>() {
+                match plus_plus.var.as_variable() {
+                    Some(variable) => (
+                        variable,
+                        Box::new(Add {
+                            left: variable.clone_box(),
+                            right: Box::new(Numeral(1)),
+                        }),
+                    ),
+                    None => return post.clone(),
+                }
+            } else if let Some(minus_minus) = increment.downcast_ref::<MinusMinus>() {
+                match minus_minus.var.as_variable() {
+                    Some(variable) => (
+                        variable,
+                        Box::new(Minus {
+                            left: variable.clone_box(),
+                            right: Box::new(Numeral(1)),
+                        }),
+                    ),
+                    None => return post.clone(),
+                }
+            } else {
+                return post.clone();
+            };
+        backward::assign(&variable.value, step.as_ref(), Span::default(), post)
+    }
+
+    // Changes of the variables over an iteration, increment included
+    fn changes(&self) -> Option<Changes> {
+        body_changes(self.body.as_ref())
+            .map(|changes| termination::increment(changes, self.increment.as_ref()))
+    }
+
+    // Analyses the loop, initialisation included, from `precondition`, returns its exit states
+    fn exits(&self, precondition: &AbstractState<AbstractInterval>) -> Vec<Partition<AbstractInterval>> {
+        let precondition = &backward::refine(self.span, precondition);
+        if text_output() {
+            println!("PRECONDITION {}", precondition);
+        }
+        let entry = self.init.abs_evaluate(&mut precondition.clone());
+        let step = |head: &AbstractState<AbstractInterval>| {
+            let (mut body_result, _) = run_body(
+                self.body.as_ref(),
+                self.guard.abs_evaluate(&mut head.clone(), false),
+            );
+            self.increment.abs_evaluate(&mut body_result);
+            body_result
+        };
+        let (unrolled, rest) = unroll(&entry, unrolling::iterations(self.span), &step);
+        let invariant = loop_invariant(self.span, &rest, &step);
+        report_invariant(self.span, &unrolled, &invariant);
+        let heads = loop_heads(&unrolled, &invariant);
+        self.guard.check_alarms(&mut heads.clone(), self.span);
+        let (mut body_result, jumps) = run_body(
+            self.body.as_ref(),
+            self.guard.abs_evaluate(&mut heads.clone(), false),
+        );
+        self.increment.check_alarms(&mut body_result, self.span);
+        // filtering with !guard, joined with the break states
+        let exits = loop_exits(
+            self.span,
+            &unrolled,
+            &invariant,
+            &|head| self.guard.abs_evaluate(&mut head.clone(), true),
+            jumps,
+        );
+        let postcondition = partitioning::join(&exits);
+        if text_output() {
+            println!("CYCLE POSTCONDITION: {}", postcondition);
+        }
+        program_points::record(self.span, precondition, &postcondition);
+        exits
+    }
 }
 
 impl Statement for For {
@@ -272,12 +2087,15 @@ impl Statement for For {
             guard: self.guard.clone_box(),
             increment: self.increment.clone_box(),
             body: self.body.clone_box(),
+            span: self.span,
         })
     }
 
     //for loop evaluation
     fn evaluate(&self, state: &mut State) -> State {
-        println!("FOR INPUT STATE {:?}", state);
+        if text_output() {
+            println!("FOR INPUT STATE {:?}", state);
+        }
         let mut prev_state: State;
         let mut current_state = state.clone();
         current_state = self.init.evaluate(&mut current_state);
@@ -285,6 +2103,9 @@ impl Statement for For {
             prev_state = current_state.clone();
             if self.guard.evaluate(&mut current_state) {
                 current_state = self.body.evaluate(&mut current_state);
+                if jumps::land() == Some(Jump::Break) {
+                    break;
+                }
                 let _ = self.increment.evaluate(&mut current_state);
             } else {
             }
@@ -293,67 +2114,121 @@ impl Statement for For {
             }
         }
         state.extend(current_state.clone());
-        println!("state after for evaluation {:?}", state);
+        if text_output() {
+            println!("state after for evaluation {:?}", state);
+        }
         current_state
     }
 
     fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
-        let wid = WIDENING_FLAG
-            .lock()
-            .expect("failed to read widening flag in for loop");
-        let narrow = NARROWING_FLAG
-            .lock()
-            .expect("failed to read narrowing flag in for loop");
-        let precondition = state.clone();
-        println!("PRECONDITION {}", precondition);
-        // println!("init component {}", self.init.to_string());
-        self.init.abs_evaluate(&mut state.clone());
-        let mut _guard_result = AbstractState::new();
-        let mut _body_result = AbstractState::new();
-        let mut _prev_state = state.clone();
-        let mut current_state = state.clone();
-        let mut _increment_result = AbstractInterval::new(0 as i64, 0 as i64);
-        loop {
-            _guard_result = self.guard.abs_evaluate(&mut _prev_state.clone(), false);
-
-            _body_result = self.body.abs_evaluate(&mut _guard_result.clone());
-            _increment_result = self.increment.abs_evaluate(&mut _body_result);
-
-            _body_result = _prev_state.state_lub(&_body_result.clone());
-
-            if *wid == true {
-                current_state = _prev_state.state_widening(&_body_result.clone());
-            }
+        let postcondition = partitioning::join(&self.exits(state));
+        state.is_bottom = postcondition.is_bottom;
+        state.variables.extend(postcondition.variables.clone());
+        state.relations = postcondition.relations.clone();
+        postcondition
+    }
+    fn abs_evaluate_partitioned(
+        &self,
+        partitions: Vec<Partition<Self::Q>>,
+    ) -> Vec<Partition<Self::Q>> {
+        partitioning::for_each(partitions, |partition| {
+            partitioning::split(&partition, self.exits(&partition.state))
+        })
+    }
 
-            if current_state == _prev_state {
-                break;
-            }
-            _prev_state = current_state.clone();
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        let exit = self.guard.abs_evaluate(&mut post.clone(), true);
+        let head = backward_invariant(self.span, &exit, &|head| {
+            let body_post = self.increment_backward(head);
+            let body_pre = jumps::backward(post, &body_post, || {
+                self.body.abs_backward(&body_post, goal)
+            });
+            self.guard.abs_evaluate(&mut body_pre.clone(), false)
+        });
+        let pre = backward::meet_forward(self.span, &self.init.abs_backward(&head, goal));
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        let entry = self.init.check_initialisation(assigned, reads);
+        let iteration = |head: &Assigned, reads: &mut Vec<UninitialisedRead>| {
+            head.read(self.guard.extract_variables(), self.span, reads);
+            let (end, jumps) = jumps::collect(|| self.body.check_initialisation(head, reads));
+            let after_body = jumps.continued(&end);
+            after_body.read(self.increment.extract_variables(), self.span, reads);
+            (after_body, jumps)
+        };
+        let head = initialisation::loop_head(&entry, |head, reads| iteration(head, reads).0);
+        let (_, jumps) = iteration(&head, reads);
+        // the body may not run at all
+        jumps.broken(&head)
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            let unreachable = Reachability::Unreachable(cause);
+            self.init.check_reachability(&unreachable, reports);
+            self.body.check_reachability(&unreachable, reports);
+            return unreachable;
         }
-        println!("CYCLE INVARIANT {}", current_state);
-        _prev_state = current_state.clone();
-
-        loop {
-            _guard_result = self.guard.abs_evaluate(&mut current_state.clone(), false);
-            _body_result = self.body.abs_evaluate(&mut _guard_result.clone());
-            _body_result = precondition.state_lub(&_body_result.clone());
-            _increment_result = self.increment.abs_evaluate(&mut _body_result);
-            if *narrow == true {
-                current_state = _prev_state.clone().state_narrowing(&_body_result.clone());
-            }
-            if current_state == _prev_state {
-                break;
+        let entry = self.init.check_reachability(&Reachability::Reachable, reports);
+        let body = match (&entry, recorded_heads(self.span)) {
+            (Reachability::Unreachable(_), _) => entry.clone(),
+            (_, Some(heads)) => {
+                reachability::filter(&heads, self.guard.as_ref(), false, "loop", self.span)
             }
-            _prev_state = current_state.clone();
+            (_, None) => Reachability::Reachable,
+        };
+        self.body.check_reachability(&body, reports);
+        reachability::exit(self.span, || never_exits(self.guard.as_ref(), self.span))
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        let point = program_points::lookup(self.span)?;
+        if point.post.is_bottom() {
+            return None;
         }
-        // filtering with !guard
-        let postcondition = self.guard.abs_evaluate(&mut current_state.clone(), true);
-        state.is_bottom = postcondition.is_bottom;
-        state.variables.extend(postcondition.variables.clone());
-        println!("CYCLE POSTCONDITION: {}", postcondition);
-        state.clone()
+        // nested loops are not followed
+        Some(termination::opaque(changes?, self))
+    }
+    fn check_termination(&self) {
+        self.init.check_termination();
+        self.body.check_termination();
+        let Some((point, heads)) = recorded_loop(self.span) else {
+            return;
+        };
+        let exits = !point.post.is_bottom();
+        let verdict = termination::verdict(self.guard.as_ref(), &heads, self.changes(), exits);
+        termination::record(self.span, verdict);
+    }
+    fn check_bounds(&self, enclosing: &Counter) {
+        self.init.check_bounds(enclosing);
+        let Some((point, heads)) = recorded_loop(self.span) else {
+            return;
+        };
+        let exits = !point.post.is_bottom();
+        // the loop is entered after the initialisation
+        let entry = program_points::lookup(self.init.span()).map_or(point.pre, |init| init.post);
+        let iterations = cost::counter(
+            self.guard.as_ref(),
+            &entry,
+            &heads,
+            self.changes(),
+            exits,
+            false,
+        );
+        self.body.check_bounds(&cost::record(self.span, iterations, enclosing));
     }
-
     fn to_string(&self) -> String {
         format!(
             "for ({} ; {} ; {}) {{{}}} ",
@@ -363,12 +2238,86 @@ impl Statement for For {
             self.body.to_string()
         )
     }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn write_listing(&self, listing: &mut Listing, indent: usize) {
+        listing.code(
+            format!(
+                "for ({}; {}; {}) {{",
+                self.init.to_string(),
+                self.guard.to_string(),
+                self.increment.to_string()
+            ),
+            indent,
+        );
+        listing.block(self.body.as_ref(), indent + 1);
+        listing.code("}".to_string(), indent);
+        listing.state_after(self.span, indent);
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "for",
+            self,
+            vec![
+                ("init", self.init.to_json()),
+                ("guard", Json::Str(self.guard.to_string())),
+                ("increment", Json::Str(self.increment.to_string())),
+                ("body", self.body.to_json()),
+            ],
+        )
+    }
 }
 
 #[derive(Debug)]
 pub struct RepeatUntil {
     pub body: Box<dyn Statement<Q = AbstractInterval>>,
     pub guard: Box<dyn BooleanExpression<Q = AbstractInterval>>,
+    pub span: Span,
+}
+
+impl RepeatUntil {
+    // Analyses the loop from `pre`, returns its exit states
+    fn exits(&self, pre: &AbstractState<AbstractInterval>) -> Vec<Partition<AbstractInterval>> {
+        let pre = &backward::refine(self.span, pre);
+        if text_output() {
+            println!("PRECONDITION: {}", pre);
+        }
+        // the loop head is where the guard is tested, after the body: the state of iteration i
+        // is the one the guard is tested in after the i-th execution of the body
+        let neg_guard = self.guard.negate();
+        let step = |head: &AbstractState<AbstractInterval>| {
+            run_body(
+                self.body.as_ref(),
+                neg_guard.abs_evaluate(&mut head.clone(), false),
+            )
+            .0
+        };
+        let (first, _) = run_body(self.body.as_ref(), pre.clone());
+        let (unrolled, rest) = unroll(&first, unrolling::iterations(self.span), &step);
+        let invariant = loop_invariant(self.span, &rest, &step);
+        report_invariant(self.span, &unrolled, &invariant);
+        let heads = loop_heads(&unrolled, &invariant);
+        self.guard.check_alarms(&mut heads.clone(), self.span);
+        // the body states recorded last are those of every iteration, the first one included
+        let (_, jumps) = run_body(
+            self.body.as_ref(),
+            pre.state_lub(&neg_guard.abs_evaluate(&mut heads.clone(), false)),
+        );
+        let exits = loop_exits(
+            self.span,
+            &unrolled,
+            &invariant,
+            &|head| self.guard.abs_evaluate(&mut head.clone(), false),
+            jumps,
+        );
+        let postcondition = partitioning::join(&exits);
+        if text_output() {
+            println!("CYCLE POSTCONDITION: {}", postcondition);
+        }
+        program_points::record(self.span, pre, &postcondition);
+        exits
+    }
 }
 
 impl Statement for RepeatUntil {
@@ -377,81 +2326,140 @@ impl Statement for RepeatUntil {
         Box::new(RepeatUntil {
             body: self.body.clone_box(),
             guard: self.guard.clone_box(),
+            span: self.span,
         })
     }
 
     //Repeat until evaluation
     fn evaluate(&self, state: &mut State) -> State {
-        println!("REPEAT UNTIL INPUT STATE {:?}", state);
+        if text_output() {
+            println!("REPEAT UNTIL INPUT STATE {:?}", state);
+        }
         let mut prev_state: State;
         //One body executione guaranteed
         let mut current_state = self.body.evaluate(&mut state.clone());
-        loop {
+        let mut broken = jumps::land() == Some(Jump::Break);
+        while !broken {
             prev_state = current_state.clone();
             if !self.guard.evaluate(&mut current_state) {
                 current_state = self.body.evaluate(&mut current_state);
+                broken = jumps::land() == Some(Jump::Break);
             } else {
             }
             if current_state == prev_state {
                 break;
             }
         }
-        println!("state after repeat until evaluation {:?}", state);
+        if text_output() {
+            println!("state after repeat until evaluation {:?}", state);
+        }
         current_state
     }
 
     fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
-        // let wid = WIDENING_FLAG
-        //     .lock()
-        //     .expect("failed to read widening flag in repeat until loop");
-        // let narrow = NARROWING_FLAG
-        //     .lock()
-        //     .expect("failed to read narrowing flag in repeat until loop");
-        // let precondition = state.clone();
-        // println!("PRECONDITION {}", precondition);
-        // let mut _guard_result = AbstractState::new();
-        // let mut _body_result = AbstractState::new();
-        // let mut prev_state = state.clone();
-        // let mut current_state = self.body.abs_evaluate(&mut prev_state);
-        // loop {
-        //     prev_state = current_state.clone();
-        //     _guard_result = self.guard.abs_evaluate(&mut prev_state.clone(), true);
-        //     _body_result = self.body.abs_evaluate(&mut _guard_result.clone());
-        //     _body_result = prev_state.state_lub(&_body_result.clone());
-        //     if *wid == true {
-        //         current_state = prev_state.state_widening(&_body_result.clone());
-        //     }
-        //     //fixpoint check
-        //     if current_state == prev_state {
-        //         break;
-        //     }
-        // }
-        // println!("CYCLE INVARIANT: {}", current_state);
-        // prev_state = current_state.clone();
-        // if *narrow == true {
-        //     loop {
-        //         _guard_result = self.guard.abs_evaluate(&mut current_state.clone(), true);
-        //         _body_result = self.body.abs_evaluate(&mut _guard_result.clone());
-        //         _body_result = precondition.state_lub(&_body_result.clone());
-        //         current_state = prev_state.state_narrowing(&_body_result.clone());
-        //         if current_state == prev_state {
-        //             break;
-        //         }
-        //         prev_state = current_state.clone();
-        //     }
-        // }
-        // // filtering with !guard
-        // let postcondition = self.guard.abs_evaluate(&mut current_state.clone(), false);
-        // state.is_bottom = postcondition.is_bottom;
-        // state.variables.extend(postcondition.variables.clone());
-        // println!("CYCLE POSTCONDITION: {}", postcondition);
-        // state.clone()
-        let neg_guard = self.guard.negate();
-        let while_obj = Box::new(While{guard: neg_guard, body: self.body.clone_box()});
-        let conc = Box::new(Concat{first: self.body.clone_box(), second: while_obj});
-        conc.abs_evaluate(state)
+        let postcondition = partitioning::join(&self.exits(state));
+        state.is_bottom = postcondition.is_bottom;
+        state.variables.extend(postcondition.variables.clone());
+        state.relations = postcondition.relations.clone();
+        postcondition
+    }
+    fn abs_evaluate_partitioned(
+        &self,
+        partitions: Vec<Partition<Self::Q>>,
+    ) -> Vec<Partition<Self::Q>> {
+        partitioning::for_each(partitions, |partition| {
+            partitioning::split(&partition, self.exits(&partition.state))
+        })
     }
 
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        // the head is where the guard is tested, as in the forward analysis
+        let exit = self.guard.abs_evaluate(&mut post.clone(), false);
+        let neg_guard = self.guard.negate();
+        let head = backward_invariant(self.span, &exit, &|head| {
+            let body_pre = jumps::backward(post, head, || self.body.abs_backward(head, goal));
+            neg_guard.abs_evaluate(&mut body_pre.clone(), false)
+        });
+        let body_pre = jumps::backward(post, &head, || self.body.abs_backward(&head, goal));
+        let pre = backward::meet_forward(self.span, &body_pre);
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        let head = initialisation::loop_head(assigned, |head, reads| {
+            let (end, jumps) = jumps::collect(|| self.body.check_initialisation(head, reads));
+            jumps.continued(&end)
+        });
+        // the body runs at least once, the guard is tested after it
+        let (end, jumps) = jumps::collect(|| self.body.check_initialisation(&head, reads));
+        let after_body = jumps.continued(&end);
+        after_body.read(self.guard.extract_variables(), self.span, reads);
+        jumps.broken(&after_body)
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            let unreachable = Reachability::Unreachable(cause);
+            self.body.check_reachability(&unreachable, reports);
+            return unreachable;
+        }
+        // the body runs at least once
+        self.body.check_reachability(&Reachability::Reachable, reports);
+        reachability::exit(self.span, || {
+            format!(
+                "the loop at {} never exits, its guard {} is never true",
+                self.span,
+                self.guard.to_string()
+            )
+        })
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        let point = program_points::lookup(self.span)?;
+        if point.post.is_bottom() {
+            return None;
+        }
+        // nested loops are not followed
+        Some(termination::opaque(changes?, self))
+    }
+    fn check_termination(&self) {
+        self.body.check_termination();
+        let Some((point, heads)) = recorded_loop(self.span) else {
+            return;
+        };
+        // the body runs again from the heads where the guard does not hold
+        let neg_guard = self.guard.negate();
+        let changes = body_changes(self.body.as_ref());
+        let exits = !point.post.is_bottom();
+        let verdict = termination::verdict(neg_guard.as_ref(), &heads, changes, exits);
+        termination::record(self.span, verdict);
+    }
+    fn check_bounds(&self, enclosing: &Counter) {
+        let Some((point, heads)) = recorded_loop(self.span) else {
+            return;
+        };
+        let neg_guard = self.guard.negate();
+        let changes = body_changes(self.body.as_ref());
+        let iterations = cost::counter(
+            neg_guard.as_ref(),
+            &point.pre,
+            &heads,
+            changes,
+            !point.post.is_bottom(),
+            true,
+        );
+        self.body.check_bounds(&cost::record(self.span, iterations, enclosing));
+    }
     fn to_string(&self) -> String {
         format!(
             "repeat {{{}}} until ({}) ",
@@ -459,4 +2467,133 @@ impl Statement for RepeatUntil {
             self.guard.to_string()
         )
     }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn write_listing(&self, listing: &mut Listing, indent: usize) {
+        listing.code("repeat {".to_string(), indent);
+        listing.block(self.body.as_ref(), indent + 1);
+        listing.code(format!("}} until ({})", self.guard.to_string()), indent);
+        listing.state_after(self.span, indent);
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "repeat",
+            self,
+            vec![
+                ("body", self.body.to_json()),
+                ("guard", Json::Str(self.guard.to_string())),
+            ],
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct Assert {
+    pub guard: Box<dyn BooleanExpression<Q = AbstractInterval>>,
+    pub span: Span,
+}
+
+impl Statement for Assert {
+    type Q = AbstractInterval;
+    fn clone_box(&self) -> Box<dyn Statement<Q = Self::Q>> {
+        Box::new(Assert {
+            guard: self.guard.clone_box(),
+            span: self.span,
+        })
+    }
+
+    fn evaluate(&self, state: &mut State) -> State {
+        if !self.guard.evaluate(&mut state.clone()) {
+            runtime_error(format!(
+                "assertion {} failed at {}",
+                self.guard.to_string(),
+                self.span
+            ));
+        }
+        state.clone()
+    }
+    fn abs_evaluate(&self, state: &mut AbstractState<Self::Q>) -> AbstractState<Self::Q> {
+        *state = backward::refine(self.span, state);
+        let pre = state.clone();
+        self.guard.check_alarms(&mut pre.clone(), self.span);
+        let holds = self.guard.abs_evaluate(&mut pre.clone(), false);
+        let fails = self.guard.abs_evaluate(&mut pre.clone(), true);
+        // definite when no state satisfies the guard, possible when some state violates it
+        let severity = if holds.is_bottom() {
+            Some(Severity::Definite)
+        } else if !fails.is_bottom() {
+            Some(Severity::Possible)
+        } else {
+            None
+        };
+        alarms::report(
+            self.span,
+            AlarmKind::Assertion,
+            self.guard.to_string(),
+            severity,
+            &pre,
+        );
+        // execution continues only in the states where the assertion holds
+        state.is_bottom = holds.is_bottom();
+        state.variables.extend(holds.variables.clone());
+        state.relations = holds.relations.clone();
+        program_points::record(self.span, &pre, state);
+        state.clone()
+    }
+    fn abs_backward(
+        &self,
+        post: &AbstractState<Self::Q>,
+        goal: Option<Span>,
+    ) -> AbstractState<Self::Q> {
+        // executions go on past the assertion only when it holds
+        let mut pre = self.guard.abs_evaluate(&mut post.clone(), false);
+        if goal == Some(self.span) {
+            if let Some(forward) = program_points::lookup(self.span) {
+                pre = pre.state_lub(&self.guard.abs_evaluate(&mut forward.pre.clone(), true));
+            }
+        }
+        let pre = backward::meet_forward(self.span, &pre);
+        backward::record(self.span, &pre);
+        pre
+    }
+    fn check_initialisation(
+        &self,
+        assigned: &Assigned,
+        reads: &mut Vec<UninitialisedRead>,
+    ) -> Assigned {
+        assigned.read(self.guard.extract_variables(), self.span, reads);
+        assigned.clone()
+    }
+    fn check_reachability(
+        &self,
+        reached: &Reachability,
+        reports: &mut Vec<Unreachable>,
+    ) -> Reachability {
+        if let Some(cause) = reachability::enter(self, reached, reports) {
+            return Reachability::Unreachable(cause);
+        }
+        reachability::exit(self.span, || {
+            format!("the assertion {} at {} always fails", self.guard.to_string(), self.span)
+        })
+    }
+    fn iteration_changes(&self, changes: Option<Changes>) -> Option<Changes> {
+        match program_points::lookup(self.span) {
+            Some(point) if point.post.is_bottom() => None,
+            _ => changes,
+        }
+    }
+    fn to_string(&self) -> String {
+        format!("assert ({})", self.guard.to_string())
+    }
+    fn span(&self) -> Span {
+        self.span
+    }
+    fn to_json(&self) -> Json {
+        Json::statement(
+            "assert",
+            self,
+            vec![("guard", Json::Str(self.guard.to_string()))],
+        )
+    }
 }
@@ -0,0 +1,269 @@
+// Backward analysis (--backward, --postcondition "guard")
+//
+// Starting from a goal, the failure of an assertion or a postcondition holding at the end of the
+// program, every statement maps the states after it that reach the goal to the states before it
+// that can reach the goal. The result over-approximates the initial states that can reach the
+// goal: an initial state outside it cannot. The forward analysis runs first and every backward
+// state is intersected with the forward one recorded at the same point.
+//
+// Forward-backward refinement (--refine rounds) alternates the two: the backward pass from the end
+// of the program, with every assertion holding, constrains the states before every statement in
+// the next forward pass, until neither changes. The refined states describe the executions that
+// reach the end of the program without failing an assertion.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::abstract_domain::AbstractDomain;
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::ast::arithmetic::ArithmeticExpression;
+use crate::lexer::Span;
+use crate::program_points;
+use crate::{M, N};
+
+// What the backward analysis starts from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Goal {
+    AssertionFailure(Span), // the assertion at the span fails
+    Postcondition(String),  // the program ends in a state satisfying the guard
+}
+
+impl std::fmt::Display for Goal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Goal::AssertionFailure(span) => write!(f, "assertion at {} fails", span),
+            Goal::Postcondition(guard) => write!(f, "postcondition {} holds", guard),
+        }
+    }
+}
+
+impl Goal {
+    // Span of the assertion whose failure is the goal
+    pub fn assertion(&self) -> Option<Span> {
+        match self {
+            Goal::AssertionFailure(span) => Some(*span),
+            Goal::Postcondition(_) => None,
+        }
+    }
+}
+
+// Necessary precondition of a goal: at the program entry and before every statement
+#[derive(Debug, Clone)]
+pub struct Result {
+    pub goal: Goal,
+    pub precondition: AbstractState<AbstractInterval>,
+    pub points: Vec<(Span, AbstractState<AbstractInterval>)>,
+}
+
+type States = HashMap<Span, AbstractState<AbstractInterval>>;
+
+lazy_static! {
+    // Backward states of the goal being analysed, before each statement and at each loop head
+    static ref POINTS: Mutex<States> = Mutex::new(HashMap::new());
+    static ref HEADS: Mutex<States> = Mutex::new(HashMap::new());
+    static ref RESULTS: Mutex<Vec<Result>> = Mutex::new(Vec::new());
+    // Backward states of the last refinement round, constraining the forward pass
+    static ref CONSTRAINTS: Mutex<States> = Mutex::new(HashMap::new());
+    static ref HEAD_CONSTRAINTS: Mutex<States> = Mutex::new(HashMap::new());
+    static ref REFINEMENT: Mutex<Option<Refinement>> = Mutex::new(None);
+}
+
+// Outcome of the forward-backward refinement
+#[derive(Debug, Clone, Copy)]
+pub struct Refinement {
+    pub rounds: u64,  // backward and forward passes run after the first forward one
+    pub stable: bool, // false when --refine rounds ran out first
+}
+
+// Records the backward state before the statement at `span`, later visits overwrite earlier ones
+pub fn record(span: Span, state: &AbstractState<AbstractInterval>) {
+    if span.is_synthetic() {
+        return;
+    }
+    POINTS
+        .lock()
+        .expect("failed to lock backward states")
+        .insert(span, state.clone());
+}
+
+// Records the backward invariant of the loop at `span`
+pub fn record_head(span: Span, state: &AbstractState<AbstractInterval>) {
+    HEADS
+        .lock()
+        .expect("failed to lock backward loop heads")
+        .insert(span, state.clone());
+}
+
+// Closes the analysis of `goal`, whose precondition at the program entry is `precondition`
+pub fn finish(goal: Goal, precondition: AbstractState<AbstractInterval>) -> Result {
+    let mut points: Vec<(Span, AbstractState<AbstractInterval>)> = POINTS
+        .lock()
+        .expect("failed to lock backward states")
+        .drain()
+        .collect();
+    points.sort_by_key(|(span, _)| *span);
+    HEADS.lock().expect("failed to lock backward loop heads").clear();
+    let result = Result {
+        goal,
+        precondition,
+        points,
+    };
+    RESULTS
+        .lock()
+        .expect("failed to lock backward results")
+        .push(result.clone());
+    result
+}
+
+pub fn results() -> Vec<Result> {
+    RESULTS.lock().expect("failed to lock backward results").clone()
+}
+
+// The states of the backward pass just run become the constraints of the next forward pass.
+// Returns whether they changed since the previous round.
+pub fn constrain() -> bool {
+    let points = std::mem::take(&mut *POINTS.lock().expect("failed to lock backward states"));
+    let heads = std::mem::take(&mut *HEADS.lock().expect("failed to lock backward loop heads"));
+    let mut constraints = CONSTRAINTS.lock().expect("failed to lock backward constraints");
+    let mut head_constraints = HEAD_CONSTRAINTS
+        .lock()
+        .expect("failed to lock backward constraints");
+    let changed = *constraints != points || *head_constraints != heads;
+    *constraints = points;
+    *head_constraints = heads;
+    changed
+}
+
+// Ends the refinement, forward passes are no longer constrained
+pub fn release() {
+    CONSTRAINTS.lock().expect("failed to lock backward constraints").clear();
+    HEAD_CONSTRAINTS
+        .lock()
+        .expect("failed to lock backward constraints")
+        .clear();
+}
+
+// State before the statement at `span` met with the constraint of the refinement, if any
+pub fn refine(
+    span: Span,
+    state: &AbstractState<AbstractInterval>,
+) -> AbstractState<AbstractInterval> {
+    let constraints = CONSTRAINTS.lock().expect("failed to lock backward constraints");
+    restrict(constraints.get(&span), state)
+}
+
+// Loop head state of the loop at `span` met with the constraint of the refinement, if any
+pub fn refine_head(
+    span: Span,
+    state: &AbstractState<AbstractInterval>,
+) -> AbstractState<AbstractInterval> {
+    let constraints = HEAD_CONSTRAINTS
+        .lock()
+        .expect("failed to lock backward constraints");
+    restrict(constraints.get(&span), state)
+}
+
+// Only the variables already defined in `state` are refined
+fn restrict(
+    constraint: Option<&AbstractState<AbstractInterval>>,
+    state: &AbstractState<AbstractInterval>,
+) -> AbstractState<AbstractInterval> {
+    let Some(constraint) = constraint else {
+        return state.clone();
+    };
+    if constraint.is_bottom() {
+        return state.bottom();
+    }
+    let mut refined = state.clone();
+    for (name, domain) in &constraint.variables {
+        if refined.variables.contains_key(name) {
+            refined = refined.update_interval(name, domain.value);
+        }
+    }
+    refined
+}
+
+pub fn record_refinement(refinement: Refinement) {
+    *REFINEMENT.lock().expect("failed to lock refinement") = Some(refinement);
+}
+
+pub fn refinement() -> Option<Refinement> {
+    *REFINEMENT.lock().expect("failed to lock refinement")
+}
+
+fn unknown() -> AbstractInterval {
+    let m = *M.lock().expect("failed to lock m mutex");
+    let n = *N.lock().expect("failed to lock n mutex");
+    AbstractInterval::new(m, n)
+}
+
+// Intersection with the forward state before the statement at `span`: only states the program
+// can be in there are kept, and the variables defined there are all present
+pub fn meet_forward(
+    span: Span,
+    state: &AbstractState<AbstractInterval>,
+) -> AbstractState<AbstractInterval> {
+    let Some(forward) = program_points::lookup(span) else {
+        return state.clone();
+    };
+    meet(&forward.pre, state)
+}
+
+// Intersection with a forward state, e.g. a loop invariant
+pub fn meet(
+    forward: &AbstractState<AbstractInterval>,
+    state: &AbstractState<AbstractInterval>,
+) -> AbstractState<AbstractInterval> {
+    let mut met = state.clone();
+    for (name, domain) in &forward.variables {
+        let value = match state.variables.get(name) {
+            Some(current) => current.value.intersect(&domain.value),
+            None => domain.value,
+        };
+        met.variables.insert(name.clone(), AbstractDomain::new(value));
+    }
+    // the relations of the forward states hold in the intersection, those carried back from later
+    // points may not
+    met.relations = forward.relations.clone();
+    if forward.is_bottom() {
+        met.is_bottom = true;
+    }
+    met
+}
+
+// States before `variable := expression` that lead to `post`: the variable is unconstrained
+// before the assignment, and the expression must evaluate in its interval after it
+pub fn assign(
+    variable: &str,
+    expression: &dyn ArithmeticExpression<Q = AbstractInterval>,
+    span: Span,
+    post: &AbstractState<AbstractInterval>,
+) -> AbstractState<AbstractInterval> {
+    let target = post
+        .variables
+        .get(variable)
+        .map_or_else(unknown, |domain| domain.value);
+    let mut pre = post.clone();
+    pre.variables.remove(variable);
+    pre.forget(variable);
+    let mut pre = meet_forward(span, &pre);
+    for read in expression.extract_variables() {
+        if !pre.variables.contains_key(&read.value) {
+            pre.variables
+                .insert(read.value.clone(), AbstractDomain::new(unknown()));
+        }
+    }
+    if pre.is_bottom() || target.is_bottom() {
+        return pre.bottom();
+    }
+    let mut var_leaves = HashMap::new();
+    let tree = expression.to_ast(&mut pre.clone(), &mut var_leaves);
+    if !tree.backward_analysis(target, &mut var_leaves) {
+        return pre.bottom();
+    }
+    for (name, value) in var_leaves {
+        pre = pre.update_interval(&name, value);
+    }
+    pre
+}
@@ -0,0 +1,102 @@
+// Boolean variables: `b := x < y`, then `b` wherever a guard goes, e.g. `if (b) then { .. }`
+//
+// A variable is boolean when the program assigns it a boolean expression (a comparison, true,
+// false, a connective or another boolean variable), it cannot hold an integer as well. A concrete
+// run keeps it in the state as 1 (true) or 0 (false). Its abstract domain is the part of the
+// intervals within [0, 1]: ⊥, [0, 0] (false), [1, 1] (true) and [0, 1] (either), of finite
+// height, so widening a boolean is joining it.
+//
+// The abstract state also relates a boolean to the guard it was assigned, e.g. b to x < y: until b
+// or a variable read by the guard is assigned again, b holds exactly where the guard does, and a
+// test of b refines the state as the guard would. A join keeps the relations both sides agree on.
+// Guards with increments are not related: evaluating them again would change the state.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use crate::abstract_interval::AbstractInterval;
+use crate::ast::boolean::BooleanExpression;
+
+// Guard of a relation and the variables it reads
+struct Related {
+    guard: Box<dyn BooleanExpression<Q = AbstractInterval>>,
+    reads: Vec<String>,
+}
+
+lazy_static! {
+    static ref DECLARED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+}
+
+// Guards are trait objects, which cannot be shared between threads: the relations live in thread
+// local storage rather than behind a Mutex
+thread_local! {
+    static GUARDS: RefCell<Vec<Related>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn declare(name: &str) {
+    DECLARED
+        .lock()
+        .expect("failed to lock boolean variables")
+        .insert(name.to_string());
+}
+
+pub fn declared(name: &str) -> bool {
+    DECLARED
+        .lock()
+        .expect("failed to lock boolean variables")
+        .contains(name)
+}
+
+// Abstract value of a boolean assigned a guard, given whether some states satisfy the guard and
+// whether some do not
+pub fn value(holds: bool, fails: bool) -> AbstractInterval {
+    match (holds, fails) {
+        (true, true) => AbstractInterval::new(0, 1),
+        (true, false) => AbstractInterval::new(1, 1),
+        (false, true) => AbstractInterval::new(0, 0),
+        (false, false) => AbstractInterval::Bottom,
+    }
+}
+
+// Relation of the booleans assigned `guard`, None when the guard cannot be evaluated again
+pub fn relate(guard: &dyn BooleanExpression<Q = AbstractInterval>) -> Option<usize> {
+    let text = guard.to_string();
+    if text.contains("++") || text.contains("--") {
+        return None;
+    }
+    let reads = guard
+        .extract_variables()
+        .into_iter()
+        .map(|variable| variable.value.clone())
+        .collect();
+    GUARDS.with(|guards| {
+        let mut guards = guards.borrow_mut();
+        // assignments of the same guard share the relation, which then survives their joins
+        if let Some(relation) = guards
+            .iter()
+            .position(|related| related.guard.to_string() == text)
+        {
+            return Some(relation);
+        }
+        guards.push(Related {
+            guard: guard.clone_box(),
+            reads,
+        });
+        Some(guards.len() - 1)
+    })
+}
+
+pub fn guard(relation: usize) -> Box<dyn BooleanExpression<Q = AbstractInterval>> {
+    GUARDS.with(|guards| guards.borrow()[relation].guard.clone_box())
+}
+
+// Whether assigning `variable` breaks the relation
+pub fn reads(relation: usize, variable: &str) -> bool {
+    GUARDS.with(|guards| {
+        guards.borrow()[relation]
+            .reads
+            .iter()
+            .any(|read| read == variable)
+    })
+}
@@ -0,0 +1,242 @@
+// Loop iteration bounds and the cost of the program
+//
+// Every loop has an implicit counter c of the executions of its body, 0 when the loop is entered.
+// Its final value is bounded with the ranking function f of the loop found by the termination
+// checker: f is at least L where the body is entered and every iteration decreases it by d, so
+// from the value f0 of f at the loop entry c <= (f0 - L) / d + 1. The bound is reported as an
+// interval, and symbolically with the variables having a single value at the loop entry replaced
+// by it. The cost of the program is the number of executions of loop bodies: the counter of a
+// loop times the counters of the loops around it, summed over the loops.
+
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::ast::boolean::BooleanExpression;
+use crate::ast::statement::Statement;
+use crate::lexer::Span;
+use crate::procedures;
+use crate::termination::{self, Changes, Linear};
+
+// Interval of a counter, the upper bound None when unbounded, and the upper bound as an
+// expression over the variables at the loop entry, None when unbounded
+#[derive(Debug, Clone, PartialEq)]
+pub struct Counter {
+    pub lower: i128,
+    pub upper: Option<i128>,
+    pub bound: Option<String>,
+}
+
+impl Counter {
+    pub fn exactly(value: i128) -> Self {
+        Counter {
+            lower: value,
+            upper: Some(value),
+            bound: Some(value.to_string()),
+        }
+    }
+
+    // Executions of a body running `self` times for each of the `other` executions of its loop
+    pub fn times(&self, other: &Counter) -> Self {
+        let zero = |counter: &Counter| counter.upper == Some(0);
+        if zero(self) || zero(other) {
+            return Counter::exactly(0);
+        }
+        let bound = match (self.bound.as_deref(), other.bound.as_deref()) {
+            (Some("1"), bound) | (bound, Some("1")) => bound.map(str::to_string),
+            (Some(left), Some(right)) => Some(format!("{} * {}", factor(left), factor(right))),
+            _ => None,
+        };
+        Counter {
+            lower: self.lower.saturating_mul(other.lower),
+            upper: self
+                .upper
+                .zip(other.upper)
+                .and_then(|(left, right)| left.checked_mul(right)),
+            bound,
+        }
+    }
+
+    pub fn plus(&self, other: &Counter) -> Self {
+        let bound = match (self.bound.as_deref(), other.bound.as_deref()) {
+            (Some("0"), bound) | (bound, Some("0")) => bound.map(str::to_string),
+            (Some(left), Some(right)) => Some(format!("{} + {}", left, right)),
+            _ => None,
+        };
+        Counter {
+            lower: self.lower.saturating_add(other.lower),
+            upper: self
+                .upper
+                .zip(other.upper)
+                .and_then(|(left, right)| left.checked_add(right)),
+            bound,
+        }
+    }
+}
+
+// Sums are put in parentheses when multiplied
+fn factor(bound: &str) -> String {
+    let mut depth = 0;
+    let mut sum = false;
+    for (position, c) in bound.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '+' | '-' if depth == 0 && position > 0 => sum = true,
+            _ => {}
+        }
+    }
+    if sum {
+        format!("({})", bound)
+    } else {
+        bound.to_string()
+    }
+}
+
+impl fmt::Display for Counter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.upper {
+            Some(upper) => write!(f, "[{}, {}]", self.lower, upper)?,
+            None => write!(f, "[{}, +∞]", self.lower)?,
+        }
+        match &self.bound {
+            Some(bound) => write!(f, ", bound {}", bound),
+            None => write!(f, ", unbounded"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoopBound {
+    pub span: Span,
+    pub iterations: Counter, // executions of the body each time the loop runs
+    pub executions: Counter, // executions of the body in the whole program
+}
+
+lazy_static! {
+    static ref BOUNDS: Mutex<Vec<LoopBound>> = Mutex::new(Vec::new());
+}
+
+// Counter of the loop that iterates while `guard` holds, entered in `entry`, whose body
+// is entered in the states of `heads` satisfying the guard and changes the variables by `changes`
+// (None when it never completes). `exits` tells whether the forward run found states leaving the
+// loop. The body of a repeat loop runs `at_least_once`, `guard` is then the negation of its exit
+// condition.
+pub fn counter(
+    guard: &dyn BooleanExpression<Q = AbstractInterval>,
+    entry: &AbstractState<AbstractInterval>,
+    heads: &AbstractState<AbstractInterval>,
+    changes: Option<Changes>,
+    exits: bool,
+    at_least_once: bool,
+) -> Counter {
+    if entry.is_bottom() {
+        return Counter::exactly(0);
+    }
+    let lower = if at_least_once || guard.abs_evaluate(&mut entry.clone(), true).is_bottom() {
+        1
+    } else {
+        0
+    };
+    let unbounded = Counter {
+        lower,
+        upper: None,
+        bound: None,
+    };
+    // nothing bounds a loop the forward run never leaves
+    if !exits {
+        return unbounded;
+    }
+    let body_entry = guard.abs_evaluate(&mut heads.clone(), false);
+    let Some(changes) = changes.filter(|_| !body_entry.is_bottom()) else {
+        // the body is not entered again, or it never completes
+        let upper = if body_entry.is_bottom() { lower } else { 1 };
+        return Counter {
+            lower,
+            upper: Some(upper),
+            bound: Some(upper.to_string()),
+        };
+    };
+    let Some(ranking) = termination::ranking(guard, heads, &changes) else {
+        return unbounded;
+    };
+    // every completed iteration decreases the ranking function and reaches the head again, heads
+    // that pin it to one value contradict the changes of the body
+    let at_heads = ranking.function.evaluate(heads);
+    if at_heads.lower.is_some() && at_heads.lower == at_heads.upper {
+        return unbounded;
+    }
+    let (least, decrease) = (ranking.lower, ranking.decrease);
+    let upper = ranking
+        .function
+        .evaluate(entry)
+        .upper
+        .map(|initial| ((initial - least).div_euclid(decrease) + 1).max(lower));
+    let initial = ranking.function.substitute(entry);
+    let bound = i64::try_from(least)
+        .ok()
+        .and_then(|least| initial.plus(&Linear::constant(least), -1))
+        .map(|distance| {
+            if distance.coefficients.is_empty() {
+                let constant = distance.constant as i128;
+                (constant.div_euclid(decrease) + 1).max(lower).to_string()
+            } else if decrease == 1 {
+                match distance.plus(&Linear::constant(1), 1) {
+                    Some(distance) => format!("max({}, {})", lower, distance),
+                    None => format!("max({}, {} + 1)", lower, distance),
+                }
+            } else {
+                format!("max({}, ({}) / {} + 1)", lower, distance, decrease)
+            }
+        });
+    Counter {
+        lower,
+        upper,
+        bound: bound.or_else(|| upper.map(|upper| upper.to_string())),
+    }
+}
+
+// Records the counter of the loop at `span`, run `enclosing` times by the loops around it, and
+// returns the executions of its body
+pub fn record(span: Span, iterations: Counter, enclosing: &Counter) -> Counter {
+    let executions = iterations.times(enclosing);
+    BOUNDS.lock().expect("failed to lock loop bounds").push(LoopBound {
+        span,
+        iterations,
+        executions: executions.clone(),
+    });
+    executions
+}
+
+// Bounds every loop of the program, returns them in source order
+pub fn check(program: &dyn Statement<Q = AbstractInterval>) -> Vec<LoopBound> {
+    BOUNDS.lock().expect("failed to lock loop bounds").clear();
+    program.check_bounds(&Counter::exactly(1));
+    // the calls of a procedure are not counted, its body runs any number of times
+    let calls = Counter {
+        lower: 0,
+        upper: None,
+        bound: None,
+    };
+    for procedure in procedures::all() {
+        if let Some(body) = &procedure.body {
+            body.check_bounds(&calls);
+        }
+    }
+    let mut bounds = all();
+    bounds.sort_by_key(|bound| bound.span);
+    *BOUNDS.lock().expect("failed to lock loop bounds") = bounds.clone();
+    bounds
+}
+
+pub fn all() -> Vec<LoopBound> {
+    BOUNDS.lock().expect("failed to lock loop bounds").clone()
+}
+
+// Executions of loop bodies in the whole program
+pub fn total() -> Counter {
+    all()
+        .iter()
+        .fold(Counter::exactly(0), |total, bound| total.plus(&bound.executions))
+}
@@ -0,0 +1,153 @@
+// Uninitialised reads (definite assignment)
+//
+// A variable is definitely assigned at a point when every path from the program entry to it
+// assigns it, the inputs being assigned at the entry. A read of a variable that is not definitely
+// assigned is reported at the program point of the statement reading it: definite when no path
+// assigns the variable, possible otherwise. Loops may run zero times, so what their bodies assign
+// is not definitely assigned after them.
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::alarms::{self, AlarmKind, Severity};
+use crate::ast::arithmetic::Variable;
+use crate::ast::statement::Statement;
+use crate::lexer::Span;
+use crate::procedures;
+use crate::program_points;
+
+// Variables assigned on every path (must) and on some path (may) to a point
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assigned {
+    pub must: BTreeSet<String>,
+    pub may: BTreeSet<String>,
+}
+
+impl Assigned {
+    pub fn entry(inputs: Vec<String>) -> Self {
+        let inputs: BTreeSet<String> = inputs.into_iter().collect();
+        Assigned {
+            must: inputs.clone(),
+            may: inputs,
+        }
+    }
+
+    pub fn assign(&self, variable: &str) -> Self {
+        let mut assigned = self.clone();
+        assigned.must.insert(variable.to_string());
+        assigned.may.insert(variable.to_string());
+        assigned
+    }
+
+    // Merge of two paths
+    pub fn join(&self, other: &Assigned) -> Self {
+        Assigned {
+            must: self.must.intersection(&other.must).cloned().collect(),
+            may: self.may.union(&other.may).cloned().collect(),
+        }
+    }
+
+    // Checks the variables read by the statement at `span`
+    pub fn read(&self, variables: Vec<&Variable>, span: Span, reads: &mut Vec<UninitialisedRead>) {
+        for variable in variables {
+            let name = &variable.value;
+            if self.must.contains(name) || reads.iter().any(|read| read.is(name, span)) {
+                continue;
+            }
+            reads.push(UninitialisedRead {
+                variable: name.clone(),
+                span,
+                definite: !self.may.contains(name),
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UninitialisedRead {
+    pub variable: String,
+    pub span: Span,
+    pub definite: bool, // no path to the read assigns the variable
+}
+
+impl UninitialisedRead {
+    fn is(&self, variable: &str, span: Span) -> bool {
+        self.variable == variable && self.span == span
+    }
+
+    pub fn severity(&self) -> Severity {
+        if self.definite {
+            Severity::Definite
+        } else {
+            Severity::Possible
+        }
+    }
+}
+
+// Head of a loop entered with `entry` whose body runs `body`: the variables the body may assign
+// may be assigned from the second iteration on, the ones it must assign are not assigned in the
+// first one
+pub fn loop_head(
+    entry: &Assigned,
+    body: impl Fn(&Assigned, &mut Vec<UninitialisedRead>) -> Assigned,
+) -> Assigned {
+    let once = body(entry, &mut Vec::new());
+    entry.join(&once)
+}
+
+lazy_static! {
+    static ref READS: Mutex<Vec<UninitialisedRead>> = Mutex::new(Vec::new());
+}
+
+// Checks the program, the inputs being assigned at its entry, and returns the reads in source order
+pub fn check(
+    program: &dyn Statement<Q = AbstractInterval>,
+    inputs: Vec<String>,
+) -> Vec<UninitialisedRead> {
+    let mut reads = Vec::new();
+    program.check_initialisation(&Assigned::entry(inputs), &mut reads);
+    // a procedure body starts with its parameters assigned, the return reads the result
+    for procedure in procedures::all() {
+        let mut assigned = Assigned::entry(procedure.parameters.clone());
+        if let Some(body) = &procedure.body {
+            assigned = body.check_initialisation(&assigned, &mut reads);
+        }
+        assigned.read(procedure.result.extract_variables(), procedure.return_span, &mut reads);
+    }
+    reads.sort_by(|a, b| (a.span, &a.variable).cmp(&(b.span, &b.variable)));
+    *READS.lock().expect("failed to lock uninitialised reads") = reads.clone();
+    reads
+}
+
+// Raises the alarms of the reads reachable in the abstract run, in the state before the statement
+pub fn report_alarms() {
+    for read in READS.lock().expect("failed to lock uninitialised reads").iter() {
+        let Some(point) = program_points::lookup(read.span) else {
+            continue;
+        };
+        alarms::report(
+            read.span,
+            AlarmKind::Uninitialised,
+            read.variable.clone(),
+            Some(read.severity()),
+            &point.pre,
+        );
+    }
+}
+
+// Raises the alarms of the reads of the return at `span`, in the state reaching it
+pub fn report_return_alarms(span: Span, state: &AbstractState<AbstractInterval>) {
+    for read in READS.lock().expect("failed to lock uninitialised reads").iter() {
+        if read.span == span {
+            alarms::report(
+                read.span,
+                AlarmKind::Uninitialised,
+                read.variable.clone(),
+                Some(read.severity()),
+                state,
+            );
+        }
+    }
+}
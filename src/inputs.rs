@@ -0,0 +1,225 @@
+// Input variables: `input x in [a, b];` and `input x;` declarations, and --input on the command line
+//
+// The inputs make the initial state of both runs. The concrete run takes the value given with
+// --input x=v, the abstract run the interval given with --input x=a..b or x=v, otherwise the
+// declared one, [m, n] when none is declared.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::abstract_domain::AbstractDomain;
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::ast::State;
+use crate::lexer::{Span, Token, TokenType};
+use crate::{syntax_error, usage_error, M, N};
+
+// Input declared in the program
+#[derive(Debug, Clone, PartialEq)]
+pub struct Input {
+    pub name: String,
+    pub range: Option<(i64, i64)>, // None for `input x;`, any value
+    pub span: Span,                // position of the `input` keyword
+}
+
+// Input given on the command line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputValue {
+    Value(i64),      // x=v
+    Range(i64, i64), // x=a..b, abstract runs only
+}
+
+lazy_static! {
+    static ref DECLARED: Mutex<BTreeMap<String, Input>> = Mutex::new(BTreeMap::new());
+    static ref GIVEN: Mutex<BTreeMap<String, InputValue>> = Mutex::new(BTreeMap::new());
+}
+
+// Parses the value of --input, `x=v` or `x=a..b`
+pub fn set(arg: &str) {
+    let Some((name, value)) = arg.split_once('=') else {
+        usage_error(format!("--input expects x=v or x=a..b, found {:?}", arg));
+    };
+    let int = |text: &str| {
+        text.trim().parse::<i64>().unwrap_or_else(|_| {
+            usage_error(format!("--input {}: {:?} is not an integer", arg, text))
+        })
+    };
+    let value = match value.split_once("..") {
+        Some((lower, upper)) => {
+            let (lower, upper) = (int(lower), int(upper));
+            if lower > upper {
+                usage_error(format!("--input {}: empty range", arg));
+            }
+            InputValue::Range(lower, upper)
+        }
+        None => InputValue::Value(int(value)),
+    };
+    GIVEN
+        .lock()
+        .expect("failed to lock given inputs")
+        .insert(name.trim().to_string(), value);
+}
+
+pub fn declare(inputs: &[Input]) {
+    let mut declared = DECLARED.lock().expect("failed to lock declared inputs");
+    for input in inputs {
+        declared.insert(input.name.clone(), input.clone());
+    }
+}
+
+// Removes the input declarations from the tokens of the program, the spans of the remaining
+// tokens are those of the source
+pub fn extract(tokens: Vec<Token>) -> (Vec<Token>, Vec<Input>) {
+    let mut remaining = Vec::with_capacity(tokens.len());
+    let mut inputs = Vec::new();
+    let mut tokens = tokens.into_iter();
+    while let Some(token) = tokens.next() {
+        if token.token_ty == TokenType::Input {
+            inputs.push(parse(&mut tokens, token.span));
+        } else {
+            remaining.push(token);
+        }
+    }
+    (remaining, inputs)
+}
+
+// `x in [a, b];` or `x;` after the keyword `input` at `span`
+fn parse(tokens: &mut impl Iterator<Item = Token>, span: Span) -> Input {
+    let name = match tokens.next() {
+        Some(Token {
+            token_ty: TokenType::Identifier(name),
+            ..
+        }) => name,
+        other => malformed(span, other),
+    };
+    let range = match tokens.next() {
+        Some(token) if token.token_ty == TokenType::Semicolon => None,
+        Some(token) if token.token_ty == TokenType::In => {
+            expect(tokens, TokenType::SBra, span);
+            let lower = integer(tokens, span);
+            expect(tokens, TokenType::Comma, span);
+            let upper = integer(tokens, span);
+            expect(tokens, TokenType::SKet, span);
+            expect(tokens, TokenType::Semicolon, span);
+            if lower > upper {
+                syntax_error(format!(
+                    "input declaration at {} has the empty range [{}, {}]",
+                    span, lower, upper
+                ));
+            }
+            Some((lower, upper))
+        }
+        other => malformed(span, other),
+    };
+    Input { name, range, span }
+}
+
+fn expect(tokens: &mut impl Iterator<Item = Token>, expected: TokenType, span: Span) {
+    match tokens.next() {
+        Some(token) if token.token_ty == expected => {}
+        other => malformed(span, other),
+    }
+}
+
+// Integer literal, possibly negative
+fn integer(tokens: &mut impl Iterator<Item = Token>, span: Span) -> i64 {
+    match tokens.next() {
+        Some(Token {
+            token_ty: TokenType::Number(value),
+            ..
+        }) => value,
+        Some(token) if token.token_ty == TokenType::Minus => match tokens.next() {
+            Some(Token {
+                token_ty: TokenType::Number(value),
+                ..
+            }) => -value,
+            other => malformed(span, other),
+        },
+        other => malformed(span, other),
+    }
+}
+
+fn malformed(span: Span, found: Option<Token>) -> ! {
+    let found = match found {
+        Some(token) => format!("{:?} at {}", token.value, token.span),
+        None => "the end of the program".to_string(),
+    };
+    syntax_error(format!(
+        "input declaration at {} is not `input x;` or `input x in [a, b];`, found {}",
+        span, found
+    ))
+}
+
+// Every input, declared or given, by name
+pub fn names() -> Vec<String> {
+    let declared = DECLARED.lock().expect("failed to lock declared inputs");
+    let given = GIVEN.lock().expect("failed to lock given inputs");
+    let mut names: Vec<String> = declared.keys().chain(given.keys()).cloned().collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+// Initial state of the concrete run: every input needs a value within its declared range
+pub fn concrete_state() -> State {
+    let mut state = State::new();
+    for name in names() {
+        let declared = DECLARED
+            .lock()
+            .expect("failed to lock declared inputs")
+            .get(&name)
+            .cloned();
+        let given = GIVEN
+            .lock()
+            .expect("failed to lock given inputs")
+            .get(&name)
+            .copied();
+        let value = match (given, declared.as_ref().and_then(|input| input.range)) {
+            (Some(InputValue::Value(value)), _) => value,
+            (_, Some((lower, upper))) if lower == upper => lower,
+            _ => usage_error(format!(
+                "the concrete run needs a value for input {}, give it with --input {}=v",
+                name, name
+            )),
+        };
+        if let Some((lower, upper)) = declared.and_then(|input| input.range) {
+            if value < lower || value > upper {
+                usage_error(format!(
+                    "input {}={} is out of its declared range [{}, {}]",
+                    name, value, lower, upper
+                ));
+            }
+        }
+        state.insert(name, value);
+    }
+    state
+}
+
+// Initial state of the abstract run, intervals are kept within [m, n]
+pub fn abstract_state() -> AbstractState<AbstractInterval> {
+    let m = *M.lock().expect("failed to lock m mutex");
+    let n = *N.lock().expect("failed to lock n mutex");
+    let mut state = AbstractState::new();
+    for name in names() {
+        let declared = DECLARED
+            .lock()
+            .expect("failed to lock declared inputs")
+            .get(&name)
+            .and_then(|input| input.range);
+        let given = GIVEN
+            .lock()
+            .expect("failed to lock given inputs")
+            .get(&name)
+            .copied();
+        let (lower, upper) = match given {
+            Some(InputValue::Value(value)) => (value, value),
+            Some(InputValue::Range(lower, upper)) => (lower, upper),
+            None => declared.unwrap_or((m, n)),
+        };
+        state.variables.insert(
+            name,
+            AbstractDomain::new(AbstractInterval::new(lower.clamp(m, n), upper.clamp(m, n))),
+        );
+    }
+    state
+}
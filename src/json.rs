@@ -0,0 +1,576 @@
+// JSON output (--format json)
+//
+// Schema of the report, every list is in source order and every variable map is sorted by name:
+//
+// {
+//   "analysis": "abstract" | "concrete",
+//   "bounds": {"m": int, "n": int},               interval bounds standing for -∞ and +∞
+//   "thresholds": [int],                          widening thresholds, empty in concrete runs
+//   "program": statement,
+//   "procedures": [{"name": string, "parameters": [string], "span": span, "body": statement | null,
+//                   "return": string, "summaries": [{"arguments": [interval], "result": interval}]}],
+//                 summaries are the results of the procedure in every calling context the abstract
+//                 run analysed, empty in concrete runs
+//   "points": [{"span": span, "pre": state, "post": state}],
+//   "invariants": [{"span": span, "unrolled": [{"iteration": int, "state": state}], "state": state}],
+//                 unrolled are the loop head states of the first iterations (--unroll, @unroll),
+//                 state is the invariant of the remaining ones
+//   "descending": {"mode": "narrowing" | "decreasing" | "none", "steps": int,
+//                  "loops": [{"span": span, "steps": [{"refined": {name: {"before": interval,
+//                                                                         "after": interval}}}]}]},
+//                 one entry per descending step that changed the loop invariant, in order
+//   "alarms": [{"kind": "division-by-zero" | "overflow" | "assertion" | "uninitialised-read"
+//                       | "out-of-bounds" | "invalid-shift",
+//               "severity": "definite" | "possible",
+//               "span": span, "expression": string, "state": state}],
+//   "unreachable": [{"span": span, "statement": string, "cause": string}],
+//                 statements whose entry state is ⊥, cause names the guard or statement responsible
+//   "termination": [{"span": span, "verdict": "terminates" | "may not terminate" | "unknown",
+//                    "ranking_function": string | null}],
+//                 one entry per reachable loop
+//   "cost": {"loops": [{"span": span, "iterations": counter, "executions": counter}],
+//            "total": counter} | null,
+//                 iterations bounds the executions of a loop body each time the loop runs,
+//                 executions those in the whole run, total sums them over the loops; null in
+//                 concrete runs
+//   "initial_state": state,                       the inputs (input declarations, --input)
+//   "final_state": state,                         {"variables": {name: int}} in concrete runs
+//   "backward": [{"goal": string, "precondition": state, "points": [{"span": span, "state": state}]}],
+//                 necessary preconditions (--backward, --postcondition): the initial states, and the
+//                 states before every statement, that can reach the goal
+//   "refinement": {"rounds": int, "stable": bool} | null,
+//                 forward-backward refinement (--refine), points and invariants are the refined ones
+//   "output": [{"span": span, "values": [int]}] | [{"span": span, "values": [interval]}],
+//                 print statements: the lines a concrete run writes, in order, or the intervals of
+//                 the arguments of every reachable print in the abstract run, in source order
+//   "partitions": [{"trace": [string], "state": state}],
+//                 final states by trace (--partition), tags are "then@span", "else@span",
+//                 "exit@span" and "exit@span#i" for the exit of the unrolled iteration i,
+//                 "break@span" for the states leaving a loop through its break statements,
+//                 "either@span" and "or@span" for the branches of a choice, "case@span#k" and
+//                 "default@span" for the branches of a switch
+//   "statistics": {"statements": int, "loop_iterations": int, "widenings": int,
+//                  "narrowings": int, "capped_loops": [span], "duration_ms": int}
+//                 capped_loops are the loops that hit --max-iterations and were widened by force
+// }
+//
+// statement: {"kind": "assign" | "skip" | "sequence" | "if" | "while" | "for" | "repeat" | "assert"
+//                     | "array" | "array-assign" | "either" | "break" | "continue" | "local"
+//                     | "print" | "switch",
+//             "span": span, "text": string, ...} plus the sub-statements of the kind
+//             (sequence: "first", "second"; if: "guard", "then", "else";
+//              while: "guard", "body"; for: "init", "guard", "increment", "body";
+//              repeat: "body", "guard"; assign: "variable", "expression"; assert: "guard";
+//              array: "array", "size"; array-assign: "array", "index", "expression";
+//              either: "first", "second"; local: "variable", "init", "body";
+//              print: "arguments"; switch: "selector", "cases" ([{"label": int, "body": statement}]),
+//              "default")
+// span: {"line": int, "column": int}, line 0 marks statements built by the parser
+// state: {"bottom": bool, "variables": {name: interval}}
+// interval: {"lower": int | null, "upper": int | null}, null bounds are infinite, ⊥ is null
+// counter: {"lower": int, "upper": int | null, "bound": string | null}, bound is the upper bound
+//          over the variables at the loop entry, null when unbounded
+
+use std::fmt::Write;
+
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::alarms;
+use crate::ast::statement::Statement;
+use crate::ast::State;
+use crate::backward;
+use crate::cost::{self, Counter};
+use crate::inputs;
+use crate::lexer::Span;
+use crate::output;
+use crate::partitioning::Partition;
+use crate::procedures::{self, Summary};
+use crate::program_points;
+use crate::reachability;
+use crate::termination::{self, Verdict};
+use crate::thresholds;
+use crate::{ANALYSIS_FLAG, DESCENDING_MODE, DESCENDING_STEPS, M, N, NARROWING_FLAG};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>), // keys keep their insertion order
+}
+
+impl Json {
+    pub fn object(fields: Vec<(&str, Json)>) -> Json {
+        Json::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    pub fn str(value: &str) -> Json {
+        Json::Str(value.to_string())
+    }
+
+    pub fn span(span: Span) -> Json {
+        Json::object(vec![
+            ("line", Json::Int(span.line as i64)),
+            ("column", Json::Int(span.column as i64)),
+        ])
+    }
+
+    pub fn interval(interval: AbstractInterval) -> Json {
+        let m = *M.lock().expect("failed to lock m mutex");
+        let n = *N.lock().expect("failed to lock n mutex");
+        match interval {
+            AbstractInterval::Bottom => Json::Null,
+            AbstractInterval::Top => {
+                Json::object(vec![("lower", Json::Null), ("upper", Json::Null)])
+            }
+            AbstractInterval::Bounded { lower, upper } => Json::object(vec![
+                ("lower", if lower == m { Json::Null } else { Json::Int(lower) }),
+                ("upper", if upper == n { Json::Null } else { Json::Int(upper) }),
+            ]),
+        }
+    }
+
+    pub fn state(state: &AbstractState<AbstractInterval>) -> Json {
+        let variables = state
+            .sorted_variables()
+            .into_iter()
+            .map(|(name, domain)| (name.clone(), Json::interval(domain.value)))
+            .collect();
+        Json::object(vec![
+            ("bottom", Json::Bool(state.is_bottom())),
+            ("variables", Json::Object(variables)),
+        ])
+    }
+
+    pub fn concrete_state(state: &State) -> Json {
+        let mut variables: Vec<(&String, &i64)> = state.iter().collect();
+        variables.sort();
+        Json::object(vec![(
+            "variables",
+            Json::Object(
+                variables
+                    .into_iter()
+                    .map(|(name, value)| (name.clone(), Json::Int(*value)))
+                    .collect(),
+            ),
+        )])
+    }
+
+    // Statement node of the "program" tree, `children` are the kind specific fields
+    pub fn statement(
+        kind: &str,
+        statement: &dyn Statement<Q = AbstractInterval>,
+        children: Vec<(&str, Json)>,
+    ) -> Json {
+        let mut fields = vec![
+            ("kind", Json::str(kind)),
+            ("span", Json::span(statement.span())),
+            ("text", Json::Str(statement.to_string())),
+        ];
+        fields.extend(children);
+        Json::object(fields)
+    }
+
+    // Indented rendering, two spaces per level
+    pub fn pretty(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        out
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Bool(value) => out.push_str(&value.to_string()),
+            Json::Int(value) => out.push_str(&value.to_string()),
+            Json::Str(value) => write_string(out, value),
+            Json::Array(items) if items.is_empty() => out.push_str("[]"),
+            Json::Object(fields) if fields.is_empty() => out.push_str("{}"),
+            Json::Array(items) => {
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    out.push_str(&"  ".repeat(indent + 1));
+                    item.write(out, indent + 1);
+                    out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&"  ".repeat(indent));
+                out.push(']');
+            }
+            Json::Object(fields) => {
+                out.push_str("{\n");
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    out.push_str(&"  ".repeat(indent + 1));
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                    out.push_str(if i + 1 < fields.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&"  ".repeat(indent));
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// Whole report of an abstract run, see the schema at the top of the file
+pub fn abstract_report(
+    program: &dyn Statement<Q = AbstractInterval>,
+    final_state: &AbstractState<AbstractInterval>,
+    partitions: &[Partition<AbstractInterval>],
+    duration_ms: u128,
+) -> Json {
+    let partitions = partitions
+        .iter()
+        .map(|partition| {
+            Json::object(vec![
+                (
+                    "trace",
+                    Json::Array(partition.trace.iter().map(|tag| Json::str(tag)).collect()),
+                ),
+                ("state", Json::state(&partition.state)),
+            ])
+        })
+        .collect();
+    let points = program_points::all()
+        .into_iter()
+        .map(|(span, states)| {
+            Json::object(vec![
+                ("span", Json::span(span)),
+                ("pre", Json::state(&states.pre)),
+                ("post", Json::state(&states.post)),
+            ])
+        })
+        .collect();
+    let invariants = program_points::invariants()
+        .into_iter()
+        .map(|(span, invariant)| {
+            let unrolled = invariant
+                .unrolled
+                .iter()
+                .enumerate()
+                .map(|(i, state)| {
+                    Json::object(vec![
+                        ("iteration", Json::Int(i as i64 + 1)),
+                        ("state", Json::state(state)),
+                    ])
+                })
+                .collect();
+            Json::object(vec![
+                ("span", Json::span(span)),
+                ("unrolled", Json::Array(unrolled)),
+                ("state", Json::state(&invariant.invariant)),
+            ])
+        })
+        .collect();
+    let alarms = alarms::all()
+        .into_iter()
+        .map(|alarm| {
+            Json::object(vec![
+                ("kind", Json::str(alarm.kind.rule_id())),
+                ("severity", Json::Str(alarm.severity.to_string())),
+                ("span", Json::span(alarm.span)),
+                ("expression", Json::Str(alarm.expression.clone())),
+                ("state", Json::state(&alarm.state)),
+            ])
+        })
+        .collect();
+    Json::object(vec![
+        ("analysis", Json::str("abstract")),
+        ("bounds", bounds()),
+        (
+            "thresholds",
+            Json::Array(thresholds::all().into_iter().map(Json::Int).collect()),
+        ),
+        ("program", program.to_json()),
+        ("procedures", procedures(&procedures::summaries())),
+        ("points", Json::Array(points)),
+        ("invariants", Json::Array(invariants)),
+        ("descending", descending()),
+        ("alarms", Json::Array(alarms)),
+        ("unreachable", unreachable()),
+        ("termination", termination()),
+        ("cost", cost()),
+        ("initial_state", Json::state(&inputs::abstract_state())),
+        ("final_state", Json::state(final_state)),
+        ("backward", backward()),
+        ("refinement", refinement()),
+        (
+            "output",
+            Json::Array(
+                output::printed()
+                    .into_iter()
+                    .map(|printed| {
+                        Json::object(vec![
+                            ("span", Json::span(printed.span)),
+                            (
+                                "values",
+                                Json::Array(printed.values.into_iter().map(Json::interval).collect()),
+                            ),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        ("partitions", Json::Array(partitions)),
+        ("statistics", statistics(duration_ms)),
+    ])
+}
+
+// Report of a concrete run: no program points, invariants or alarms
+pub fn concrete_report(
+    program: &dyn Statement<Q = AbstractInterval>,
+    final_state: &State,
+    duration_ms: u128,
+) -> Json {
+    Json::object(vec![
+        ("analysis", Json::str("concrete")),
+        ("bounds", bounds()),
+        ("thresholds", Json::Array(Vec::new())),
+        ("program", program.to_json()),
+        ("procedures", procedures(&[])),
+        ("points", Json::Array(Vec::new())),
+        ("invariants", Json::Array(Vec::new())),
+        ("descending", descending()),
+        ("alarms", Json::Array(Vec::new())),
+        ("unreachable", Json::Array(Vec::new())),
+        ("termination", Json::Array(Vec::new())),
+        ("cost", Json::Null),
+        ("initial_state", Json::concrete_state(&inputs::concrete_state())),
+        ("final_state", Json::concrete_state(final_state)),
+        ("backward", Json::Array(Vec::new())),
+        ("refinement", Json::Null),
+        (
+            "output",
+            Json::Array(
+                output::written()
+                    .into_iter()
+                    .map(|written| {
+                        Json::object(vec![
+                            ("span", Json::span(written.span)),
+                            (
+                                "values",
+                                Json::Array(written.values.into_iter().map(Json::Int).collect()),
+                            ),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        ("partitions", Json::Array(Vec::new())),
+        ("statistics", statistics(duration_ms)),
+    ])
+}
+
+fn procedures(summaries: &[Summary]) -> Json {
+    let procedures = procedures::all()
+        .iter()
+        .map(|procedure| {
+            let summaries = summaries
+                .iter()
+                .filter(|summary| summary.procedure == procedure.name)
+                .map(|summary| {
+                    Json::object(vec![
+                        (
+                            "arguments",
+                            Json::Array(summary.arguments.iter().copied().map(Json::interval).collect()),
+                        ),
+                        ("result", Json::interval(summary.result)),
+                    ])
+                })
+                .collect();
+            Json::object(vec![
+                ("name", Json::Str(procedure.name.clone())),
+                (
+                    "parameters",
+                    Json::Array(procedure.parameters.iter().map(|name| Json::str(name)).collect()),
+                ),
+                ("span", Json::span(procedure.span)),
+                ("body", procedure.body.as_ref().map_or(Json::Null, |body| body.to_json())),
+                ("return", Json::Str(procedure.result.to_string())),
+                ("summaries", Json::Array(summaries)),
+            ])
+        })
+        .collect();
+    Json::Array(procedures)
+}
+
+fn bounds() -> Json {
+    Json::object(vec![
+        ("m", Json::Int(*M.lock().expect("failed to lock m mutex"))),
+        ("n", Json::Int(*N.lock().expect("failed to lock n mutex"))),
+    ])
+}
+
+fn unreachable() -> Json {
+    Json::Array(
+        reachability::all()
+            .into_iter()
+            .map(|unreachable| {
+                Json::object(vec![
+                    ("span", Json::span(unreachable.span)),
+                    ("statement", Json::Str(unreachable.statement)),
+                    ("cause", Json::Str(unreachable.cause)),
+                ])
+            })
+            .collect(),
+    )
+}
+
+fn termination() -> Json {
+    Json::Array(
+        termination::all()
+            .into_iter()
+            .map(|termination| {
+                let (verdict, ranking_function) = match termination.verdict {
+                    Verdict::Terminates(ranking) => ("terminates", Json::Str(ranking)),
+                    Verdict::MayNotTerminate => ("may not terminate", Json::Null),
+                    Verdict::Unknown => ("unknown", Json::Null),
+                };
+                Json::object(vec![
+                    ("span", Json::span(termination.span)),
+                    ("verdict", Json::str(verdict)),
+                    ("ranking_function", ranking_function),
+                ])
+            })
+            .collect(),
+    )
+}
+
+fn cost() -> Json {
+    let counter = |counter: Counter| {
+        Json::object(vec![
+            ("lower", int(counter.lower)),
+            ("upper", counter.upper.map_or(Json::Null, int)),
+            ("bound", counter.bound.map_or(Json::Null, Json::Str)),
+        ])
+    };
+    let loops = cost::all()
+        .into_iter()
+        .map(|bound| {
+            Json::object(vec![
+                ("span", Json::span(bound.span)),
+                ("iterations", counter(bound.iterations)),
+                ("executions", counter(bound.executions)),
+            ])
+        })
+        .collect();
+    Json::object(vec![("loops", Json::Array(loops)), ("total", counter(cost::total()))])
+}
+
+// Counters beyond i64 are unbounded in practice
+fn int(value: i128) -> Json {
+    i64::try_from(value).map_or(Json::Null, Json::Int)
+}
+
+fn backward() -> Json {
+    Json::Array(
+        backward::results()
+            .into_iter()
+            .map(|result| {
+                let points = result
+                    .points
+                    .iter()
+                    .map(|(span, state)| {
+                        Json::object(vec![("span", Json::span(*span)), ("state", Json::state(state))])
+                    })
+                    .collect();
+                Json::object(vec![
+                    ("goal", Json::Str(result.goal.to_string())),
+                    ("precondition", Json::state(&result.precondition)),
+                    ("points", Json::Array(points)),
+                ])
+            })
+            .collect(),
+    )
+}
+
+fn refinement() -> Json {
+    match backward::refinement() {
+        Some(refinement) => Json::object(vec![
+            ("rounds", Json::Int(refinement.rounds as i64)),
+            ("stable", Json::Bool(refinement.stable)),
+        ]),
+        None => Json::Null,
+    }
+}
+
+fn descending() -> Json {
+    let enabled = *ANALYSIS_FLAG.lock().expect("failed to lock analysis flag") == 2
+        && *NARROWING_FLAG.lock().expect("failed to lock narrowing flag");
+    let mode = if enabled {
+        DESCENDING_MODE.lock().expect("failed to lock descending mode").name()
+    } else {
+        "none"
+    };
+    let loops = program_points::descending()
+        .into_iter()
+        .map(|(span, steps)| {
+            let steps = steps
+                .into_iter()
+                .map(|step| {
+                    let refined = step
+                        .refined
+                        .into_iter()
+                        .map(|(name, before, after)| {
+                            let change = Json::object(vec![
+                                ("before", Json::interval(before)),
+                                ("after", Json::interval(after)),
+                            ]);
+                            (name, change)
+                        })
+                        .collect();
+                    Json::object(vec![("refined", Json::Object(refined))])
+                })
+                .collect();
+            Json::object(vec![("span", Json::span(span)), ("steps", Json::Array(steps))])
+        })
+        .collect();
+    Json::object(vec![
+        ("mode", Json::str(mode)),
+        (
+            "steps",
+            Json::Int(*DESCENDING_STEPS.lock().expect("failed to lock descending steps") as i64),
+        ),
+        ("loops", Json::Array(loops)),
+    ])
+}
+
+fn statistics(duration_ms: u128) -> Json {
+    let statistics = program_points::statistics();
+    Json::object(vec![
+        ("statements", Json::Int(statistics.statements as i64)),
+        ("loop_iterations", Json::Int(statistics.loop_iterations as i64)),
+        ("widenings", Json::Int(statistics.widenings as i64)),
+        ("narrowings", Json::Int(statistics.narrowings as i64)),
+        (
+            "capped_loops",
+            Json::Array(program_points::capped_loops().into_iter().map(Json::span).collect()),
+        ),
+        ("duration_ms", Json::Int(duration_ms as i64)),
+    ])
+}
@@ -0,0 +1,237 @@
+// Jumps out of loop bodies: `break` leaves the innermost loop, `continue` goes on with its next
+// iteration
+//
+// A concrete run flags the pending jump: the rest of the sequences around it does not run, and the
+// innermost loop takes the flag, exiting or going on as after its body. The walks of a loop body
+// (abstract run, definite assignment, iteration changes) collect the values reaching its jumps:
+// the break values join the exit of the loop, next to the states filtered by the negated guard,
+// the continue values join the end of the body. A jump does not complete, the abstract state after
+// it is ⊥. The backward analysis goes the other way: every jump gets the states after its target,
+// after the loop for break and at the end of the body for continue. Blocks with locals between a
+// jump and its loop adjust the values on the way, see scopes.rs.
+
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::initialisation::Assigned;
+use crate::lexer::Span;
+use crate::termination::{self, Changes};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Jump {
+    Break,
+    Continue,
+}
+
+impl fmt::Display for Jump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Jump::Break => write!(f, "break"),
+            Jump::Continue => write!(f, "continue"),
+        }
+    }
+}
+
+// Values reaching the jumps of a loop body, None when no jump is reached
+#[derive(Debug, Clone)]
+pub struct Jumps<T> {
+    pub breaks: Option<T>,
+    pub continues: Option<T>,
+}
+
+// States after the targets of the jumps of a loop body, for the backward analysis
+struct Targets {
+    breaks: AbstractState<AbstractInterval>,
+    continues: AbstractState<AbstractInterval>,
+}
+
+lazy_static! {
+    static ref PENDING: Mutex<Option<Jump>> = Mutex::new(None);
+    static ref STATES: Mutex<Vec<Jumps<AbstractState<AbstractInterval>>>> = Mutex::new(Vec::new());
+    static ref ASSIGNED: Mutex<Vec<Jumps<Assigned>>> = Mutex::new(Vec::new());
+    static ref CHANGES: Mutex<Vec<Jumps<Changes>>> = Mutex::new(Vec::new());
+    static ref TARGETS: Mutex<Vec<Targets>> = Mutex::new(Vec::new());
+}
+
+// Values collected at the jumps by one of the walks
+pub trait Collected: Clone + Sized + 'static {
+    fn stack() -> &'static Mutex<Vec<Jumps<Self>>>;
+    fn join(&self, other: &Self) -> Self;
+}
+
+impl Collected for AbstractState<AbstractInterval> {
+    fn stack() -> &'static Mutex<Vec<Jumps<Self>>> {
+        &STATES
+    }
+    fn join(&self, other: &Self) -> Self {
+        self.state_lub(other)
+    }
+}
+
+impl Collected for Assigned {
+    fn stack() -> &'static Mutex<Vec<Jumps<Self>>> {
+        &ASSIGNED
+    }
+    fn join(&self, other: &Self) -> Self {
+        Assigned::join(self, other)
+    }
+}
+
+impl Collected for Changes {
+    fn stack() -> &'static Mutex<Vec<Jumps<Self>>> {
+        &CHANGES
+    }
+    fn join(&self, other: &Self) -> Self {
+        termination::join(Some(self.clone()), Some(other.clone())).unwrap_or_default()
+    }
+}
+
+impl<T: Collected> Jumps<T> {
+    // The end of the body, where the continue values go
+    pub fn continued(&self, end: &T) -> T {
+        match &self.continues {
+            Some(continues) => end.join(continues),
+            None => end.clone(),
+        }
+    }
+
+    // The exit of the loop, where the break values go
+    pub fn broken(&self, exit: &T) -> T {
+        match &self.breaks {
+            Some(breaks) => exit.join(breaks),
+            None => exit.clone(),
+        }
+    }
+}
+
+// Walks a loop body with `walk`, returns its result and the values reaching the jumps in it
+pub fn collect<T: Collected, R>(walk: impl FnOnce() -> R) -> (R, Jumps<T>) {
+    T::stack().lock().expect("failed to lock jumps").push(Jumps {
+        breaks: None,
+        continues: None,
+    });
+    // the lock is not held during the walk: the loops inside it collect their own jumps
+    let result = walk();
+    let jumps = T::stack()
+        .lock()
+        .expect("failed to lock jumps")
+        .pop()
+        .expect("the jumps of the loop are on the stack");
+    (result, jumps)
+}
+
+// Walks a block with `walk`: the values it sends to the jumps of the innermost loop get `leave`,
+// what leaving the block does to them, before joining the ones sent before it
+pub fn scope<T: Collected, R>(leave: impl Fn(&T) -> T, walk: impl FnOnce() -> R) -> R {
+    let before = T::stack()
+        .lock()
+        .expect("failed to lock jumps")
+        .last_mut()
+        .map(|jumps| Jumps {
+            breaks: jumps.breaks.take(),
+            continues: jumps.continues.take(),
+        });
+    let result = walk();
+    if let Some(before) = before {
+        let mut stack = T::stack().lock().expect("failed to lock jumps");
+        let jumps = stack.last_mut().expect("the jumps of the loop are on the stack");
+        for (slot, before) in [
+            (&mut jumps.breaks, before.breaks),
+            (&mut jumps.continues, before.continues),
+        ] {
+            let inside = slot.take().map(|value| leave(&value));
+            *slot = match (before, inside) {
+                (Some(before), Some(inside)) => Some(before.join(&inside)),
+                (before, None) => before,
+                (None, inside) => inside,
+            };
+        }
+    }
+    result
+}
+
+// `value` reaches the `jump` at `span`, in the innermost loop body being walked
+pub fn reach<T: Collected>(jump: Jump, span: Span, value: &T) {
+    let mut stack = T::stack().lock().expect("failed to lock jumps");
+    let Some(jumps) = stack.last_mut() else {
+        unreachable!("{} at {} outside of a loop", jump, span);
+    };
+    let slot = match jump {
+        Jump::Break => &mut jumps.breaks,
+        Jump::Continue => &mut jumps.continues,
+    };
+    *slot = Some(match slot.take() {
+        Some(joined) => joined.join(value),
+        None => value.clone(),
+    });
+}
+
+// Walks a loop body backward with `walk`, the break statements in it going to `breaks` and the
+// continue statements to `continues`
+pub fn backward<R>(
+    breaks: &AbstractState<AbstractInterval>,
+    continues: &AbstractState<AbstractInterval>,
+    walk: impl FnOnce() -> R,
+) -> R {
+    TARGETS.lock().expect("failed to lock jump targets").push(Targets {
+        breaks: breaks.clone(),
+        continues: continues.clone(),
+    });
+    let result = walk();
+    TARGETS.lock().expect("failed to lock jump targets").pop();
+    result
+}
+
+// Walks a block backward with `walk`, the jumps in it going to the targets of the innermost loop
+// as seen from inside the block, given by `enter`
+pub fn backward_scope<R>(
+    enter: impl Fn(&AbstractState<AbstractInterval>) -> AbstractState<AbstractInterval>,
+    walk: impl FnOnce() -> R,
+) -> R {
+    let outside = TARGETS
+        .lock()
+        .expect("failed to lock jump targets")
+        .last_mut()
+        .map(|targets| {
+            let inside = Targets {
+                breaks: enter(&targets.breaks),
+                continues: enter(&targets.continues),
+            };
+            std::mem::replace(targets, inside)
+        });
+    let result = walk();
+    if let Some(outside) = outside {
+        *TARGETS
+            .lock()
+            .expect("failed to lock jump targets")
+            .last_mut()
+            .expect("the jump targets of the loop are on the stack") = outside;
+    }
+    result
+}
+
+// States after the target of the `jump` at `span`
+pub fn target(jump: Jump, span: Span) -> AbstractState<AbstractInterval> {
+    let targets = TARGETS.lock().expect("failed to lock jump targets");
+    match (targets.last(), jump) {
+        (Some(targets), Jump::Break) => targets.breaks.clone(),
+        (Some(targets), Jump::Continue) => targets.continues.clone(),
+        (None, _) => unreachable!("{} at {} outside of a loop", jump, span),
+    }
+}
+
+// Concrete run: the jump is taken, the statements up to its loop are skipped
+pub fn take(jump: Jump) {
+    *PENDING.lock().expect("failed to lock pending jump") = Some(jump);
+}
+
+pub fn pending() -> bool {
+    PENDING.lock().expect("failed to lock pending jump").is_some()
+}
+
+// Concrete run: the jump the loop body ended with, if any, cleared
+pub fn land() -> Option<Jump> {
+    PENDING.lock().expect("failed to lock pending jump").take()
+}
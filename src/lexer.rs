@@ -15,6 +15,12 @@ pub enum TokenType {
     Minus,      // '-'
     Multiply,   // '*'
     Divide,     // '/'
+    Modulo,     // '%'
+    BitAnd,     // '&'
+    BitOr,      // '|'
+    BitXor,     // '^'
+    ShiftLeft,  // '<<'
+    ShiftRight, // '>>'
     Assign,     // ':='
     LessEqual,  // '<='
     Less,       // '<'
@@ -37,8 +43,23 @@ pub enum TokenType {
     Until,
     For,
     Skip,
+    Assert,
+    Input,
+    In,
     True,
     False,
+    Procedure,
+    Return,
+    Random,
+    Either,
+    OrElse, // 'or' di either { } or { }
+    Break,
+    Continue,
+    Var,
+    Print,
+    Switch,
+    Case,
+    Default,
 
     // Simboli
     Bra,       // '('
@@ -46,6 +67,34 @@ pub enum TokenType {
     CBra,      // '{'
     Cket,      // '}'
     Semicolon, // ';'
+    Comma,     // ','
+    Question,  // '?'
+    Colon,     // ':' di case k:
+    SBra,      // '['
+    SKet,      // ']'
+    At,        // '@' di un'annotazione
+}
+
+// Source position of a token (1-based), line 0 marks nodes built by the analyzer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(line: usize, column: usize) -> Self {
+        Span { line, column }
+    }
+    pub fn is_synthetic(&self) -> bool {
+        self.line == 0
+    }
+}
+
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
 }
 
 #[derive(Clone)]
@@ -54,11 +103,12 @@ pub enum TokenType {
 pub struct Token {
     pub value: String,
     pub token_ty: TokenType,
+    pub span: Span,
 }
 
 impl Token {
     pub fn new(value: String, token: TokenType) -> Self {
-        Token { value, token_ty: token }
+        Token { value, token_ty: token, span: Span::default() }
     }
     pub fn to_string(& self) -> String {
         format!("{}", self.value)
@@ -68,6 +118,8 @@ impl Token {
 pub struct Lexer {
     input: Vec<char>, // Input trattato come una sequenza di caratteri
     pos: usize,       // Posizione corrente nell'input
+    line: usize,      // Riga corrente (per gli span dei token)
+    column: usize,    // Colonna corrente
 }
 
 impl Lexer {
@@ -76,6 +128,8 @@ impl Lexer {
         Lexer {
             input: input.chars().collect(),
             pos: 0,
+            line: 1,
+            column: 1,
         }
     }
 
@@ -95,6 +149,12 @@ impl Lexer {
 
     // Avanza di un carattere
     fn advance(&mut self) {
+        if self.current_char() == Some('\n') {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
         self.pos += 1;
     }
 
@@ -113,8 +173,9 @@ impl Lexer {
     pub fn next_token(&mut self) -> Option<Token> {
         self.skip_whitespace();
 
+        let span = Span::new(self.line, self.column);
         if let Some(current) = self.current_char() {
-            let curr_token = match current {
+            let mut curr_token = match current {
                 // Operatori singoli e doppi
                 '+' => {
                     self.advance();
@@ -142,13 +203,21 @@ impl Lexer {
                     self.advance();
                     Token::new("/".to_string(), TokenType::Divide)
                 }
+                '%' => {
+                    self.advance();
+                    Token::new("%".to_string(), TokenType::Modulo)
+                }
+                '^' => {
+                    self.advance();
+                    Token::new("^".to_string(), TokenType::BitXor)
+                }
                 ':' => {
                     self.advance();
                     if let Some('=') = self.current_char() {
                         self.advance();
                         Token::new(":=".to_string(), TokenType::Assign)
                     } else {
-                        return None; // Errore sintattico
+                        Token::new(":".to_string(), TokenType::Colon)
                     }
                 }
                 '<' => {
@@ -156,6 +225,9 @@ impl Lexer {
                     if let Some('=') = self.current_char() {
                         self.advance();
                         Token::new("<=".to_string(), TokenType::LessEqual)
+                    } else if let Some('<') = self.current_char() {
+                        self.advance();
+                        Token::new("<<".to_string(), TokenType::ShiftLeft)
                     } else {
                         Token::new("<".to_string(), TokenType::Less)
                     }
@@ -165,6 +237,9 @@ impl Lexer {
                     if let Some('=') = self.current_char() {
                         self.advance();
                         Token::new(">=".to_string(), TokenType::GreatEqual)
+                    } else if let Some('>') = self.current_char() {
+                        self.advance();
+                        Token::new(">>".to_string(), TokenType::ShiftRight)
                     } else {
                         Token::new(">".to_string(), TokenType::Greater)
                     }
@@ -179,7 +254,7 @@ impl Lexer {
                         self.advance();
                         Token::new("&&".to_string(), TokenType::And)
                     } else {
-                        return None; // Errore sintattico
+                        Token::new("&".to_string(), TokenType::BitAnd)
                     }
                 }
                 '|' => {
@@ -188,7 +263,7 @@ impl Lexer {
                         self.advance();
                         Token::new("||".to_string(), TokenType::Or)
                     } else {
-                        return None; // Errore sintattico
+                        Token::new("|".to_string(), TokenType::BitOr)
                     }
                 }
                 '!' => {
@@ -223,6 +298,26 @@ impl Lexer {
                     self.advance();
                     Token::new(";".to_string(), TokenType::Semicolon)
                 }
+                ',' => {
+                    self.advance();
+                    Token::new(",".to_string(), TokenType::Comma)
+                }
+                '?' => {
+                    self.advance();
+                    Token::new("?".to_string(), TokenType::Question)
+                }
+                '[' => {
+                    self.advance();
+                    Token::new("[".to_string(), TokenType::SBra)
+                }
+                ']' => {
+                    self.advance();
+                    Token::new("]".to_string(), TokenType::SKet)
+                }
+                '@' => {
+                    self.advance();
+                    Token::new("@".to_string(), TokenType::At)
+                }
 
                 // Identificatori o parole chiave (analisi per stringa completa)
                 _ if current.is_alphabetic() => {
@@ -236,8 +331,23 @@ impl Lexer {
                         "until" => Token::new(identifier.clone(), TokenType::Until),
                         "for" => Token::new(identifier.clone(), TokenType::For),
                         "skip" => Token::new(identifier.clone(), TokenType::Skip),
+                        "assert" => Token::new(identifier.clone(), TokenType::Assert),
+                        "input" => Token::new(identifier.clone(), TokenType::Input),
+                        "in" => Token::new(identifier.clone(), TokenType::In),
                         "true" => Token::new(identifier.clone(), TokenType::True),
                         "false" => Token::new(identifier.clone(), TokenType::False),
+                        "procedure" => Token::new(identifier.clone(), TokenType::Procedure),
+                        "return" => Token::new(identifier.clone(), TokenType::Return),
+                        "random" => Token::new(identifier.clone(), TokenType::Random),
+                        "either" => Token::new(identifier.clone(), TokenType::Either),
+                        "or" => Token::new(identifier.clone(), TokenType::OrElse),
+                        "break" => Token::new(identifier.clone(), TokenType::Break),
+                        "continue" => Token::new(identifier.clone(), TokenType::Continue),
+                        "var" => Token::new(identifier.clone(), TokenType::Var),
+                        "print" => Token::new(identifier.clone(), TokenType::Print),
+                        "switch" => Token::new(identifier.clone(), TokenType::Switch),
+                        "case" => Token::new(identifier.clone(), TokenType::Case),
+                        "default" => Token::new(identifier.clone(), TokenType::Default),
                         _ => Token::new(identifier.clone(), TokenType::Identifier(identifier)),
                     }
                 }
@@ -251,6 +361,7 @@ impl Lexer {
                 _ => return None, // Carattere non riconosciuto
             };
 
+            curr_token.span = span;
             Some(curr_token)
         } else {
             None // Fine dell'input
@@ -0,0 +1,77 @@
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::ast::statement::Statement;
+use crate::lexer::Span;
+use crate::program_points;
+
+const INDENT: &str = "    ";
+
+// Source listing rebuilt from the AST, with the recorded abstract states as comments
+pub struct Listing {
+    lines: Vec<String>,
+    last_code_line: usize,
+}
+
+impl Listing {
+    pub fn new() -> Self {
+        Listing {
+            lines: Vec::new(),
+            last_code_line: 0,
+        }
+    }
+
+    // Builds the listing of a whole program
+    pub fn of_program(program: &dyn Statement<Q = AbstractInterval>) -> String {
+        let mut listing = Listing::new();
+        listing.block(program, 0);
+        listing.lines.join("\n")
+    }
+
+    // A block starts with the state before its first statement
+    pub fn block(&mut self, body: &dyn Statement<Q = AbstractInterval>, indent: usize) {
+        if let Some(states) = program_points::lookup(body.span()) {
+            self.comment(&states.pre, indent);
+        }
+        body.write_listing(self, indent);
+    }
+
+    pub fn code(&mut self, text: String, indent: usize) {
+        self.lines.push(format!("{}{}", INDENT.repeat(indent), text));
+        self.last_code_line = self.lines.len() - 1;
+    }
+
+    // Appends the sequence separator to the last line of code, before its comment
+    pub fn terminate(&mut self) {
+        if let Some(line) = self.lines.get_mut(self.last_code_line) {
+            line.push(';');
+        }
+    }
+
+    // Comment with the state after the statement at `span`
+    pub fn state_after(&mut self, span: Span, indent: usize) {
+        if let Some(states) = program_points::lookup(span) {
+            self.comment(&states.post, indent);
+        }
+    }
+
+    fn comment(&mut self, state: &AbstractState<AbstractInterval>, indent: usize) {
+        self.lines.push(format!(
+            "{}/* {} */",
+            INDENT.repeat(indent),
+            Listing::format_state(state)
+        ));
+    }
+
+    // {x: [0, 10], y: [1, +∞]} with variables in sorted order, ⊥ for unreachable states
+    pub fn format_state(state: &AbstractState<AbstractInterval>) -> String {
+        if state.is_bottom() {
+            return "⊥".to_string();
+        }
+        let variables: Vec<String> = state
+            .sorted_variables()
+            .iter()
+            .map(|(name, domain)| format!("{}: {}", name, domain.value))
+            .collect();
+        format!("{{{}}}", variables.join(", "))
+    }
+}
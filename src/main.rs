@@ -1,9 +1,33 @@
 mod abstract_domain;
 mod abstract_interval;
 mod abstract_state;
+mod alarms;
+mod annotations;
+mod arrays;
 mod ast;
+mod backward;
+mod booleans;
+mod cost;
+mod initialisation;
+mod inputs;
+mod json;
+mod jumps;
 pub mod lexer;
+mod listing;
+mod output;
 mod parser;
+mod partitioning;
+mod procedures;
+mod program_points;
+mod random;
+mod reachability;
+mod sarif;
+mod scopes;
+mod switch;
+mod termination;
+mod thresholds;
+mod unrolling;
+use std::env;
 use std::fs;
 use std::io;
 use std::path::Path;
@@ -16,13 +40,122 @@ extern crate lazy_static;
 // 9223372036854775807
 
 lazy_static! {
-    static ref CONSTANTS_VECTOR: Mutex<Vec<i64>> = Mutex::new(Vec::new());
+    // Widening thresholds: m, n, --thresholds, @thresholds(...) and, optionally, the inferred ones
+    static ref THRESHOLDS: Mutex<Vec<i64>> = Mutex::new(Vec::new());
 }
 pub static M: Mutex<i64> = Mutex::new(0);
 pub static N: Mutex<i64> = Mutex::new(0);
 pub static ANALYSIS_FLAG: Mutex<i64> = Mutex::new(1);
 pub static WIDENING_FLAG: Mutex<bool> = Mutex::new(false);
 pub static NARROWING_FLAG: Mutex<bool> = Mutex::new(false);
+// Loop iterations joined before widening starts
+pub static WIDENING_DELAY: Mutex<u64> = Mutex::new(0);
+// Iterations after which widening is forced on a loop that has not stabilized
+pub static MAX_ITERATIONS: Mutex<u64> = Mutex::new(1000);
+// Descending phase after widening: how each step is computed and how many steps are run at most
+pub static DESCENDING_MODE: Mutex<DescendingMode> = Mutex::new(DescendingMode::Narrowing);
+pub static DESCENDING_STEPS: Mutex<u64> = Mutex::new(5);
+// Loop iterations analysed one at a time before the fixpoint, @unroll(k) overrides it per loop
+pub static UNROLL: Mutex<u64> = Mutex::new(0);
+// Trace partitioning and where the partitions are merged
+pub static PARTITIONING: Mutex<Partitioning> = Mutex::new(Partitioning::Off);
+// Backward analysis from the failure of every assertion, and from a postcondition guard
+pub static BACKWARD: Mutex<bool> = Mutex::new(false);
+pub static POSTCONDITION: Mutex<Option<String>> = Mutex::new(None);
+// Rounds of forward-backward refinement after the forward analysis, none by default
+pub static REFINEMENT_ROUNDS: Mutex<u64> = Mutex::new(0);
+// Adds the thresholds inferred from loop guards and loop initialisations
+pub static INFER_THRESHOLDS: Mutex<bool> = Mutex::new(false);
+pub static OUTPUT_FORMAT: Mutex<OutputFormat> = Mutex::new(OutputFormat::Text);
+
+// How the analysis results are reported
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,      // trace of the analysis as it runs
+    Annotated, // program listing with the abstract state around every statement
+    Json,      // machine readable report, schema in json.rs
+    Sarif,     // alarms as a SARIF 2.1.0 log
+}
+
+// Descending iterations refining the post-fixpoint reached by widening
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DescendingMode {
+    Narrowing,  // head ∇ (entry ⊔ F(head)) with the narrowing operator
+    Decreasing, // head ⊓ (entry ⊔ F(head)), plain decreasing iterations
+}
+
+impl DescendingMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DescendingMode::Narrowing => "narrowing",
+            DescendingMode::Decreasing => "decreasing",
+        }
+    }
+}
+
+// Merge point of the partitions made by if-then-else branches and loop exits
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Partitioning {
+    Off,   // branches are joined right away
+    Block, // at the end of the enclosing block
+    Loop,  // at the end of the enclosing loop body, or of the program
+}
+
+// The analysis trace is printed only in the plain text output
+pub fn text_output() -> bool {
+    *OUTPUT_FORMAT.lock().expect("failed to lock output format") == OutputFormat::Text
+}
+
+// Settings given on the command line, the missing ones are asked interactively
+struct Args {
+    program: String,
+    analysis: Option<i64>,
+    bounds: Option<(i64, i64)>,
+    widening: Option<bool>,
+    narrowing: Option<bool>,
+    thresholds: Vec<i64>,
+}
+
+// Prompts go to stderr when stdout carries a machine readable report
+fn prompt(message: &str) {
+    if text_output() {
+        println!("{}", message);
+    } else {
+        eprintln!("{}", message);
+    }
+}
+
+fn usage_error(message: String) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(2);
+}
+
+// Error of the concrete run, e.g. a failed assertion or a read of an uninitialised variable
+fn runtime_error(message: String) -> ! {
+    eprintln!("**RUNTIME ERROR, {}**", message);
+    std::process::exit(1);
+}
+
+// Malformed program, e.g. an annotation whose arguments are not integers
+fn syntax_error(message: String) -> ! {
+    eprintln!("**SYNTAX ERROR, {}**", message);
+    std::process::exit(2);
+}
+
+fn int_arg(value: Option<String>, flag: &str) -> i64 {
+    match value.as_deref().map(str::parse::<i64>) {
+        Some(Ok(value)) => value,
+        _ => usage_error(format!("{} expects an integer, found {:?}", flag, value)),
+    }
+}
+
+fn bool_arg(value: Option<String>, flag: &str) -> bool {
+    match value.as_deref() {
+        Some("y") => true,
+        Some("n") => false,
+        other => usage_error(format!("{} expects y or n, found {:?}", flag, other)),
+    }
+}
 
 pub fn take_int() -> i64 {
     let mut input = String::new();
@@ -44,9 +177,155 @@ pub fn take_bool() -> bool {
         _ => return true,
     }
 }
+// usage: softver [program file] [--format text|annotated|json|sarif] [--analysis concrete|abstract]
+//                [--bounds m n] [--widening y|n] [--narrowing y|n] [--widening-delay k]
+//                [--max-iterations n] [--thresholds c1,c2,...] [--infer-thresholds]
+//                [--descending narrowing|decreasing] [--descending-steps n] [--unroll k]
+//                [--partition off|block|loop] [--backward] [--postcondition guard]
+//                [--refine rounds] [--input x=v | x=a..b]... [--seed n] [--random v1,v2,...]
+fn parse_args() -> Args {
+    let mut parsed = Args {
+        program: String::from("/home/alberto/Desktop/soft2ver/soft2ver/src/test/factorial"),
+        analysis: None,
+        bounds: None,
+        widening: None,
+        narrowing: None,
+        thresholds: Vec::new(),
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let format = match args.next().as_deref() {
+                    Some("text") => OutputFormat::Text,
+                    Some("annotated") => OutputFormat::Annotated,
+                    Some("json") => OutputFormat::Json,
+                    Some("sarif") => OutputFormat::Sarif,
+                    other => usage_error(format!(
+                        "unknown output format {:?}, expected text, annotated, json or sarif",
+                        other
+                    )),
+                };
+                *OUTPUT_FORMAT.lock().unwrap() = format;
+            }
+            "--analysis" => {
+                parsed.analysis = match args.next().as_deref() {
+                    Some("concrete") => Some(1),
+                    Some("abstract") => Some(2),
+                    other => usage_error(format!(
+                        "unknown analysis {:?}, expected concrete or abstract",
+                        other
+                    )),
+                };
+            }
+            "--bounds" => {
+                let m = int_arg(args.next(), "--bounds");
+                let n = int_arg(args.next(), "--bounds");
+                if m > n {
+                    usage_error(format!("invalid bounds {} {}, ensure that m <= n", m, n));
+                }
+                parsed.bounds = Some((m, n));
+            }
+            "--widening" => parsed.widening = Some(bool_arg(args.next(), "--widening")),
+            "--narrowing" => parsed.narrowing = Some(bool_arg(args.next(), "--narrowing")),
+            "--widening-delay" => {
+                let delay = int_arg(args.next(), "--widening-delay");
+                if delay < 0 {
+                    usage_error(format!("invalid widening delay {}", delay));
+                }
+                *WIDENING_DELAY.lock().unwrap() = delay as u64;
+            }
+            "--thresholds" => {
+                let values = args.next().unwrap_or_default();
+                for value in values.split(',') {
+                    parsed.thresholds.push(int_arg(Some(value.trim().to_string()), "--thresholds"));
+                }
+            }
+            "--infer-thresholds" => *INFER_THRESHOLDS.lock().unwrap() = true,
+            "--max-iterations" => {
+                let cap = int_arg(args.next(), "--max-iterations");
+                if cap < 1 {
+                    usage_error(format!("invalid iteration cap {}", cap));
+                }
+                *MAX_ITERATIONS.lock().unwrap() = cap as u64;
+            }
+            "--descending" => {
+                let mode = match args.next().as_deref() {
+                    Some("narrowing") => DescendingMode::Narrowing,
+                    Some("decreasing") => DescendingMode::Decreasing,
+                    other => usage_error(format!(
+                        "unknown descending mode {:?}, expected narrowing or decreasing",
+                        other
+                    )),
+                };
+                *DESCENDING_MODE.lock().unwrap() = mode;
+                // choosing a descending mode asks for the descending phase
+                parsed.narrowing.get_or_insert(true);
+            }
+            "--descending-steps" => {
+                let steps = int_arg(args.next(), "--descending-steps");
+                if steps < 0 {
+                    usage_error(format!("invalid number of descending steps {}", steps));
+                }
+                *DESCENDING_STEPS.lock().unwrap() = steps as u64;
+            }
+            "--unroll" => {
+                let iterations = int_arg(args.next(), "--unroll");
+                if iterations < 0 {
+                    usage_error(format!("invalid number of unrolled iterations {}", iterations));
+                }
+                *UNROLL.lock().unwrap() = iterations as u64;
+            }
+            "--input" => match args.next() {
+                Some(input) => inputs::set(&input),
+                None => usage_error("--input expects x=v or x=a..b".to_string()),
+            },
+            "--seed" => random::seed(int_arg(args.next(), "--seed") as u64),
+            "--random" => {
+                let values = args.next().unwrap_or_default();
+                let values: Vec<i64> = values
+                    .split(',')
+                    .map(|value| int_arg(Some(value.trim().to_string()), "--random"))
+                    .collect();
+                random::script(&values);
+            }
+            "--refine" => {
+                let rounds = int_arg(args.next(), "--refine");
+                if rounds < 0 {
+                    usage_error(format!("invalid number of refinement rounds {}", rounds));
+                }
+                *REFINEMENT_ROUNDS.lock().unwrap() = rounds as u64;
+            }
+            "--partition" => {
+                let partitioning = match args.next().as_deref() {
+                    Some("off") => Partitioning::Off,
+                    Some("block") => Partitioning::Block,
+                    Some("loop") => Partitioning::Loop,
+                    other => usage_error(format!(
+                        "unknown partitioning {:?}, expected off, block or loop",
+                        other
+                    )),
+                };
+                *PARTITIONING.lock().unwrap() = partitioning;
+            }
+            "--backward" => *BACKWARD.lock().unwrap() = true,
+            "--postcondition" => match args.next() {
+                Some(guard) if parser::is_guard(&guard) => {
+                    *POSTCONDITION.lock().unwrap() = Some(guard)
+                }
+                Some(guard) => usage_error(format!("--postcondition {:?} is not a guard", guard)),
+                None => usage_error("--postcondition expects a guard".to_string()),
+            },
+            _ => parsed.program = arg,
+        }
+    }
+    parsed
+}
+
 fn main() {
     //test file path
-    let program_file_path = Path::new("/home/alberto/Desktop/soft2ver/soft2ver/src/test/factorial");
+    let args = parse_args();
+    let program_file_path = Path::new(&args.program);
 
     //read from the file
     let contents = fs::read_to_string(program_file_path)
@@ -57,27 +336,35 @@ fn main() {
     let mut _analysis = 0;
 
     loop {
-        println!("Type '1' or '2' to choose between denotational and abstract semantics to perform the analysis ");
-        _analysis = take_int();
+        _analysis = match args.analysis {
+            Some(analysis) => analysis,
+            None => {
+                prompt("Type '1' or '2' to choose between denotational and abstract semantics to perform the analysis ");
+                take_int()
+            }
+        };
         if _analysis == 1 {
             break;
         }
         if _analysis == 2 {
             loop {
-                println!("INSERT m value");
-                _m = take_int();
-                println!("INSERT n value");
-                _n = take_int();
+                if let Some((m, n)) = args.bounds {
+                    _m = m;
+                    _n = n;
+                } else {
+                    prompt("INSERT m value");
+                    _m = take_int();
+                    prompt("INSERT n value");
+                    _n = take_int();
+                }
 
                 if _m <= _n {
-                    let mut vec = CONSTANTS_VECTOR
-                        .lock()
-                        .expect("failed to lock constant vector");
+                    let mut vec = THRESHOLDS.lock().expect("failed to lock thresholds");
                     vec.push(_m);
                     vec.push(_n);
                     break; // Exit the loop when condition is met
                 } else {
-                    println!("Invalid input. Ensure that and m <= n.");
+                    prompt("Invalid input. Ensure that and m <= n.");
                 }
             }
             // Update M and N values
@@ -89,10 +376,12 @@ fn main() {
                 let mut global_n = N.lock().unwrap();
                 *global_n = _n;
             }
+            thresholds::add(&args.thresholds);
 
-            let mut _wid = false;
-            println!("Do you wanna use widening? type y or n, otherwise will be yes ");
-            _wid = take_bool();
+            let _wid = args.widening.unwrap_or_else(|| {
+                prompt("Do you wanna use widening? type y or n, otherwise will be yes ");
+                take_bool()
+            });
 
             {
                 let mut global_wid_flag = WIDENING_FLAG.lock().unwrap();
@@ -100,8 +389,10 @@ fn main() {
             }
             let mut _narrow = false;
             if _wid {
-                println!("Do you wanna use narrowing? type y or n, otherwise will be yes ");
-                _narrow = take_bool();
+                _narrow = args.narrowing.unwrap_or_else(|| {
+                    prompt("Do you wanna use narrowing? type y or n, otherwise will be yes ");
+                    take_bool()
+                });
             }
             {
                 let mut global_narrow_flag = NARROWING_FLAG.lock().unwrap();
@@ -109,7 +400,7 @@ fn main() {
             }
             break;
         } else {
-            println!("invalid input, ensure yor're typing '1' or '2' ");
+            prompt("invalid input, ensure yor're typing '1' or '2' ");
         }
     }
     {
@@ -117,7 +408,7 @@ fn main() {
         *global_analysis_flag = _analysis;
     }
     //lex parse and evaluate the program
-    parser::analyze(contents);
+    parser::analyze(contents, &args.program);
 }
 
 pub fn find_max(vec: &mut MutexGuard<'_, Vec<i64>>, value: i64) -> i64 {
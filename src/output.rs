@@ -0,0 +1,94 @@
+// Output: the statement `print(e1, e2, ...)`
+//
+// A concrete run writes the values of the arguments, one line per print executed, and keeps them
+// for the JSON report. The abstract output of a print is the intervals of its arguments in the
+// state recorded before it, the last visit of the analysis as for every program point: each line a
+// concrete run writes at that print has its values in them. The abstract outputs are listed in
+// source order, the prints never reached are left out.
+
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+use crate::abstract_interval::AbstractInterval;
+use crate::ast::arithmetic::ArithmeticExpression;
+use crate::lexer::Span;
+use crate::program_points;
+use crate::text_output;
+
+// Line written by a print in a concrete run
+#[derive(Debug, Clone)]
+pub struct Written {
+    pub span: Span,
+    pub values: Vec<i64>,
+}
+
+// Intervals of the arguments of a print in the abstract run
+#[derive(Debug, Clone)]
+pub struct Printed {
+    pub span: Span,
+    pub values: Vec<AbstractInterval>,
+}
+
+// A print of the program and its arguments
+struct Print {
+    span: Span,
+    arguments: Vec<Box<dyn ArithmeticExpression<Q = AbstractInterval>>>,
+}
+
+lazy_static! {
+    static ref WRITTEN: Mutex<Vec<Written>> = Mutex::new(Vec::new());
+}
+
+// Arguments are trait objects, which cannot be shared between threads: the prints of the program
+// live in thread local storage rather than behind a Mutex
+thread_local! {
+    static PRINTS: RefCell<Vec<Print>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn declare(span: Span, arguments: &[Box<dyn ArithmeticExpression<Q = AbstractInterval>>]) {
+    let arguments = arguments.iter().map(|argument| argument.clone_box()).collect();
+    PRINTS.with(|prints| prints.borrow_mut().push(Print { span, arguments }));
+}
+
+pub fn write(span: Span, values: Vec<i64>) {
+    if text_output() {
+        let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        println!("OUTPUT AT {}: {}", span, values.join(", "));
+    }
+    WRITTEN
+        .lock()
+        .expect("failed to lock output")
+        .push(Written { span, values });
+}
+
+// Lines of the concrete run, in the order they were written
+pub fn written() -> Vec<Written> {
+    WRITTEN.lock().expect("failed to lock output").clone()
+}
+
+// Abstract output of the reachable prints, in source order
+pub fn printed() -> Vec<Printed> {
+    let mut printed: Vec<Printed> = PRINTS.with(|prints| {
+        prints
+            .borrow()
+            .iter()
+            .filter_map(|print| {
+                let pre = program_points::lookup(print.span)?.pre;
+                if pre.is_bottom() {
+                    return None;
+                }
+                let values = print
+                    .arguments
+                    .iter()
+                    .map(|argument| argument.abs_evaluate(&mut pre.clone()))
+                    .collect();
+                Some(Printed {
+                    span: print.span,
+                    values,
+                })
+            })
+            .collect()
+    });
+    printed.sort_by_key(|printed| printed.span);
+    printed
+}
@@ -1,12 +1,36 @@
 use crate::abstract_interval::AbstractInterval;
 use crate::ast::{arithmetic::*, boolean::*, statement::*, State};
 use crate::lexer::Lexer;
+use crate::lexer::Span;
 use crate::lexer::Token;
 use crate::lexer::TokenType;
-use crate::CONSTANTS_VECTOR;
-use crate::{abstract_state, ANALYSIS_FLAG};
+use crate::alarms;
+use crate::annotations;
+use crate::arrays;
+use crate::initialisation;
+use crate::inputs;
+use crate::backward::{self, Goal, Refinement};
+use crate::booleans;
+use crate::cost;
+use crate::json;
+use crate::jumps::Jump;
+use crate::listing::Listing;
+use crate::partitioning::{self, Partition};
+use crate::output;
+use crate::procedures::{self, Procedure};
+use crate::reachability;
+use crate::sarif;
+use crate::scopes;
+use crate::termination;
+use crate::thresholds;
+use crate::unrolling;
+use crate::{
+    abstract_state, syntax_error, text_output, OutputFormat, ANALYSIS_FLAG, BACKWARD,
+    INFER_THRESHOLDS, OUTPUT_FORMAT, POSTCONDITION, REFINEMENT_ROUNDS,
+};
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::time::Instant;
 
 pub struct TokenVec {
     tokens: Vec<Token>,
@@ -23,6 +47,12 @@ impl Display for TokenVec {
                 TokenType::Minus => "Minus".to_string(),
                 TokenType::Multiply => "Multiply".to_string(),
                 TokenType::Divide => "Divide".to_string(),
+                TokenType::Modulo => "Modulo(%)".to_string(),
+                TokenType::BitAnd => "BitAnd(&)".to_string(),
+                TokenType::BitOr => "BitOr(|)".to_string(),
+                TokenType::BitXor => "BitXor(^)".to_string(),
+                TokenType::ShiftLeft => "ShiftLeft(<<)".to_string(),
+                TokenType::ShiftRight => "ShiftRight(>>)".to_string(),
                 TokenType::Assign => "Assign(:=)".to_string(),
                 TokenType::LessEqual => "LessEqual(<=)".to_string(),
                 TokenType::Less => "Less(<)".to_string(),
@@ -43,13 +73,34 @@ impl Display for TokenVec {
                 TokenType::Until => "Until".to_string(),
                 TokenType::For => "For".to_string(),
                 TokenType::Skip => "Skip".to_string(),
+                TokenType::Assert => "Assert".to_string(),
+                TokenType::Input => "Input".to_string(),
+                TokenType::In => "In".to_string(),
                 TokenType::True => "True".to_string(),
                 TokenType::False => "False".to_string(),
+                TokenType::Procedure => "Procedure".to_string(),
+                TokenType::Return => "Return".to_string(),
+                TokenType::Random => "Random".to_string(),
+                TokenType::Either => "Either".to_string(),
+                TokenType::OrElse => "OrElse".to_string(),
+                TokenType::Break => "Break".to_string(),
+                TokenType::Continue => "Continue".to_string(),
+                TokenType::Var => "Var".to_string(),
+                TokenType::Print => "Print".to_string(),
+                TokenType::Switch => "Switch".to_string(),
+                TokenType::Case => "Case".to_string(),
+                TokenType::Default => "Default".to_string(),
                 TokenType::Bra => "Bra (".to_string(),
                 TokenType::Ket => "Ket )".to_string(),
                 TokenType::CBra => "CBra {".to_string(),
                 TokenType::Cket => "Cket }".to_string(),
                 TokenType::Semicolon => "Semicolon ; ".to_string(),
+                TokenType::Comma => "Comma , ".to_string(),
+                TokenType::Question => "Question ?".to_string(),
+                TokenType::Colon => "Colon :".to_string(),
+                TokenType::SBra => "SBra [".to_string(),
+                TokenType::SKet => "SKet ]".to_string(),
+                TokenType::At => "At @".to_string(),
             };
 
             // Scrive il token corrente nel formatter
@@ -100,6 +151,15 @@ impl Any {
         }
     }
 
+    // Funzione per ottenere un riferimento a BooleanExpression (se presente)
+    pub fn as_boolean_expr(&self) -> Option<&dyn BooleanExpression<Q = AbstractInterval>> {
+        if let Any::BooleanExpression(expr) = self {
+            Some(expr.as_ref())
+        } else {
+            None
+        }
+    }
+
     // Funzione per ottenere un riferimento a Statement (se presente)
     pub fn as_statement(&self) -> Option<&Box<dyn Statement<Q = AbstractInterval>>> {
         if let Any::Statement(stmt) = self {
@@ -172,15 +232,9 @@ pub fn parse_lit(tok_vec: &mut AnyVec, index: &mut usize) {
     match &tok_vec.nodes[*index] {
         Any::Token(token) => match token.token_ty {
             TokenType::Number(value) => {
-                let mut constant = CONSTANTS_VECTOR
-                    .lock()
-                    .expect("FAILED TO LOCK THE CONSTANT VECTOR");
                 // Crea un Numeral e sostituisci il Token con un ArithmeticExpression
                 let numeral = Numeral(value);
                 let arithmetic_expr = Any::from_arithmetic_expr(Box::new(numeral));
-                if ! constant.contains(&value){
-                    constant.push(value.clone());
-                }
                 // Sostituisce il token corrente con l'espressione aritmetica
                 tok_vec.nodes[*index] = arithmetic_expr;
             }
@@ -230,6 +284,15 @@ pub fn parse_var(tok_vec: &mut AnyVec, index: &mut usize) {
     // Controlla che il nodo corrente sia un Token
     match &tok_vec.nodes[*index] {
         Any::Token(token) => match &token.token_ty {
+            TokenType::Identifier(ref id) if booleans::declared(id) => {
+                // Le variabili booleane sono espressioni booleane
+                let var = BoolVariable {
+                    var: Variable {
+                        value: id.to_string(),
+                    },
+                };
+                tok_vec.nodes[*index] = Any::from_boolean_expr(Box::new(var));
+            }
             TokenType::Identifier(ref id) => {
                 // Crea una Variable e sostituisci il Token con un ArithmeticExpression
                 let var = Variable {
@@ -258,7 +321,7 @@ pub fn parse_skip(tok_vec: &mut AnyVec, index: &mut usize) {
         Any::Token(token) => match token.token_ty {
             TokenType::Skip => {
                 // Creiamo un'espressione o dichiarazione Skip
-                let skip_stmt = Skip;
+                let skip_stmt = Skip { span: token.span };
                 let statement_expr = Any::from_statement(Box::new(skip_stmt));
 
                 // Sostituisce il token corrente con la dichiarazione di skip
@@ -289,6 +352,20 @@ pub fn parse_atomic(tok_vec: &mut AnyVec, index: &mut usize) {
                 TokenType::True | TokenType::False => {
                     parse_bool_value(tok_vec, index);
                 }
+                TokenType::Break | TokenType::Continue => {
+                    let jump = if token.token_ty == TokenType::Break {
+                        Jump::Break
+                    } else {
+                        Jump::Continue
+                    };
+                    let span = token.span;
+                    tok_vec.nodes[*index] = Any::from_statement(Box::new(LoopJump { jump, span }));
+                }
+                TokenType::Question => {
+                    // valore qualsiasi, `?`
+                    tok_vec.nodes[*index] =
+                        Any::from_arithmetic_expr(Box::new(Random { range: None }));
+                }
                 _ => {}
             }
         }
@@ -297,1143 +374,752 @@ pub fn parse_atomic(tok_vec: &mut AnyVec, index: &mut usize) {
     }
 }
 
-pub fn parse_arithmetic_subexpression(
-    tok_vec: &mut AnyVec,
-    index: &mut usize,
-) -> Box<dyn ArithmeticExpression<Q = AbstractInterval>> {
-    // Incrementa l'indice per saltare la parentesi aperta
-    *index += 1;
-
-    let start = *index;
-    let mut depth = 1; // Traccia la profondità delle parentesi
-
-    // Cerca la parentesi chiusa corrispondente
-    while *index < tok_vec.nodes.len() {
-        if let Some(Any::Token(token)) = tok_vec.nodes.get(*index) {
-            match token.token_ty {
-                TokenType::Bra => depth += 1, // Nuova parentesi aperta, aumenta la profondità
-                TokenType::Ket => {
-                    depth -= 1; // Parentesi chiusa, diminuisci la profondità
-                    if depth == 0 {
-                        break; // Trovata la parentesi chiusa corrispondente
+// Array declarations `a[size]` standing alone as statements and accesses `a[index]`, reduced on
+// the tokens before the other passes so that accesses are operands of arithmetic expressions
+pub fn parse_arrays(tok_vec: &mut AnyVec, index: &mut usize) {
+    while *index + 1 < tok_vec.nodes.len() {
+        let (name, span) = match (&tok_vec.nodes[*index], &tok_vec.nodes[*index + 1]) {
+            (Any::Token(identifier), Any::Token(bra)) if bra.token_ty == TokenType::SBra => {
+                match &identifier.token_ty {
+                    TokenType::Identifier(name) => (name.clone(), identifier.span),
+                    _ => {
+                        *index += 1;
+                        continue;
                     }
                 }
-                _ => {}
             }
+            _ => {
+                *index += 1;
+                continue;
+            }
+        };
+        // Cerca la parentesi quadra chiusa corrispondente
+        let start = *index + 2;
+        let mut end = start;
+        let mut depth = 1;
+        while end < tok_vec.nodes.len() {
+            if let Any::Token(token) = &tok_vec.nodes[end] {
+                match token.token_ty {
+                    TokenType::SBra => depth += 1,
+                    TokenType::SKet => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            end += 1;
         }
+        if depth != 0 {
+            unreachable!("Errore di parsing: parentesi quadra chiusa mancante dopo {} a {}.", name, span);
+        }
+        let sub_nodes: Vec<Any> = tok_vec.nodes.drain(start..end).collect();
+        // rimuove '[' e ']', resta l'identificatore
+        tok_vec.nodes.drain(*index + 1..*index + 3);
+        let array_index = match parse_operand(sub_nodes) {
+            Some(expr) => expr,
+            None => unreachable!("Errore di parsing: indice non valido per l'array {} a {}.", name, span),
+        };
+        // una dichiarazione sta da sola tra ';', '{' e '}'
+        let separator = |node: Option<&Any>, separators: &[TokenType]| match node {
+            None => true,
+            Some(Any::Token(token)) => separators.contains(&token.token_ty),
+            Some(_) => false,
+        };
+        let previous = index.checked_sub(1).and_then(|previous| tok_vec.nodes.get(previous));
+        let standalone = separator(previous, &[TokenType::Semicolon, TokenType::CBra])
+            && separator(tok_vec.nodes.get(*index + 1), &[TokenType::Semicolon, TokenType::Cket]);
+        let size = array_index.as_any().downcast_ref::<Numeral>().map(|size| size.0);
+        tok_vec.nodes[*index] = match size.filter(|_| standalone) {
+            Some(size) => {
+                arrays::declare(&name, size, span);
+                Any::Statement(Box::new(ArrayDeclaration {
+                    array: name,
+                    size,
+                    span,
+                }))
+            }
+            None => {
+                if !arrays::declared(&name) {
+                    syntax_error(format!("array {} used at {} without being declared", name, span));
+                }
+                Any::ArithmeticExpression(Box::new(ArrayRead {
+                    array: Variable { value: name },
+                    index: array_index,
+                }))
+            }
+        };
         *index += 1;
     }
+}
 
-    if depth != 0 {
-        unreachable!("Errore di parsing: parentesi chiusa mancante.");
+// Espressione aritmetica isolata (indice di un array, argomento di una chiamata), None se i
+// nodi non si riducono a una sola espressione
+fn parse_operand(nodes: Vec<Any>) -> Option<Box<dyn ArithmeticExpression<Q = AbstractInterval>>> {
+    let mut sub_any_vec = AnyVec { nodes };
+    parse_calls(&mut sub_any_vec, &mut 0);
+    parse_arrays(&mut sub_any_vec, &mut 0);
+    parse_conditionals(&mut sub_any_vec, &mut 0);
+    parse_atomic(&mut sub_any_vec, &mut 0);
+    parse_arithmetic_unop(&mut sub_any_vec, &mut 0);
+    parse_arithmetic_expression(&mut sub_any_vec, &mut 0);
+    clean_from_void(&mut sub_any_vec);
+    match sub_any_vec.nodes.as_slice() {
+        [Any::ArithmeticExpression(expr)] => Some(expr.clone_box()),
+        _ => None,
     }
-    let num_removed = *index - start;
-    // Parsiamo la sottoespressione tra start e index-1
-    let sub_tok_vec = tok_vec.nodes.drain(start..*index).collect::<Vec<Any>>();
-
-    // Aggiorna l'indice principale in base alla nuova lunghezza di tok_vec
-    // Sottrai il numero di elementi drenati (index - start) per correggere l'indice
-    *index -= num_removed;
-
-    // Creo il vettore Any contenente solo la sottoespressione da parsare
-    let mut sub_any_vec = AnyVec { nodes: sub_tok_vec };
-
-    // Richiama il parsing della sottoespressione
-    let mut sub_index = 0; // Indice locale per la sottoespressione
-    parse_arithmetic_expression(&mut sub_any_vec, &mut sub_index);
+}
 
-    // Controlla il risultato del parsing
-    if let Some(Any::ArithmeticExpression(expr)) = sub_any_vec.nodes.pop() {
-        expr // Ritorna l'espressione parsata
-    } else {
-        unreachable!("Errore di parsing: expected ArithmeticExpression in sottoespressione.");
+// Espressione booleana isolata (condizione di un'espressione condizionale, postcondizione), None
+// se i nodi non si riducono a una sola espressione
+fn parse_condition(nodes: Vec<Any>) -> Option<Box<dyn BooleanExpression<Q = AbstractInterval>>> {
+    let mut sub_any_vec = AnyVec { nodes };
+    parse_calls(&mut sub_any_vec, &mut 0);
+    parse_arrays(&mut sub_any_vec, &mut 0);
+    parse_conditionals(&mut sub_any_vec, &mut 0);
+    parse_atomic(&mut sub_any_vec, &mut 0);
+    parse_arithmetic_unop(&mut sub_any_vec, &mut 0);
+    parse_arithmetic_expression(&mut sub_any_vec, &mut 0);
+    parse_bool_expression(&mut sub_any_vec, &mut 0);
+    clean_from_void(&mut sub_any_vec);
+    match sub_any_vec.nodes.as_slice() {
+        [Any::BooleanExpression(expr)] => Some(expr.clone_box()),
+        _ => None,
     }
 }
 
-pub fn parse_bool_subexpression(
-    tok_vec: &mut AnyVec,
-    index: &mut usize,
-) -> Box<dyn BooleanExpression<Q = AbstractInterval>> {
-    // Incrementa l'indice per saltare la parentesi aperta
-    *index += 1;
+// Token che può chiudere un operando: dopo di esso `?` apre un'espressione condizionale, altrove
+// è un valore qualsiasi
+fn ends_operand(token_ty: &TokenType) -> bool {
+    matches!(
+        token_ty,
+        TokenType::Identifier(_)
+            | TokenType::Number(_)
+            | TokenType::True
+            | TokenType::False
+            | TokenType::Ket
+            | TokenType::SKet
+    )
+}
 
-    let start = *index;
-    let mut depth = 1; // Traccia la profondità delle parentesi
+// Token della condizione fuori dalle parentesi: operandi, operatori aritmetici, confronti e
+// connettivi
+fn condition_token(token_ty: &TokenType) -> bool {
+    ends_operand(token_ty)
+        || arithmetic_precedence(token_ty).is_some()
+        || is_comparison(token_ty)
+        || matches!(
+            token_ty,
+            TokenType::And
+                | TokenType::Or
+                | TokenType::Not
+                | TokenType::PlusPlus
+                | TokenType::MinusMinus
+        )
+}
 
-    // Cerca la parentesi chiusa corrispondente
+// Espressioni condizionali `b ? e1 : e2`, ridotte sui token dopo chiamate e array. Il `?` dopo un
+// operando apre un'espressione condizionale: la condizione va a sinistra fino al primo
+// delimitatore (`:=`, `(`, `,`, `return`, ...), il ramo then fino ai `:` corrispondenti, il ramo
+// else a destra fino al primo delimitatore, e può essere a sua volta condizionale
+// (`a ? 1 : b ? 2 : 3`). Come in C ha la precedenza più bassa, tra gli operandi di un confronto o
+// di un'espressione aritmetica va tra parentesi
+pub fn parse_conditionals(tok_vec: &mut AnyVec, index: &mut usize) {
+    let opens = |nodes: &[Any], position: usize| match (&nodes[position], position.checked_sub(1)) {
+        (Any::Token(question), Some(previous)) if question.token_ty == TokenType::Question => {
+            match &nodes[previous] {
+                Any::Token(token) => ends_operand(&token.token_ty),
+                Any::Statement(_) => false,
+                _ => true,
+            }
+        }
+        _ => false,
+    };
     while *index < tok_vec.nodes.len() {
-        if let Some(Any::Token(token)) = tok_vec.nodes.get(*index) {
-            match token.token_ty {
-                TokenType::Bra => depth += 1, // Nuova parentesi aperta, aumenta la profondità
-                TokenType::Ket => {
-                    depth -= 1; // Parentesi chiusa, diminuisci la profondità
-                    if depth == 0 {
-                        break; // Trovata la parentesi chiusa corrispondente
-                    }
+        if !opens(&tok_vec.nodes, *index) {
+            *index += 1;
+            continue;
+        }
+        let question = *index;
+        let span = tok_vec.nodes[question].as_token().map(|token| token.span).unwrap_or_default();
+        // Condizione: a sinistra fino a un delimitatore o a una parentesi aperta non chiusa
+        let mut start = question;
+        let mut depth = 0;
+        while start > 0 {
+            match &tok_vec.nodes[start - 1] {
+                Any::Token(token) => match token.token_ty {
+                    TokenType::Ket | TokenType::SKet => depth += 1,
+                    TokenType::Bra | TokenType::SBra if depth == 0 => break,
+                    TokenType::Bra | TokenType::SBra => depth -= 1,
+                    ref token_ty if depth > 0 || condition_token(token_ty) => {}
+                    _ => break,
+                },
+                Any::Statement(_) => break,
+                _ => {}
+            }
+            start -= 1;
+        }
+        // Ramo then: fino ai `:` che corrispondono al `?`
+        let mut colon = question + 1;
+        let mut depth = 0;
+        let mut nested = 0;
+        while colon < tok_vec.nodes.len() {
+            if let Any::Token(token) = &tok_vec.nodes[colon] {
+                match token.token_ty {
+                    TokenType::Bra | TokenType::SBra => depth += 1,
+                    TokenType::Ket | TokenType::SKet => depth -= 1,
+                    TokenType::Question if depth == 0 && opens(&tok_vec.nodes, colon) => nested += 1,
+                    TokenType::Colon if depth == 0 && nested == 0 => break,
+                    TokenType::Colon if depth == 0 => nested -= 1,
+                    _ => {}
                 }
+            }
+            colon += 1;
+        }
+        if colon == tok_vec.nodes.len() {
+            unreachable!("Errore di parsing: ':' mancante nell'espressione condizionale a {}.", span);
+        }
+        // Ramo else: a destra finché resta un'espressione aritmetica
+        let mut end = colon + 1;
+        let mut depth = 0;
+        let mut nested = 0;
+        while end < tok_vec.nodes.len() {
+            match &tok_vec.nodes[end] {
+                Any::Token(token) => match token.token_ty {
+                    TokenType::Bra | TokenType::SBra => depth += 1,
+                    TokenType::Ket | TokenType::SKet if depth == 0 => break,
+                    TokenType::Ket | TokenType::SKet => depth -= 1,
+                    _ if depth > 0 => {}
+                    TokenType::Question if opens(&tok_vec.nodes, end) => nested += 1,
+                    TokenType::Colon if nested > 0 => nested -= 1,
+                    ref token_ty if condition_token(token_ty) => {}
+                    _ => break,
+                },
+                Any::Statement(_) => break,
                 _ => {}
             }
+            end += 1;
         }
-        *index += 1;
-    }
-
-    if depth != 0 {
-        unreachable!("Errore di parsing: parentesi chiusa mancante.");
+        let mut nodes: Vec<Any> = tok_vec.nodes.drain(start..end).collect();
+        let otherwise: Vec<Any> = nodes.drain(colon + 1 - start..).collect();
+        nodes.pop();
+        let then: Vec<Any> = nodes.drain(question + 1 - start..).collect();
+        nodes.pop();
+        let expr = match (parse_condition(nodes), parse_operand(then), parse_operand(otherwise)) {
+            (Some(guard), Some(then), Some(otherwise)) => Conditional {
+                guard,
+                then,
+                otherwise,
+            },
+            _ => unreachable!("Errore di parsing: espressione condizionale malformata a {}.", span),
+        };
+        tok_vec
+            .nodes
+            .insert(start, Any::ArithmeticExpression(Box::new(expr)));
+        *index = start + 1;
     }
-    let num_removed = *index - start;
-    // Parsiamo la sottoespressione tra start e index-1
-    let sub_tok_vec = tok_vec.nodes.drain(start..*index).collect::<Vec<Any>>();
-
-    // Aggiorna l'indice principale in base alla nuova lunghezza di tok_vec
-    // Sottrai il numero di elementi drenati (index - start) per correggere l'indice
-    *index -= num_removed;
-
-    // Creo il vettore Any contenente solo la sottoespressione da parsare
-    let mut sub_any_vec = AnyVec { nodes: sub_tok_vec };
-
-    // Richiama il parsing della sottoespressione
-    let mut sub_index = 0; // Indice locale per la sottoespressione
-    parse_bool_expression(&mut sub_any_vec, &mut sub_index);
+}
 
-    // Controlla il risultato del parsing
-    if let Some(Any::BooleanExpression(expr)) = sub_any_vec.nodes.pop() {
-        expr // Ritorna l'espressione parsata
-    } else {
-        unreachable!("Errore di parsing: expected ArithmeticExpression in sottoespressione.");
+// Chiamate `f(a, b)` di procedure dichiarate e `random(a, b)`, ridotte sui token prima degli
+// altri passi come gli accessi agli array
+pub fn parse_calls(tok_vec: &mut AnyVec, index: &mut usize) {
+    while *index + 1 < tok_vec.nodes.len() {
+        let (name, span) = match (&tok_vec.nodes[*index], &tok_vec.nodes[*index + 1]) {
+            (Any::Token(identifier), Any::Token(bra)) if bra.token_ty == TokenType::Bra => {
+                match &identifier.token_ty {
+                    TokenType::Identifier(name) if procedures::declared(name) => {
+                        (name.clone(), identifier.span)
+                    }
+                    TokenType::Random | TokenType::Print => (identifier.value.clone(), identifier.span),
+                    TokenType::Identifier(name) => syntax_error(format!("procedure {} not declared", name)),
+                    _ => {
+                        *index += 1;
+                        continue;
+                    }
+                }
+            }
+            _ => {
+                *index += 1;
+                continue;
+            }
+        };
+        // Cerca la parentesi chiusa corrispondente, separando gli argomenti sulle virgole
+        let start = *index + 2;
+        let mut end = start;
+        let mut depth = 1;
+        let mut commas = Vec::new();
+        while end < tok_vec.nodes.len() {
+            if let Any::Token(token) = &tok_vec.nodes[end] {
+                match token.token_ty {
+                    TokenType::Bra | TokenType::SBra => depth += 1,
+                    TokenType::Ket | TokenType::SKet => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    TokenType::Comma if depth == 1 => commas.push(end),
+                    _ => {}
+                }
+            }
+            end += 1;
+        }
+        if depth != 0 {
+            unreachable!("Errore di parsing: parentesi chiusa mancante nella chiamata di {} a {}.", name, span);
+        }
+        let mut sub_nodes: Vec<Any> = tok_vec.nodes.drain(start..end).collect();
+        // rimuove '(' e ')', resta l'identificatore
+        tok_vec.nodes.drain(*index + 1..*index + 3);
+        let mut arguments = Vec::new();
+        if !sub_nodes.is_empty() {
+            for comma in commas.iter().rev() {
+                let argument: Vec<Any> = sub_nodes.drain(comma - start..).skip(1).collect();
+                arguments.push(argument);
+            }
+            arguments.push(sub_nodes);
+            arguments.reverse();
+        }
+        let arguments: Vec<Box<dyn ArithmeticExpression<Q = AbstractInterval>>> = arguments
+            .into_iter()
+            .map(|argument| match parse_operand(argument) {
+                Some(expr) => expr,
+                None => unreachable!("Errore di parsing: argomento non valido nella chiamata di {} a {}.", name, span),
+            })
+            .collect();
+        // random(a, b), un valore da a a b
+        if name == "random" {
+            let mut arguments = arguments.into_iter();
+            let count = arguments.len();
+            let range = match (arguments.next(), arguments.next(), arguments.next()) {
+                (Some(lower), Some(upper), None) => (lower, upper),
+                _ => syntax_error(format!(
+                    "random called at {} with {} arguments instead of 2",
+                    span, count
+                )),
+            };
+            tok_vec.nodes[*index] = Any::ArithmeticExpression(Box::new(Random { range: Some(range) }));
+            *index += 1;
+            continue;
+        }
+        // print(e1, e2, ...), uno statement
+        if name == "print" {
+            output::declare(span, &arguments);
+            tok_vec.nodes[*index] = Any::Statement(Box::new(Print { arguments, span }));
+            *index += 1;
+            continue;
+        }
+        if arguments.len() != procedures::arity(&name) {
+            syntax_error(format!(
+                "procedure {} called at {} with {} arguments instead of {}",
+                name,
+                span,
+                arguments.len(),
+                procedures::arity(&name)
+            ));
+        }
+        tok_vec.nodes[*index] = Any::ArithmeticExpression(Box::new(Call {
+            procedure: name,
+            arguments,
+        }));
+        *index += 1;
     }
 }
 
-pub fn parse_bool_expression(tok_vec: &mut AnyVec, index: &mut usize) {
+pub fn parse_arithmetic_unop(tok_vec: &mut AnyVec, index: &mut usize) {
     while *index < tok_vec.nodes.len() {
-        // println!("analyzed tokens {}", tok_vec.nodes[*index]);
-        // Controlla se il nodo attuale è un token
         if let Some(Any::Token(token)) = tok_vec.nodes.get(*index) {
             match token.token_ty {
-                TokenType::And => {
-                    // println!("and found");
-                    let mut j = 0;
-                    while j < tok_vec.nodes.len() {
-                        // println!("{:?}", tok_vec.nodes[j]);
-
-                        j = j + 1;
-                    }
-                    // Prima dell' and si trova l'operando sinistro (left)
+                TokenType::PlusPlus => {
+                    // Assicurati di avere un token variabile prima di `PlusPlus`
                     if *index == 0 {
-                        unreachable!("Errore di parsing: operando sinistro mancante per l'and.");
+                        unreachable!("Errore di parsing: attesa una variabile prima di '++'.");
                     }
-                    let left_node = tok_vec.nodes.remove(*index - 1);
-
-                    let left = match left_node {
-                        Any::BooleanExpression(expr) => expr,
-                        _ => unreachable!(
-                            "Errore di parsing: attesa espressione aritmetica a sinistra dell'and."
-                        ),
-                    };
-
-                    // Dopo l'and, cerca l'operando destro
-                    if *index >= tok_vec.nodes.len() {
-                        unreachable!("Errore di parsing: operando destro mancante per l'and.");
-                    }
-                    // println!("and rhs {}", tok_vec.nodes[*index]);
-                    // Se trovi una parentesi aperta, esegui parse_bool_subexpression
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_bool_subexpression(tok_vec, index)
-                                } else {
-                                    // Token is not a parenthesis, check if it's a valid arithmetic expression
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::BooleanExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'and."),
-                                    }
-                                }
-                            }
-                            Any::BooleanExpression(_expr) => {
-                                let right_node = tok_vec.nodes.remove(*index);
-                                match right_node {
-                                        Any::BooleanExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'and."),
-                                    }
+                    let var_node = tok_vec.nodes.remove(*index - 1); // Estrae il nodo della variabile
+                    let var = match var_node.as_arithmetic_expr() {
+                        Some(expr) => {
+                            if let Some(variable) = expr.as_variable() {
+                                variable
+                            } else {
+                                unreachable!(
+                                    "Errore di parsing: attesa una variabile prima di '++'."
+                                );
                             }
-                            _ => unreachable!(
-                                "Errore di parsing: nodo non riconosciuto a destra dell'and."
-                            ),
                         }
-                    } else {
-                        unreachable!("Errore di parsing: nessun token trovato a destra dell'and.");
+                        None => {
+                            unreachable!("Errore di parsing: attesa una variabile prima di '++'.")
+                        }
+                    };
+                    //qui ho una variabile prima del token ++
+                    // Crea l'assegnamento `i = i + 1`
+                    let plusp = PlusPlus {
+                        var: var.clone_box(),
                     };
-                    // Crea l'oggetto And con left e right
-                    let and_expr = And { left, right };
 
-                    // Reinserisci l'oggetto And nel vettore come ArithmeticExpression
+                    // Inserisci l'oggetto `PlusPlus` nel vettore di parsing
                     tok_vec
                         .nodes
-                        .insert(*index - 1, Any::BooleanExpression(Box::new(and_expr)));
-
-                    //elimino il token contenente l'operatore and
+                        .insert(*index - 1, Any::ArithmeticExpression(Box::new(plusp)));
                     tok_vec.nodes.remove(*index);
                 }
-                TokenType::Or => {
-                    // Prima del or si trova l'operando sinistro (left)
+                TokenType::MinusMinus => {
+                    // Assicurati di avere un token variabile prima di `PlusPlus`
                     if *index == 0 {
-                        unreachable!("Errore di parsing: operando sinistro mancante per l'or.");
+                        unreachable!("Errore di parsing: attesa una variabile prima di '--'.");
                     }
-                    let left_node = tok_vec.nodes.remove(*index - 1);
-
-                    let left = match left_node {
-                        Any::BooleanExpression(expr) => expr,
-                        _ => unreachable!(
-                            "Errore di parsing: attesa espressione aritmetica a sinistra dell'or."
-                        ),
-                    };
-
-                    // Dopo l'or, cerca l'operando destro
-                    if *index >= tok_vec.nodes.len() {
-                        unreachable!("Errore di parsing: operando destro mancante per l'or.");
-                    }
-
-                    // Se trovi una parentesi aperta, esegui parse_boolean_subexpression
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_bool_subexpression(tok_vec, index)
-                                } else {
-                                    // Token is not a parenthesis, check if it's a valid arithmetic expression
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::BooleanExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'or."),
-                                    }
-                                }
-                            }
-                            Any::BooleanExpression(_expr) => {
-                                let right_node = tok_vec.nodes.remove(*index);
-                                match right_node {
-                                        Any::BooleanExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'or."),
-                                    }
-                            }
-                            _ => {
+                    let var_node = tok_vec.nodes.remove(*index - 1); // Estrae il nodo della variabile
+                    let var = match var_node.as_arithmetic_expr() {
+                        Some(expr) => {
+                            if let Some(variable) = expr.as_variable() {
+                                variable
+                            } else {
                                 unreachable!(
-                                    "Errore di parsing: nodo non riconosciuto a destra dell'or."
-                                )
+                                    "Errore di parsing: attesa una variabile prima di '--', found {:?}", expr
+                                );
                             }
                         }
-                    } else {
-                        unreachable!("Errore di parsing: nessun token trovato a destra dell'or.");
+                        None => {
+                            unreachable!("Errore di parsing: attesa una variabile prima di '--'.")
+                        }
+                    };
+                    //qui ho una variabile prima del token ++
+                    // Crea l'assegnamento `i = i + 1`
+                    let minusm = MinusMinus {
+                        var: var.clone_box(),
                     };
-                    // Crea l'oggetto Add con left e right
-                    let or_expr = Or { left, right };
 
-                    // Reinserisci l'oggetto Add nel vettore come ArithmeticExpression
+                    // Inserisci l'oggetto `Assign` nel vettore di parsing
                     tok_vec
                         .nodes
-                        .insert(*index - 1, Any::BooleanExpression(Box::new(or_expr)));
-
-                    tok_vec.nodes.remove(*index);
+                        .insert(*index - 1, Any::ArithmeticExpression(Box::new(minusm)));
                 }
-                TokenType::Equal => {
-                    // Prima del = si trova l'operando sinistro (left)
-                    if *index == 0 {
-                        unreachable!(
-                            "Errore di parsing: operando sinistro mancante per l'op booleano =."
-                        );
-                    }
-                    let left_node = tok_vec.nodes.remove(*index - 1);
-
-                    let left = match left_node {
-                        Any::ArithmeticExpression(expr) => expr,
-                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a sinistra dell'op booleano =."),
-                    };
+                _ => {}
+            }
+        }
+        *index += 1;
+    }
+}
 
-                    // Dopo l'=, cerca l'operando destro
-                    if *index >= tok_vec.nodes.len() {
-                        unreachable!(
-                            "Errore di parsing: operando destro mancante per l'op booleano =."
-                        );
-                    }
-                    // Se trovi una parentesi aperta, esegui parse_boolean_subexpression
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_arithmetic_subexpression(tok_vec, index)
-                                } else {
-                                    // Token is not a parenthesis, check if it's a valid arithmetic expression
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'op booleano =."),
-                                    }
-                                }
-                            },
-                            Any::ArithmeticExpression(_expr) =>{
-                                let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'op booleano =."),
-                                    }
-                                },
-                                _ => unreachable!("Errore di parsing: nodo non riconosciuto a destra dell'op booleano =."),
-                        }
-                    } else {
-                        unreachable!(
-                            "Errore di parsing: nessun token trovato a destra dell'op booleano =."
-                        );
-                    };
-                    // Crea l'oggetto Equal con left e right
-                    let eq_expr = Equal { left, right };
+// Precedenza degli operatori aritmetici binari, come in C: | più debole di ^, poi &, gli shift,
+// + e -, infine * / e %
+fn arithmetic_precedence(token_ty: &TokenType) -> Option<u8> {
+    match token_ty {
+        TokenType::BitOr => Some(1),
+        TokenType::BitXor => Some(2),
+        TokenType::BitAnd => Some(3),
+        TokenType::ShiftLeft | TokenType::ShiftRight => Some(4),
+        TokenType::Plus | TokenType::Minus => Some(5),
+        TokenType::Multiply | TokenType::Divide | TokenType::Modulo => Some(6),
+        _ => None,
+    }
+}
 
-                    // Reinserisci l'oggetto Equal nel vettore come BooleanExpression
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::BooleanExpression(Box::new(eq_expr)));
+fn binary_arithmetic(
+    token_ty: &TokenType,
+    left: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    right: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+) -> Box<dyn ArithmeticExpression<Q = AbstractInterval>> {
+    match token_ty {
+        TokenType::Plus => Box::new(Add { left, right }),
+        TokenType::Minus => Box::new(Minus { left, right }),
+        TokenType::Multiply => Box::new(Product { left, right }),
+        TokenType::Divide => Box::new(Divide { left, right }),
+        TokenType::Modulo => Box::new(Modulo { left, right }),
+        TokenType::BitAnd => Box::new(BitwiseAnd { left, right }),
+        TokenType::BitOr => Box::new(BitwiseOr { left, right }),
+        TokenType::BitXor => Box::new(BitwiseXor { left, right }),
+        TokenType::ShiftLeft => Box::new(ShiftLeft { left, right }),
+        TokenType::ShiftRight => Box::new(ShiftRight { left, right }),
+        _ => unreachable!("Errore di parsing: {:?} non è un operatore aritmetico binario.", token_ty),
+    }
+}
 
-                    //elimino il token contenente l'operatore =
-                    tok_vec.nodes.remove(*index);
-                }
-                TokenType::LessEqual => {
-                    // Prima del <= si trova l'operando sinistro (left)
-                    if *index == 0 {
-                        unreachable!(
-                            "Errore di parsing: operando sinistro mancante per l'op booleano <=."
-                        );
+// Posizione della parentesi che chiude quella aperta in `start`, se tra le due c'è solo
+// un'espressione aritmetica (le guardie dei cicli e le sottoespressioni booleane restano)
+fn arithmetic_group(nodes: &[Any], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (position, node) in nodes.iter().enumerate().skip(start) {
+        match node {
+            Any::ArithmeticExpression(_) => {}
+            Any::Token(token) => match token.token_ty {
+                TokenType::Bra => depth += 1,
+                TokenType::Ket => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(position);
                     }
-                    let left_node = tok_vec.nodes.remove(*index - 1);
+                }
+                ref token_ty if arithmetic_precedence(token_ty).is_some() => {}
+                _ => return None,
+            },
+            _ => return None,
+        }
+    }
+    None
+}
 
-                    let left = match left_node {
-                        Any::ArithmeticExpression(expr) => expr,
-                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a sinistra dell'op booleano <=."),
-                    };
+// Operando che inizia in `start`, un'espressione già ridotta, un gruppo aritmetico tra
+// parentesi o un '-' unario col suo operando, con la posizione che lo segue
+fn arithmetic_operand(
+    nodes: &[Any],
+    start: usize,
+) -> Option<(Box<dyn ArithmeticExpression<Q = AbstractInterval>>, usize)> {
+    match nodes.get(start)? {
+        Any::ArithmeticExpression(expr) => Some((expr.clone_box(), start + 1)),
+        // dove è atteso un operando il '-' è unario, e lega più degli operatori binari
+        Any::Token(token) if token.token_ty == TokenType::Minus => {
+            let (right, next) = arithmetic_operand(nodes, start + 1)?;
+            Some((Box::new(Uminus { right }), next))
+        }
+        Any::Token(token) if token.token_ty == TokenType::Bra => {
+            let end = arithmetic_group(nodes, start)?;
+            match climb_arithmetic(nodes, start + 1, 1) {
+                Some((expr, next)) if next == end => Some((expr, end + 1)),
+                _ => unreachable!(
+                    "Errore di parsing: espressione aritmetica malformata tra le parentesi a {}.",
+                    token.span
+                ),
+            }
+        }
+        _ => None,
+    }
+}
 
-                    // Dopo il <=, cerca l'operando destro
-                    if *index >= tok_vec.nodes.len() {
-                        unreachable!(
-                            "Errore di parsing: operando destro mancante per l'op booleano <=."
-                        );
-                    }
+// Precedence climbing: l'espressione più lunga che inizia in `start` con operatori di precedenza
+// almeno `min_precedence`, associativi a sinistra, e la posizione che la segue
+fn climb_arithmetic(
+    nodes: &[Any],
+    start: usize,
+    min_precedence: u8,
+) -> Option<(Box<dyn ArithmeticExpression<Q = AbstractInterval>>, usize)> {
+    let (mut left, mut next) = arithmetic_operand(nodes, start)?;
+    while let Some(Any::Token(token)) = nodes.get(next) {
+        let Some(precedence) = arithmetic_precedence(&token.token_ty) else {
+            break;
+        };
+        if precedence < min_precedence {
+            break;
+        }
+        let Some((right, after)) = climb_arithmetic(nodes, next + 1, precedence + 1) else {
+            unreachable!(
+                "Errore di parsing: operando destro mancante per '{}' a {}.",
+                token.value, token.span
+            );
+        };
+        left = binary_arithmetic(&token.token_ty, left, right);
+        next = after;
+    }
+    Some((left, next))
+}
 
-                    // Se trovi una parentesi aperta, esegui parse_boolean_subexpression
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_arithmetic_subexpression(tok_vec, index)
-                                } else {
-                                    // Token is not a parenthesis, check if it's a valid arithmetic expression
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'op booleano <=."),
-                                    }
-                                }
-                            },
-                            Any::ArithmeticExpression(_expr) =>{
-                                let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'op booleano <=."),
-                                    }
-                                },
-                                _ => unreachable!("Errore di parsing: nodo non riconosciuto a destra dell'op booleano <=."),
-                        }
-                    } else {
-                        unreachable!(
-                            "Errore di parsing: nessun token trovato a destra dell'op booleano <=."
-                        );
-                    };
-                    // Crea l'oggetto LessEqual con left e right
-                    let leq_expr = LessEqual { left, right };
+// Riduce ogni espressione aritmetica, a partire da `index`, a un solo nodo
+pub fn parse_arithmetic_expression(tok_vec: &mut AnyVec, index: &mut usize) {
+    while *index < tok_vec.nodes.len() {
+        if let Some((expr, end)) = climb_arithmetic(&tok_vec.nodes, *index, 1) {
+            tok_vec
+                .nodes
+                .splice(*index..end, [Any::ArithmeticExpression(expr)]);
+        }
+        // Incrementa l'indice per passare al prossimo token
+        *index += 1;
+    }
+}
 
-                    // Reinserisci l'oggetto LessEqual nel vettore come BooleanExpression
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::BooleanExpression(Box::new(leq_expr)));
+fn is_comparison(token_ty: &TokenType) -> bool {
+    matches!(
+        token_ty,
+        TokenType::Equal
+            | TokenType::NotEqual
+            | TokenType::Less
+            | TokenType::LessEqual
+            | TokenType::Greater
+            | TokenType::GreatEqual
+    )
+}
 
-                    //elimino il token contenente l'operatore <=
-                    tok_vec.nodes.remove(*index);
-                }
-                TokenType::NotEqual => {
-                    // Prima del < si trova l'operando sinistro (left)
-                    if *index == 0 {
-                        unreachable!(
-                            "Errore di parsing: operando sinistro mancante per l'op booleano <."
-                        );
-                    }
-                    let left_node = tok_vec.nodes.remove(*index - 1);
+fn comparison(
+    token_ty: &TokenType,
+    left: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    right: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+) -> Box<dyn BooleanExpression<Q = AbstractInterval>> {
+    match token_ty {
+        TokenType::Equal => Box::new(Equal { left, right }),
+        TokenType::NotEqual => Box::new(NotEqual { left, right }),
+        TokenType::Less => Box::new(Less { left, right }),
+        TokenType::LessEqual => Box::new(LessEqual { left, right }),
+        TokenType::Greater => Box::new(Great { left, right }),
+        TokenType::GreatEqual => Box::new(GreatEqual { left, right }),
+        _ => unreachable!("Errore di parsing: {:?} non è un confronto.", token_ty),
+    }
+}
 
-                    let left = match left_node {
-                        Any::ArithmeticExpression(expr) => expr,
-                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a sinistra dell'op booleano <."),
-                    };
+// Riduce ogni confronto, a partire da `index`, a una espressione booleana
+fn parse_comparisons(tok_vec: &mut AnyVec, index: &mut usize) {
+    while *index < tok_vec.nodes.len() {
+        let Any::Token(token) = &tok_vec.nodes[*index] else {
+            *index += 1;
+            continue;
+        };
+        if !is_comparison(&token.token_ty) {
+            *index += 1;
+            continue;
+        }
+        let expr = match (
+            index.checked_sub(1).map(|left| &tok_vec.nodes[left]),
+            tok_vec.nodes.get(*index + 1),
+        ) {
+            (Some(Any::ArithmeticExpression(left)), Some(Any::ArithmeticExpression(right))) => {
+                comparison(&token.token_ty, left.clone_box(), right.clone_box())
+            }
+            _ => unreachable!(
+                "Errore di parsing: attese due espressioni aritmetiche per '{}' a {}.",
+                token.value, token.span
+            ),
+        };
+        tok_vec
+            .nodes
+            .splice(*index - 1..*index + 2, [Any::BooleanExpression(expr)]);
+    }
+}
 
-                    // Dopo il <, cerca l'operando destro
-                    if *index >= tok_vec.nodes.len() {
-                        unreachable!(
-                            "Errore di parsing: operando destro mancante per l'op booleano <."
-                        );
-                    }
-
-                    // Se trovi una parentesi aperta, esegui parse_boolean_subexpression
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_arithmetic_subexpression(tok_vec, index)
-                                } else {
-                                    // Token is not a parenthesis, check if it's a valid arithmetic expression
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'op booleano <."),
-                                    }
-                                }
-                            },
-                            Any::ArithmeticExpression(_expr) =>{
-                                let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'op booleano <."),
-                                    }
-                                },
-                                _ => unreachable!("Errore di parsing: nodo non riconosciuto a destra dell'op booleano <."),
-                        }
-                    } else {
-                        unreachable!(
-                            "Errore di parsing: nessun token trovato a destra dell'op booleano <."
-                        );
-                    };
-                    // Crea l'oggetto Less con left e right
-                    let less_expr = NotEqual { left, right };
-
-                    // Reinserisci l'oggetto Less nel vettore come BooleanExpression
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::BooleanExpression(Box::new(less_expr)));
-
-                    //elimino il token contenente l'operatore <
-                    tok_vec.nodes.remove(*index);
-                }
-                TokenType::Less => {
-                    // Prima del < si trova l'operando sinistro (left)
-                    if *index == 0 {
-                        unreachable!(
-                            "Errore di parsing: operando sinistro mancante per l'op booleano <."
-                        );
-                    }
-                    let left_node = tok_vec.nodes.remove(*index - 1);
-
-                    let left = match left_node {
-                        Any::ArithmeticExpression(expr) => expr,
-                        t => unreachable!("Errore di parsing: attesa espressione aritmetica a sinistra dell'op booleano < trovato {:?}. " , t ),
-                    };
-
-                    // Dopo il <, cerca l'operando destro
-                    if *index >= tok_vec.nodes.len() {
-                        unreachable!(
-                            "Errore di parsing: operando destro mancante per l'op booleano <."
-                        );
-                    }
-
-                    // Se trovi una parentesi aperta, esegui parse_boolean_subexpression
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_arithmetic_subexpression(tok_vec, index)
-                                } else {
-                                    // Token is not a parenthesis, check if it's a valid arithmetic expression
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'op booleano <."),
-                                    }
-                                }
-                            },
-                            Any::ArithmeticExpression(_expr) =>{
-                                let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'op booleano <."),
-                                    }
-                                },
-                                _ => unreachable!("Errore di parsing: nodo non riconosciuto a destra dell'op booleano <."),
-                        }
-                    } else {
-                        unreachable!(
-                            "Errore di parsing: nessun token trovato a destra dell'op booleano <."
-                        );
-                    };
-                    // Crea l'oggetto Less con left e right
-                    let less_expr = Less { left, right };
-
-                    // Reinserisci l'oggetto Less nel vettore come BooleanExpression
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::BooleanExpression(Box::new(less_expr)));
-
-                    //elimino il token contenente l'operatore <
-                    tok_vec.nodes.remove(*index);
-                }
-                TokenType::GreatEqual => {
-                    // Prima del >= si trova l'operando sinistro (left)
-                    if *index == 0 {
-                        unreachable!(
-                            "Errore di parsing: operando sinistro mancante per l'op booleano >=."
-                        );
-                    }
-                    let left_node = tok_vec.nodes.remove(*index - 1);
-
-                    let left = match left_node {
-                        Any::ArithmeticExpression(expr) => expr,
-                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a sinistra dell'op booleano >=."),
-                    };
-
-                    // Dopo il >=, cerca l'operando destro
-                    if *index >= tok_vec.nodes.len() {
-                        unreachable!(
-                            "Errore di parsing: operando destro mancante per l'op booleano >=."
-                        );
-                    }
-                    // Se trovi una parentesi aperta, esegui parse_arithmetic_subexpression
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_arithmetic_subexpression(tok_vec, index)
-                                } else {
-                                    // Token is not a parenthesis, check if it's a valid arithmetic expression
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'op booleano >=."),
-                                    }
-                                }
-                            },
-                            Any::ArithmeticExpression(_expr) =>{
-                                let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'op booleano >=."),
-                                    }
-                                },
-                                _ => unreachable!("Errore di parsing: nodo non riconosciuto a destra dell'op booleano >=."),
-                        }
-                    } else {
-                        unreachable!(
-                            "Errore di parsing: nessun token trovato a destra dell'op booleano >=."
-                        );
-                    };
-                    // Crea l'oggetto GreatEqual con left e right
-                    let geq_expr = GreatEqual { left, right };
-
-                    // Reinserisci l'oggetto Less nel vettore come BooleanExpression
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::BooleanExpression(Box::new(geq_expr)));
-
-                    //elimino il token contenente l'operatore <
-                    tok_vec.nodes.remove(*index);
-                }
-                TokenType::Greater => {
-                    // Prima del > si trova l'operando sinistro (left)
-                    if *index == 0 {
-                        unreachable!(
-                            "Errore di parsing: operando sinistro mancante per l'op booleano >."
-                        );
-                    }
-                    // println!("great found");
-                    let mut j = 0;
-                    while j < tok_vec.nodes.len() {
-                        // println!("{:?}", tok_vec.nodes[j]);
-
-                        j = j + 1;
-                    }
-                    // println!("great lhs {}", tok_vec.nodes[*index - 1]);
-                    let left_node = tok_vec.nodes.remove(*index - 1);
+// Precedenza dei connettivi, come in C: || più debole di &&
+fn boolean_precedence(token_ty: &TokenType) -> Option<u8> {
+    match token_ty {
+        TokenType::Or => Some(1),
+        TokenType::And => Some(2),
+        _ => None,
+    }
+}
 
-                    let left = match left_node {
-                        Any::ArithmeticExpression(expr) => expr,
-                        t => unreachable!("Errore di parsing: attesa espressione aritmetica a sinistra dell'op booleano > trovato {:?}.", t),
-                    };
+fn binary_boolean(
+    token_ty: &TokenType,
+    left: Box<dyn BooleanExpression<Q = AbstractInterval>>,
+    right: Box<dyn BooleanExpression<Q = AbstractInterval>>,
+) -> Box<dyn BooleanExpression<Q = AbstractInterval>> {
+    match token_ty {
+        TokenType::And => Box::new(And { left, right }),
+        TokenType::Or => Box::new(Or { left, right }),
+        _ => unreachable!("Errore di parsing: {:?} non è un connettivo booleano.", token_ty),
+    }
+}
 
-                    // Dopo il >, cerca l'operando destro
-                    if *index >= tok_vec.nodes.len() {
-                        unreachable!(
-                            "Errore di parsing: operando destro mancante per l'op booleano >."
-                        );
+// Posizione della parentesi che chiude quella aperta in `start`, se tra le due c'è solo
+// un'espressione booleana
+fn boolean_group(nodes: &[Any], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (position, node) in nodes.iter().enumerate().skip(start) {
+        match node {
+            Any::BooleanExpression(_) => {}
+            Any::Token(token) => match token.token_ty {
+                TokenType::Bra => depth += 1,
+                TokenType::Ket => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(position);
                     }
-                    // println!("printing vector after left remove");
-                    // let mut j = 0;
-                    // while j < tok_vec.nodes.len() {
-                    //     println!("{:?}", tok_vec.nodes[j]);
-
-                    //     j = j + 1;
-                    // }
-                    // println!("great rhs {}", tok_vec.nodes[*index]);
-                    // Se trovi una parentesi aperta, esegui parse_arithmetic_subexpression
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_arithmetic_subexpression(tok_vec, index)
-                                } else {
-                                    // Token is not a parenthesis, check if it's a valid arithmetic expression
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'op booleano >."),
-                                    }
-                                }
-                            },
-                            Any::ArithmeticExpression(_expr) =>{
-                                let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra dell'op booleano >."),
-                                    }
-                                },
-                                _ => unreachable!("Errore di parsing: nodo non riconosciuto a destra dell'op booleano >."),
-                        }
-                    } else {
-                        unreachable!(
-                            "Errore di parsing: nessun token trovato a destra dell'op booleano >."
-                        );
-                    };
-
-                    // Crea l'oggetto Grea con left e right
-                    let great_expr = Great { left, right };
-                    // println!("expression extracted {:?}", great_expr);
-                    // println!("printing the vector before insertion ");
-                    // let mut j = 0;
-                    // while j < tok_vec.nodes.len() {
-                    //     // println!("{:?}", tok_vec.nodes[j]);
-
-                    //     j = j + 1;
-                    // }
-                    // Reinserisci l'oggetto Less nel vettore come BooleanExpression
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::BooleanExpression(Box::new(great_expr)));
-
-                    // println!("printing the vector after insertion ");
-                    // let mut j = 0;
-                    // while j < tok_vec.nodes.len() {
-                    //     println!("{:?}", tok_vec.nodes[j]);
-
-                    //     j = j + 1;
-                    // }
-                    // println!("deleting token {}", tok_vec.nodes[*index]);
-                    //elimino il token contenente l'operatore <
-                    tok_vec.nodes.remove(*index);
-                    // *index -= 1;
                 }
-                _ => {}
-            }
+                TokenType::Not => {}
+                ref token_ty if boolean_precedence(token_ty).is_some() => {}
+                _ => return None,
+            },
+            _ => return None,
         }
-        // Incrementa l'indice per passare al prossimo token
-        *index += 1;
     }
+    None
 }
 
-pub fn parse_arithmetic_unop(tok_vec: &mut AnyVec, index: &mut usize) {
-    while *index < tok_vec.nodes.len() {
-        if let Some(Any::Token(token)) = tok_vec.nodes.get(*index) {
-            match token.token_ty {
-                TokenType::PlusPlus => {
-                    // Assicurati di avere un token variabile prima di `PlusPlus`
-                    let var_node = tok_vec.nodes.remove(*index - 1); // Estrae il nodo della variabile
-                    let var = match var_node.as_arithmetic_expr() {
-                        Some(expr) => {
-                            if let Some(variable) = expr.as_variable() {
-                                variable
-                            } else {
-                                unreachable!(
-                                    "Errore di parsing: attesa una variabile prima di '++'."
-                                );
-                            }
-                        }
-                        None => {
-                            unreachable!("Errore di parsing: attesa una variabile prima di '++'.")
-                        }
-                    };
-                    //qui ho una variabile prima del token ++
-                    // Crea l'assegnamento `i = i + 1`
-                    let plusp = PlusPlus {
-                        var: var.clone_box(),
-                    };
-
-                    // Inserisci l'oggetto `PlusPlus` nel vettore di parsing
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::ArithmeticExpression(Box::new(plusp)));
-                    tok_vec.nodes.remove(*index);
-                }
-                TokenType::MinusMinus => {
-                    // Assicurati di avere un token variabile prima di `PlusPlus`
-                    let var_node = tok_vec.nodes.remove(*index - 1); // Estrae il nodo della variabile
-                    let var = match var_node.as_arithmetic_expr() {
-                        Some(expr) => {
-                            if let Some(variable) = expr.as_variable() {
-                                variable
-                            } else {
-                                unreachable!(
-                                    "Errore di parsing: attesa una variabile prima di '--', found {:?}", expr
-                                );
-                            }
-                        }
-                        None => {
-                            unreachable!("Errore di parsing: attesa una variabile prima di '--'.")
-                        }
-                    };
-                    //qui ho una variabile prima del token ++
-                    // Crea l'assegnamento `i = i + 1`
-                    let minusm = MinusMinus {
-                        var: var.clone_box(),
-                    };
-
-                    // Inserisci l'oggetto `Assign` nel vettore di parsing
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::ArithmeticExpression(Box::new(minusm)));
-                }
-                TokenType::Minus => {
-                    // Assicurati che non ci sia un operando a sinistra
-                    if *index > 0 {
-                        // Controlla cosa c'è prima del segno meno
-                        if let Some(node) = tok_vec.nodes.get(*index - 1) {
-                            match node {
-                                Any::ArithmeticExpression(_) => {
-                                    // C'è un'espressione a sinistra, quindi il meno è binario
-                                    // Puoi decidere di gestirlo come operatore binario
-                                    return; // O interrompi qui, a seconda del caso
-                                }
-                                Any::Token(previous_token) => {
-                                    // Controlla se il token precedente è un token che rappresenta
-                                    // un operatore binario o un delimitatore (es. parentesi)
-                                    match previous_token.token_ty {
-                                        TokenType::Plus
-                                        | TokenType::Minus
-                                        | TokenType::Multiply
-                                        | TokenType::Divide
-                                        | TokenType::Bra
-                                        | TokenType::Assign => {
-                                            // Token valido per un operatore unario
-                                        }
-                                        _ => {
-                                            // Se trovi un token non valido per un meno unario, interrompi
-                                            unreachable!("Errore di parsing: operando sinistro non valido per il '-' unario.");
-                                        }
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-
-                    // Se non ci sono operandi a sinistra o il token precedente è valido
-                    //rimuovo il token e continuo
-                    tok_vec.nodes.remove(*index);
-
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_arithmetic_subexpression(tok_vec, index)
-                                } else {
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra del '-' unario."),
-                                    }
-                                }
-                            }
-                            Any::ArithmeticExpression(_expr) => {
-                                let right_node = tok_vec.nodes.remove(*index);
-                                match right_node {
-                                    Any::ArithmeticExpression(expr) => expr,
-                                    _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra del '-' unario."),
-                                }
-                            }
-                            _ => unreachable!(
-                                "Errore di parsing: nodo non riconosciuto a destra del '-' unario."
-                            ),
-                        }
-                    } else {
-                        unreachable!(
-                            "Errore di parsing: nessun token trovato a destra del '-' unario."
-                        );
-                    };
-
-                    let min_expr = Uminus { right };
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::ArithmeticExpression(Box::new(min_expr)));
-                }
-                _ => {}
+// Operando che inizia in `start`, un'espressione già ridotta, una negazione o un gruppo booleano
+// tra parentesi, con la posizione che lo segue
+fn boolean_operand(
+    nodes: &[Any],
+    start: usize,
+) -> Option<(Box<dyn BooleanExpression<Q = AbstractInterval>>, usize)> {
+    match nodes.get(start)? {
+        Any::BooleanExpression(expr) => Some((expr.clone_box(), start + 1)),
+        Any::Token(token) if token.token_ty == TokenType::Not => {
+            let Some((expression, next)) = boolean_operand(nodes, start + 1) else {
+                unreachable!("Errore di parsing: operando mancante per '!' a {}.", token.span);
+            };
+            Some((Box::new(Not { expression }), next))
+        }
+        Any::Token(token) if token.token_ty == TokenType::Bra => {
+            let end = boolean_group(nodes, start)?;
+            match climb_boolean(nodes, start + 1, 1) {
+                Some((expr, next)) if next == end => Some((expr, end + 1)),
+                _ => unreachable!(
+                    "Errore di parsing: espressione booleana malformata tra le parentesi a {}.",
+                    token.span
+                ),
             }
         }
-        *index += 1;
+        _ => None,
     }
 }
 
-pub fn parse_bool_unop(tok_vec: &mut AnyVec, index: &mut usize) {
-    while *index < tok_vec.nodes.len() {
-        if let Some(Any::Token(token)) = tok_vec.nodes.get(*index) {
-            match token.token_ty {
-                TokenType::Not => {
-                    // Assicurati che non ci sia un operando a sinistra
-                    if *index > 0 {
-                        // Controlla cosa c'è prima del segno meno
-                        if let Some(node) = tok_vec.nodes.get(*index - 1) {
-                            match node {
-                                Any::BooleanExpression(_) => {
-                                    // C'è un'espressione a sinistra, quindi il meno è binario
-                                    // Puoi decidere di gestirlo come operatore binario
-                                    return; // interrompo gestione delegata a parse_bool_expression
-                                }
-                                Any::Token(previous_token) => {
-                                    // Controlla se il token precedente è un token che rappresenta
-                                    // un operatore binario o un delimitatore (es. parentesi)
-                                    match previous_token.token_ty {
-                                        TokenType::And
-                                        | TokenType::Or
-                                        | TokenType::Bra
-                                        | TokenType::Assign => {
-                                            // Token valido per un operatore unario
-                                        }
-                                        _ => {
-                                            // Se trovi un token non valido per un meno unario, interrompi
-                                            unreachable!("Errore di parsing: operando sinistro non valido per il '-' unario.");
-                                        }
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-
-                    // Se non ci sono operandi a sinistra o il token precedente è valido
-                    //rimuovo il token e continuo
-                    tok_vec.nodes.remove(*index);
-
-                    let expression = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_bool_subexpression(tok_vec, index)
-                                } else {
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::BooleanExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra del '-' unario."),
-                                    }
-                                }
-                            }
-                            Any::BooleanExpression(_expr) => {
-                                let right_node = tok_vec.nodes.remove(*index);
-                                match right_node {
-                                    Any::BooleanExpression(expr) => expr,
-                                    _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra del '-' unario."),
-                                }
-                            }
-                            _ => unreachable!(
-                                "Errore di parsing: nodo non riconosciuto a destra del '-' unario."
-                            ),
-                        }
-                    } else {
-                        unreachable!(
-                            "Errore di parsing: nessun token trovato a destra del '-' unario."
-                        );
-                    };
-
-                    let min_expr = Not { expression };
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::BooleanExpression(Box::new(min_expr)));
-                }
-                _ => {}
-            }
+// Precedence climbing sui connettivi, come per le espressioni aritmetiche
+fn climb_boolean(
+    nodes: &[Any],
+    start: usize,
+    min_precedence: u8,
+) -> Option<(Box<dyn BooleanExpression<Q = AbstractInterval>>, usize)> {
+    let (mut left, mut next) = boolean_operand(nodes, start)?;
+    while let Some(Any::Token(token)) = nodes.get(next) {
+        let Some(precedence) = boolean_precedence(&token.token_ty) else {
+            break;
+        };
+        if precedence < min_precedence {
+            break;
         }
-        *index += 1;
+        let Some((right, after)) = climb_boolean(nodes, next + 1, precedence + 1) else {
+            unreachable!(
+                "Errore di parsing: operando destro mancante per '{}' a {}.",
+                token.value, token.span
+            );
+        };
+        left = binary_boolean(&token.token_ty, left, right);
+        next = after;
     }
+    Some((left, next))
 }
 
-pub fn parse_arithmetic_expression(tok_vec: &mut AnyVec, index: &mut usize) {
+// Riduce ogni espressione booleana, a partire da `index`, a un solo nodo: prima i confronti, poi
+// `!`, `&&` e `||`. Le parentesi di un gruppo restano quando sono quelle di una guardia
+// (`while (b)`, `assert(b)`), cadono se il gruppo è operando di un connettivo o il lato destro di
+// un assegnamento
+pub fn parse_bool_expression(tok_vec: &mut AnyVec, index: &mut usize) {
+    let start = *index;
+    parse_comparisons(tok_vec, index);
+    *index = start;
     while *index < tok_vec.nodes.len() {
-        // Controlla se il nodo attuale è un token
-        if let Some(Any::Token(token)) = tok_vec.nodes.get(*index) {
-            match token.token_ty {
-                TokenType::Plus => {
-                    // Prima del `+` si trova l'operando sinistro (left)
-                    if *index == 0 {
-                        unreachable!(
-                            "Errore di parsing: operando sinistro mancante per l'addizione."
-                        );
-                    }
-                    let left_node = tok_vec.nodes.remove(*index - 1);
-
-                    let left = match left_node {
-                        Any::ArithmeticExpression(expr) => expr,
-                        _ => unreachable!(
-                            "Errore di parsing: attesa espressione aritmetica a sinistra del '+'."
-                        ),
-                    };
-
-                    // Dopo il `+`, cerca l'operando destro
-                    if *index >= tok_vec.nodes.len() {
-                        unreachable!(
-                            "Errore di parsing: operando destro mancante per l'addizione."
-                        );
-                    }
-                    // Se trovi una parentesi aperta, esegui parse_subexpression
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_arithmetic_subexpression(tok_vec, index)
-                                } else {
-                                    // Token is not a parenthesis, check if it's a valid arithmetic expression
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra del '+'."),
-                                    }
-                                }
-                            }
-                            Any::ArithmeticExpression(_expr) => {
-                                let right_node = tok_vec.nodes.remove(*index);
-                                match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra del '+'."),
-                                    }
-                            }
-                            _ => {
-                                unreachable!(
-                                    "Errore di parsing: nodo non riconosciuto a destra del '+'."
-                                )
-                            }
-                        }
-                    } else {
-                        unreachable!("Errore di parsing: nessun token trovato a destra del '+'.");
-                    };
-                    // Crea l'oggetto Add con left e right
-                    let add_expr = Add { left, right };
-
-                    // Reinserisci l'oggetto Add nel vettore come ArithmeticExpression
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::ArithmeticExpression(Box::new(add_expr)));
-
-                    //elimino il token contenente l'operatore +
-                    tok_vec.nodes.remove(*index);
-                }
-                TokenType::Multiply => {
-                    // Prima del `*` si trova l'operando sinistro (left)
-                    if *index == 0 {
-                        unreachable!(
-                            "Errore di parsing: operando sinistro mancante per la moltiplicazione."
-                        );
-                    }
-                    let left_node = tok_vec.nodes.remove(*index - 1);
-
-                    let left = match left_node {
-                        Any::ArithmeticExpression(expr) => expr,
-                        _ => unreachable!(
-                            "Errore di parsing: attesa espressione aritmetica a sinistra del '*'."
-                        ),
-                    };
-
-                    // Dopo il `*`, cerca l'operando destro
-                    if *index >= tok_vec.nodes.len() {
-                        unreachable!(
-                            "Errore di parsing: operando destro mancante per la moltiplicazione."
-                        );
-                    }
-                    // Se trovi una parentesi aperta, esegui parse_subexpression
-
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_arithmetic_subexpression(tok_vec, index)
-                                } else {
-                                    // Token is not a parenthesis, check if it's a valid arithmetic expression
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra del '*'."),
-                                    }
-                                }
-                            }
-                            //caso in cui ho già un ArithmeticExpression a dx
-                            Any::ArithmeticExpression(_expr) => {
-                                let right_node = tok_vec.nodes.remove(*index);
-                                match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra del '*'."),
-                                    }
-                            }
-                            _ => {
-                                unreachable!(
-                                    "Errore di parsing: nodo non riconosciuto a destra del '*'."
-                                )
-                            }
-                        }
-                    } else {
-                        unreachable!("Errore di parsing: nessun token trovato a destra del '*'.");
-                    };
-
-                    // Crea l'oggetto Product con left e right
-                    let prod_expr = Product { left, right };
-
-                    // Reinserisci l'oggetto Add nel vettore come ArithmeticExpression
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::ArithmeticExpression(Box::new(prod_expr)));
-
-                    //elimino il token contenente l'operatore *
-                    tok_vec.nodes.remove(*index);
-                }
-                TokenType::Minus => {
-                    // Prima del `-` si trova l'operando sinistro (left)
-                    if *index == 0 {
-                        unreachable!(
-                            "Errore di parsing: operando sinistro mancante per la sottrazione."
-                        );
-                    }
-                    let left_node = tok_vec.nodes.remove(*index - 1);
-
-                    let left = match left_node {
-                        Any::ArithmeticExpression(expr) => expr,
-                        _ => unreachable!(
-                            "Errore di parsing: attesa espressione aritmetica a sinistra del '-'."
-                        ),
-                    };
-                    // Dopo il `-`, cerca l'operando destro
-                    if *index >= tok_vec.nodes.len() {
-                        unreachable!(
-                            "Errore di parsing: operando destro mancante per la sottrazione."
-                        );
-                    }
-                    // Se trovi una parentesi aperta, esegui parse_subexpression
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_arithmetic_subexpression(tok_vec, index)
-                                } else {
-                                    // Token is not a parenthesis, check if it's a valid arithmetic expression
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra del '-'."),
-                                    }
-                                }
-                            }
-                            //caso in cui ho già un ArithmeticExpression a dx
-                            Any::ArithmeticExpression(_expr) => {
-                                let right_node = tok_vec.nodes.remove(*index);
-                                match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra del '-'."),
-                                    }
-                            }
-                            _ => {
-                                unreachable!(
-                                    "Errore di parsing: nodo non riconosciuto a destra del '-'."
-                                )
-                            }
-                        }
-                    } else {
-                        unreachable!("Errore di parsing: nessun token trovato a destra del '-'.");
-                    };
-
-                    // Crea l'oggetto Product con left e right
-                    let diff_expr = Minus { left, right };
-
-                    // Reinserisci l'oggetto Add nel vettore come ArithmeticExpression
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::ArithmeticExpression(Box::new(diff_expr)));
-
-                    //elimino il token contenente l'operatore *
-                    tok_vec.nodes.remove(*index);
-                }
-                TokenType::Divide => {
-                    // Prima del `/` si trova l'operando sinistro (left)
-                    if *index == 0 {
-                        unreachable!(
-                            "Errore di parsing: operando sinistro mancante per la divisione."
-                        );
-                    }
-                    let left_node = tok_vec.nodes.remove(*index - 1);
-
-                    let left = match left_node {
-                        Any::ArithmeticExpression(expr) => expr,
-                        _ => unreachable!(
-                            "Errore di parsing: attesa espressione aritmetica a sinistra del '/'."
+        let climb = match &tok_vec.nodes[*index] {
+            Any::BooleanExpression(_) => true,
+            Any::Token(token) if token.token_ty == TokenType::Not => true,
+            Any::Token(token) if token.token_ty == TokenType::Bra => {
+                boolean_group(&tok_vec.nodes, *index).is_some_and(|end| {
+                    let connective = matches!(
+                        tok_vec.nodes.get(end + 1),
+                        Some(Any::Token(next)) if boolean_precedence(&next.token_ty).is_some()
+                    );
+                    let assigned = match index.checked_sub(1) {
+                        None => true,
+                        Some(previous) => matches!(
+                            &tok_vec.nodes[previous],
+                            Any::Token(previous) if previous.token_ty == TokenType::Assign
                         ),
                     };
-                    // Dopo il `/`, cerca l'operando destro
-                    if *index >= tok_vec.nodes.len() {
-                        unreachable!(
-                            "Errore di parsing: operando destro mancante per la divisione."
-                        );
-                    }
-                    // Se trovi una parentesi aperta, esegui parse_subexpression
-
-                    let right = if let Some(node) = tok_vec.nodes.get(*index) {
-                        match node {
-                            Any::Token(token) => {
-                                if let TokenType::Bra = token.token_ty {
-                                    parse_arithmetic_subexpression(tok_vec, index)
-                                } else {
-                                    // Token is not a parenthesis, check if it's a valid arithmetic expression
-                                    let right_node = tok_vec.nodes.remove(*index);
-                                    match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra del '/'."),
-                                    }
-                                }
-                            }
-                            //caso in cui ho già un ArithmeticExpression a dx
-                            Any::ArithmeticExpression(_expr) => {
-                                let right_node = tok_vec.nodes.remove(*index);
-                                match right_node {
-                                        Any::ArithmeticExpression(expr) => expr,
-                                        _ => unreachable!("Errore di parsing: attesa espressione aritmetica a destra del '/'."),
-                                    }
-                            }
-                            _ => {
-                                unreachable!(
-                                    "Errore di parsing: nodo non riconosciuto a destra del '/'."
-                                )
-                            }
-                        }
-                    } else {
-                        unreachable!("Errore di parsing: nessun token trovato a destra del '/'.");
-                    };
-
-                    // Crea l'oggetto Divide con left e right
-                    let div_expr = Divide { left, right };
-
-                    // Reinserisci l'oggetto Add nel vettore come ArithmeticExpression
-                    tok_vec
-                        .nodes
-                        .insert(*index - 1, Any::ArithmeticExpression(Box::new(div_expr)));
-
-                    //elimino il token contenente l'operatore *
-                    tok_vec.nodes.remove(*index);
-                }
-
-                _ => {}
+                    connective || assigned
+                })
+            }
+            _ => false,
+        };
+        if climb {
+            if let Some((expr, end)) = climb_boolean(&tok_vec.nodes, *index, 1) {
+                tok_vec
+                    .nodes
+                    .splice(*index..end, [Any::BooleanExpression(expr)]);
             }
         }
-        // Incrementa l'indice per passare al prossimo token
         *index += 1;
     }
 }
@@ -1634,6 +1320,121 @@ pub fn parse_for_block(
     Some((init, guard, increment))
 }
 
+// Casi di uno switch: il blocco `{ case k: S; ...; default: S }` in `index`, che viene rimosso
+fn parse_switch_cases(
+    any_vec: &mut AnyVec,
+    index: &mut usize,
+    span: Span,
+) -> (Vec<Case>, Box<dyn Statement<Q = AbstractInterval>>) {
+    match any_vec.nodes.get(*index) {
+        Some(Any::Token(tok)) if tok.token_ty == TokenType::CBra => {}
+        _ => unreachable!("Errore di parsing: atteso '{{' dopo il selettore dello switch a {}.", span),
+    }
+    // Cerca la graffa chiusa corrispondente
+    let mut end = *index + 1;
+    let mut depth = 1;
+    while end < any_vec.nodes.len() {
+        if let Any::Token(token) = &any_vec.nodes[end] {
+            match token.token_ty {
+                TokenType::CBra => depth += 1,
+                TokenType::Cket => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        end += 1;
+    }
+    if depth != 0 {
+        unreachable!("Errore di parsing: parentesi graffa chiusa mancante nello switch a {}.", span);
+    }
+    let mut nodes: Vec<Any> = any_vec.nodes.drain(*index..=end).collect();
+    nodes.pop();
+    nodes.remove(0);
+
+    // Divide i nodi sui `case` e `default` al primo livello
+    let mut branches: Vec<Vec<Any>> = Vec::new();
+    let mut depth = 0;
+    for node in nodes {
+        if let Any::Token(token) = &node {
+            match token.token_ty {
+                TokenType::CBra => depth += 1,
+                TokenType::Cket => depth -= 1,
+                TokenType::Case | TokenType::Default if depth == 0 => branches.push(Vec::new()),
+                _ => {}
+            }
+        }
+        match branches.last_mut() {
+            Some(branch) => branch.push(node),
+            None => unreachable!("Errore di parsing: atteso 'case' o 'default' nello switch a {}.", span),
+        }
+    }
+
+    let mut cases: Vec<Case> = Vec::new();
+    let mut default = None;
+    for mut branch in branches {
+        let keyword = branch.remove(0);
+        let label = match keyword.as_token().map(|token| &token.token_ty) {
+            Some(TokenType::Case) => {
+                // l'etichetta è un'espressione costante
+                let label = match branch.first() {
+                    Some(Any::ArithmeticExpression(expr)) if expr.extract_variables().is_empty() => {
+                        expr.evaluate(&mut State::new())
+                    }
+                    _ => unreachable!("Errore di parsing: attesa un'etichetta costante dopo 'case' nello switch a {}.", span),
+                };
+                branch.remove(0);
+                if cases.iter().any(|(other, _)| *other == label) {
+                    syntax_error(format!("case {} repeated in the switch at {}", label, span));
+                }
+                Some(label)
+            }
+            _ => {
+                if default.is_some() {
+                    syntax_error(format!("default repeated in the switch at {}", span));
+                }
+                None
+            }
+        };
+        match branch.first() {
+            Some(Any::Token(tok)) if tok.token_ty == TokenType::Colon => {
+                branch.remove(0);
+            }
+            _ => unreachable!("Errore di parsing: atteso ':' dopo l'etichetta nello switch a {}.", span),
+        }
+        // il ';' che separa il caso dal successivo
+        if let Some(Any::Token(tok)) = branch.last() {
+            if tok.token_ty == TokenType::Semicolon {
+                branch.pop();
+            }
+        }
+        // un caso vuoto non fa niente
+        let body = if branch.is_empty() {
+            Box::new(Skip { span: Span::default() })
+        } else {
+            // il corpo è parsato come un blocco tra graffe
+            branch.insert(0, Any::Token(Token::new("{".to_string(), TokenType::CBra)));
+            branch.push(Any::Token(Token::new("}".to_string(), TokenType::Cket)));
+            let mut block = AnyVec { nodes: branch };
+            parse_substatement_block(&mut block, &mut 0).unwrap_or_else(|| {
+                unreachable!("Errore di parsing: atteso uno statement in un caso dello switch a {}.", span)
+            })
+        };
+        match label {
+            Some(label) => cases.push((label, body)),
+            None => default = Some(body),
+        }
+    }
+    let default: Box<dyn Statement<Q = AbstractInterval>> = match default {
+        Some(default) => default,
+        None => Box::new(Skip { span: Span::default() }),
+    };
+    (cases, default)
+}
+
 pub fn parse_substatement_block(
     any_vec: &mut AnyVec,
     index: &mut usize,
@@ -1692,9 +1493,58 @@ pub fn parse_assignment(any_vec: &mut AnyVec, index: &mut usize) {
         if let Some(Any::Token(token)) = any_vec.nodes.get(*index) {
             match token.token_ty {
                 TokenType::Assign => {
+                    let span = token.span;
                     any_vec.nodes.remove(*index);
                     // Controlla che ci sia una variabile prima dell'assegnamento
                     let var_node = any_vec.nodes.remove(*index - 1); // Estrae il nodo della variabile
+                    // assegnamento di un elemento di un array, a[i] := e
+                    if let Some(element) = var_node
+                        .as_arithmetic_expr()
+                        .and_then(|expr| expr.as_any().downcast_ref::<ArrayRead>())
+                    {
+                        let expr = match any_vec.nodes.remove(*index - 1).as_arithmetic_expr() {
+                            Some(arith_expression) => arith_expression.clone_box(),
+                            None => unreachable!(
+                                "Errore di parsing: attesa un'espressione aritmetica a destra di ':='."
+                            ),
+                        };
+                        let assignment_stmt = ArrayAssign {
+                            array: Variable {
+                                value: element.array.value.clone(),
+                            },
+                            index: element.index.clone_box(),
+                            expr,
+                            span,
+                        };
+                        any_vec
+                            .nodes
+                            .insert(*index - 1, Any::Statement(Box::new(assignment_stmt)));
+                        *index += 1;
+                        continue;
+                    }
+                    // assegnamento di una variabile booleana, b := guardia
+                    if let Some(var) = var_node.as_boolean_expr().and_then(|expr| expr.as_variable())
+                    {
+                        let guard = match any_vec.nodes.remove(*index - 1).as_boolean_expr() {
+                            Some(guard) => guard.clone_box(),
+                            None => syntax_error(format!(
+                                "boolean variable {} assigned something other than a boolean at {}",
+                                var.value, span
+                            )),
+                        };
+                        let assignment_stmt = BoolAssign {
+                            var: Variable {
+                                value: var.value.clone(),
+                            },
+                            relation: booleans::relate(guard.as_ref()),
+                            guard,
+                            span,
+                        };
+                        any_vec
+                            .nodes
+                            .insert(*index - 1, Any::Statement(Box::new(assignment_stmt)));
+                        continue;
+                    }
                     let var = match var_node.as_arithmetic_expr() {
                         Some(expr) => {
                             if let Some(variable) = expr.as_variable() {
@@ -1722,6 +1572,7 @@ pub fn parse_assignment(any_vec: &mut AnyVec, index: &mut usize) {
                     let assignment_stmt = Assign {
                         var_name: var.clone_box(),
                         expr: expr.clone_box(),
+                        span,
                     };
                     any_vec
                         .nodes
@@ -1736,6 +1587,44 @@ pub fn parse_assignment(any_vec: &mut AnyVec, index: &mut usize) {
     }
 }
 
+// Il punto di programma di un assegnamento è la posizione del suo bersaglio, `x` in `x := e` e
+// `a` in `a[i] := e`: la si copia sul token `:=` prima che le altre passate riducano il bersaglio
+fn anchor_assignments(any_vec: &mut AnyVec) {
+    for position in 1..any_vec.nodes.len() {
+        let is_assign = matches!(
+            &any_vec.nodes[position],
+            Any::Token(token) if token.token_ty == TokenType::Assign
+        );
+        if !is_assign {
+            continue;
+        }
+        // salta l'indice `[ ... ]` di un elemento di array fino al nome
+        let mut target = position - 1;
+        let mut depth = 0;
+        loop {
+            match any_vec.nodes[target].as_token().map(|token| &token.token_ty) {
+                Some(TokenType::SKet) => depth += 1,
+                Some(TokenType::SBra) => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 || target == 0 {
+                break;
+            }
+            target -= 1;
+        }
+        if target + 1 < position && target > 0 {
+            target -= 1;
+        }
+        let span = match any_vec.nodes.get(target) {
+            Some(Any::Token(token)) => token.span,
+            _ => continue,
+        };
+        if let Any::Token(assign) = &mut any_vec.nodes[position] {
+            assign.span = span;
+        }
+    }
+}
+
 pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
     while *index < any_vec.nodes.len() {
         if let Some(Any::Token(token)) = any_vec.nodes.get(*index) {
@@ -1808,9 +1697,19 @@ pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
                     *index -= 1;
                 }
                 TokenType::If => {
+                    let span = token.span;
                     let startpos = index.clone();
                     // Rimuove il token `If`
                     any_vec.nodes.remove(*index);
+                    // la guardia può stare tra parentesi, `if (b) then`
+                    if let [Any::Token(bra), Any::BooleanExpression(_), Any::Token(ket), ..] =
+                        &any_vec.nodes[*index..]
+                    {
+                        if bra.token_ty == TokenType::Bra && ket.token_ty == TokenType::Ket {
+                            any_vec.nodes.remove(*index + 2);
+                            any_vec.nodes.remove(*index);
+                        }
+                    }
                     // Controlla che l'elemento in `any_vec.nodes[index]` sia una `BooleanExpression`
                     let guard = match any_vec.nodes.get(*index) {
                         Some(Any::BooleanExpression(expr)) => expr.clone_box(),
@@ -1856,6 +1755,7 @@ pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
                                 guard,
                                 true_expr: then_expr.clone_box(),
                                 false_expr: else_expr.clone_box(),
+                                span,
                             };
 
                             // Inserisce il risultato `IfThenElse` in `any_vec.nodes` alla posizione originale
@@ -1864,32 +1764,132 @@ pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
                                 .insert(startpos, Any::Statement(Box::new(if_stmt)));
                         } else {
                             // Caso in cui non c'è il token `else`, quindi inserisce uno statement `Skip`
-                            let skip_stmt = Skip;
+                            let skip_stmt = Skip { span: Span::default() };
                             let if_stmt = IfThenElse {
                                 guard,
                                 true_expr: then_expr.clone_box(),
                                 false_expr: Box::new(skip_stmt),
+                                span,
                             };
 
                             any_vec
                                 .nodes
                                 .insert(startpos, Any::Statement(Box::new(if_stmt)));
                         }
-                    } else {
-                        // Caso in cui non c'è il token `else`, quindi inserisce uno statement `Skip`
-                        let skip_stmt = Skip;
-                        let if_stmt = IfThenElse {
-                            guard,
-                            true_expr: then_expr.clone_box(),
-                            false_expr: Box::new(skip_stmt),
-                        };
-
-                        any_vec
-                            .nodes
-                            .insert(startpos, Any::Statement(Box::new(if_stmt)));
+                    } else {
+                        // Caso in cui non c'è il token `else`, quindi inserisce uno statement `Skip`
+                        let skip_stmt = Skip { span: Span::default() };
+                        let if_stmt = IfThenElse {
+                            guard,
+                            true_expr: then_expr.clone_box(),
+                            false_expr: Box::new(skip_stmt),
+                            span,
+                        };
+
+                        any_vec
+                            .nodes
+                            .insert(startpos, Any::Statement(Box::new(if_stmt)));
+                    }
+                }
+                TokenType::Either => {
+                    let span = token.span;
+                    let startpos = *index;
+                    // Rimuove il token `either`
+                    any_vec.nodes.remove(*index);
+                    let first = parse_substatement_block(any_vec, index).unwrap_or_else(|| {
+                        unreachable!("Errore di parsing: atteso uno statement dopo 'either'.")
+                    });
+                    clean_curly_braces(any_vec, &mut 0);
+                    match any_vec.nodes.get(*index) {
+                        Some(Any::Token(tok)) if tok.token_ty == TokenType::OrElse => {
+                            any_vec.nodes.remove(*index);
+                        }
+                        _ => unreachable!(
+                            "Errore di parsing: atteso 'or' dopo il primo blocco di 'either' a {}.",
+                            span
+                        ),
+                    }
+                    let second = parse_substatement_block(any_vec, index).unwrap_or_else(|| {
+                        unreachable!("Errore di parsing: atteso uno statement dopo 'or'.")
+                    });
+                    let either_stmt = Either {
+                        first,
+                        second,
+                        span,
+                    };
+                    any_vec
+                        .nodes
+                        .insert(startpos, Any::Statement(Box::new(either_stmt)));
+                }
+                TokenType::Switch => {
+                    let span = token.span;
+                    let startpos = *index;
+                    // Rimuove il token `switch`, il selettore può stare tra parentesi
+                    any_vec.nodes.remove(*index);
+                    if let [Any::Token(bra), Any::ArithmeticExpression(_), Any::Token(ket), ..] =
+                        &any_vec.nodes[*index..]
+                    {
+                        if bra.token_ty == TokenType::Bra && ket.token_ty == TokenType::Ket {
+                            any_vec.nodes.remove(*index + 2);
+                            any_vec.nodes.remove(*index);
+                        }
+                    }
+                    let selector = match any_vec.nodes.get(*index) {
+                        Some(Any::ArithmeticExpression(expr)) => expr.clone_box(),
+                        _ => unreachable!(
+                            "Errore di parsing: attesa un'espressione aritmetica dopo 'switch' a {}.",
+                            span
+                        ),
+                    };
+                    any_vec.nodes.remove(*index);
+                    let (cases, default) = parse_switch_cases(any_vec, index, span);
+                    let switch_stmt = Switch {
+                        selector,
+                        cases,
+                        default,
+                        span,
+                    };
+                    any_vec
+                        .nodes
+                        .insert(startpos, Any::Statement(Box::new(switch_stmt)));
+                }
+                TokenType::Var => {
+                    let span = token.span;
+                    let startpos = *index;
+                    // Rimuove il token `var`, segue l'assegnamento della variabile locale
+                    any_vec.nodes.remove(*index);
+                    let init = match any_vec.nodes.get(*index) {
+                        Some(Any::Statement(stmt)) => stmt.clone_box(),
+                        _ => unreachable!(
+                            "Errore di parsing: atteso un assegnamento dopo 'var' a {}.",
+                            span
+                        ),
+                    };
+                    any_vec.nodes.remove(*index);
+                    match any_vec.nodes.get(*index) {
+                        Some(Any::Token(tok)) if tok.token_ty == TokenType::In => {
+                            any_vec.nodes.remove(*index);
+                        }
+                        _ => unreachable!(
+                            "Errore di parsing: atteso 'in' dopo l'assegnamento di 'var' a {}.",
+                            span
+                        ),
                     }
+                    let body = parse_substatement_block(any_vec, index).unwrap_or_else(|| {
+                        unreachable!("Errore di parsing: atteso un blocco dopo 'in'.")
+                    });
+                    let local_stmt = Local {
+                        var: scopes::local(init.as_ref()),
+                        init,
+                        body,
+                        span,
+                    };
+                    any_vec
+                        .nodes
+                        .insert(startpos, Any::Statement(Box::new(local_stmt)));
                 }
                 TokenType::While => {
+                    let span = token.span;
                     // Rimozione del token `While` dal vettore e check del token aperto `(`
                     any_vec.nodes.remove(*index);
                     let open_paren = any_vec.nodes.get(*index);
@@ -1938,11 +1938,11 @@ pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
                     // Utilizza parse_statement_block per ottenere il body del ciclo `while`
                     let body = match parse_substatement_block(any_vec, index) {
                         Some(statement) => statement,
-                        None => Box::new(Skip), // Se il body è vuoto, utilizza uno statement Skip come default
+                        None => Box::new(Skip { span: Span::default() }), // Se il body è vuoto, utilizza uno statement Skip come default
                     };
 
                     // Creazione dell'oggetto While
-                    let while_stmt = While { guard, body };
+                    let while_stmt = While { guard, body, span };
 
                     // Inserimento del `while` statement nel vettore any_vec.nodes
 
@@ -1950,7 +1950,40 @@ pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
                         .nodes
                         .insert(body_start_index, Any::Statement(Box::new(while_stmt)));
                 }
+                TokenType::Assert => {
+                    let span = token.span;
+                    // Rimozione del token `assert` e check della parentesi aperta `(`
+                    any_vec.nodes.remove(*index);
+                    match any_vec.nodes.get(*index) {
+                        Some(Any::Token(t)) if t.token_ty == TokenType::Bra => {
+                            any_vec.nodes.remove(*index);
+                        }
+                        _ => unreachable!(
+                            "Errore di parsing: attesa una parentesi aperta '(' dopo 'assert'."
+                        ),
+                    }
+                    let guard = match any_vec.nodes.get(*index) {
+                        Some(Any::BooleanExpression(expr)) => expr.clone_box(),
+                        _ => unreachable!(
+                            "Errore di parsing: attesa una espressione booleana dopo 'assert'."
+                        ),
+                    };
+                    any_vec.nodes.remove(*index);
+                    match any_vec.nodes.get(*index) {
+                        Some(Any::Token(t)) if t.token_ty == TokenType::Ket => {
+                            any_vec.nodes.remove(*index);
+                        }
+                        _ => unreachable!(
+                            "Errore di parsing: attesa una parentesi chiusa ')' dopo l'asserzione."
+                        ),
+                    }
+                    let assert_stmt = Assert { guard, span };
+                    any_vec
+                        .nodes
+                        .insert(*index, Any::Statement(Box::new(assert_stmt)));
+                }
                 TokenType::For => {
+                    let span = token.span;
                     any_vec.nodes.remove(*index);
                     let (init, guard, increment);
                     // Controlla la presenza di '(' dopo 'for'
@@ -1979,7 +2012,7 @@ pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
                     let body_start_index = *index;
                     let body = match parse_substatement_block(any_vec, index) {
                         Some(statement) => statement,
-                        None => Box::new(Skip), // Se il body è vuoto, utilizza uno statement Skip come default
+                        None => Box::new(Skip { span: Span::default() }), // Se il body è vuoto, utilizza uno statement Skip come default
                     };
 
                     // Creazione dell'oggetto for
@@ -1988,6 +2021,7 @@ pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
                         guard,
                         increment,
                         body,
+                        span,
                     };
 
                     // Inserimento del `for` statement nel vettore any_vec.nodes
@@ -1997,6 +2031,7 @@ pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
                         .insert(body_start_index, Any::Statement(Box::new(for_stmt)));
                 }
                 TokenType::Repeat => {
+                    let span = token.span;
                     //remove repeat token
                     any_vec.nodes.remove(*index);
                     let open_paren = any_vec.nodes.get(*index);
@@ -2013,7 +2048,7 @@ pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
                     // Utilizza parse_statement_block per ottenere il body del ciclo `while`
                     let body = match parse_substatement_block(any_vec, index) {
                         Some(statement) => statement,
-                        None => Box::new(Skip), // Se il body è vuoto, utilizza uno statement Skip come default
+                        None => Box::new(Skip { span: Span::default() }), // Se il body è vuoto, utilizza uno statement Skip come default
                     };
 
                     //match del token until
@@ -2031,13 +2066,15 @@ pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
                             "Errore di parsing: atteso un token 'until' dopo il body del ciclo."
                         );
                     }
-                    let mut j = 0;
-                    while j < any_vec.nodes.len() {
-                        println!("{:?}", any_vec.nodes[j]);
+                    if text_output() {
+                        let mut j = 0;
+                        while j < any_vec.nodes.len() {
+                            println!("{:?}", any_vec.nodes[j]);
 
-                        j = j + 1;
+                            j = j + 1;
+                        }
+                        println!("trying to remove {:?}", any_vec.nodes.get(*index));
                     }
-                    println!("trying to remove {:?}", any_vec.nodes.get(*index));
                     let open_paren = any_vec.nodes.get(*index);
                     if let Some(Any::Token(t)) = open_paren {
                         if t.token_ty != TokenType::Bra {
@@ -2058,16 +2095,18 @@ pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
                         ),
                     };
                     any_vec.nodes.remove(*index);
-                    let repeat_until_statement = RepeatUntil { body, guard };
+                    let repeat_until_statement = RepeatUntil { body, guard, span };
                     any_vec.nodes.insert(
                         body_start_index,
                         Any::Statement(Box::new(repeat_until_statement)),
                     );
-                    let mut j = 0;
-                    while j < any_vec.nodes.len() {
-                        println!("{:?}", any_vec.nodes[j]);
+                    if text_output() {
+                        let mut j = 0;
+                        while j < any_vec.nodes.len() {
+                            println!("{:?}", any_vec.nodes[j]);
 
-                        j = j + 1;
+                            j = j + 1;
+                        }
                     }
                     any_vec.nodes.remove(*index + 1);
                 }
@@ -2078,7 +2117,437 @@ pub fn parse_statement(any_vec: &mut AnyVec, index: &mut usize) {
     }
 }
 
-pub fn analyze(program: String) {
+// Passi di riduzione di un programma (o del corpo di una procedura) da token a statement
+fn parse_passes(any_vec: &mut AnyVec) {
+    check_jumps(any_vec);
+    anchor_assignments(any_vec);
+    let mut index: usize;
+    index = 0;
+    parse_calls(any_vec, &mut index);
+    index = 0;
+    parse_arrays(any_vec, &mut index);
+    index = 0;
+    parse_conditionals(any_vec, &mut index);
+    index = 0;
+    parse_atomic(any_vec, &mut index);
+    index = 0;
+    parse_arithmetic_unop(any_vec, &mut index);
+    // println!("before bool parsing ");
+    // let mut j = 0;
+    // while j < any_vec.nodes.len() {
+    //     println!("{:?}", any_vec.nodes[j]);
+
+    //     j = j + 1;
+    // }
+    //arithmetic expressions
+    index = 0;
+    parse_arithmetic_expression(any_vec, &mut index);
+    index = 0;
+    parse_bool_expression(any_vec, &mut index);
+    // println!("bool parsed");
+    // let mut j = 0;
+    // while j < any_vec.nodes.len() {
+    //     println!("{:?}", any_vec.nodes[j]);
+
+    //     j = j + 1;
+    // }
+    index = 0;
+    parse_assignment(any_vec, &mut index);
+    //statements
+    index = 0;
+    parse_statement(any_vec, &mut index);
+    clean_from_void(any_vec);
+}
+
+// `break` e `continue` devono stare nel corpo di un ciclo: il corpo è il primo blocco `{ }` dopo
+// `while`, `for` o `repeat`
+fn check_jumps(any_vec: &AnyVec) {
+    let mut blocks: Vec<bool> = Vec::new(); // per ogni blocco aperto, se è il corpo di un ciclo
+    let mut loop_keyword = false;
+    for node in &any_vec.nodes {
+        let Any::Token(token) = node else {
+            continue;
+        };
+        match token.token_ty {
+            TokenType::While | TokenType::For | TokenType::Repeat => loop_keyword = true,
+            TokenType::CBra => {
+                blocks.push(loop_keyword);
+                loop_keyword = false;
+            }
+            TokenType::Cket => {
+                blocks.pop();
+            }
+            TokenType::Break | TokenType::Continue if !blocks.contains(&true) => {
+                syntax_error(format!("{} at {} outside of a loop body", token.value, token.span))
+            }
+            _ => {}
+        }
+    }
+}
+
+// Variabili booleane: quelle a cui il programma assegna una espressione booleana, cioè un
+// confronto, true, false, un connettivo o un'altra variabile booleana. Sono dichiarate prima di
+// ridurre le espressioni, così `b` diventa una espressione booleana ovunque compaia
+fn declare_booleans(any_vec: &AnyVec) {
+    let tokens: Vec<&Token> = any_vec.nodes.iter().filter_map(Any::as_token).collect();
+    // lato destro di ogni assegnamento `x := ...`, fino a `;`, `}` o alla `)` che lo chiude
+    let mut assignments = Vec::new();
+    for (position, pair) in tokens.windows(2).enumerate() {
+        let (TokenType::Identifier(name), TokenType::Assign) = (&pair[0].token_ty, &pair[1].token_ty)
+        else {
+            continue;
+        };
+        let mut depth = 0;
+        let mut rhs = Vec::new();
+        for token in &tokens[position + 2..] {
+            match token.token_ty {
+                TokenType::Bra => depth += 1,
+                TokenType::Ket if depth == 0 => break,
+                TokenType::Ket => depth -= 1,
+                TokenType::Semicolon | TokenType::Cket | TokenType::CBra if depth == 0 => break,
+                _ => {}
+            }
+            rhs.push(&token.token_ty);
+        }
+        assignments.push((name.clone(), strip_conditionals(rhs)));
+    }
+    let boolean = |rhs: &[&TokenType]| {
+        rhs.iter().any(|token_ty| {
+            matches!(
+                token_ty,
+                TokenType::Equal
+                    | TokenType::NotEqual
+                    | TokenType::Less
+                    | TokenType::LessEqual
+                    | TokenType::Greater
+                    | TokenType::GreatEqual
+                    | TokenType::And
+                    | TokenType::Or
+                    | TokenType::Not
+                    | TokenType::True
+                    | TokenType::False
+            )
+        }) || matches!(rhs, [TokenType::Identifier(name)] if booleans::declared(name))
+    };
+    // una variabile assegnata un'altra variabile booleana lo è a sua volta: si ripete fino al
+    // punto fisso
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (name, rhs) in &assignments {
+            if !booleans::declared(name) && boolean(rhs) {
+                booleans::declare(name);
+                changed = true;
+            }
+        }
+    }
+}
+
+// Lato destro senza la condizione e il ramo then delle espressioni condizionali: `b ? e1 : e2`
+// vale un intero anche quando contiene confronti
+fn strip_conditionals(rhs: Vec<&TokenType>) -> Vec<&TokenType> {
+    let mut stripped: Vec<&TokenType> = Vec::new();
+    let mut position = 0;
+    while position < rhs.len() {
+        let token_ty = rhs[position];
+        position += 1;
+        let opens = *token_ty == TokenType::Question
+            && stripped.last().is_some_and(|previous| ends_operand(previous));
+        if !opens {
+            stripped.push(token_ty);
+            continue;
+        }
+        // la condizione, fino a un delimitatore o a una parentesi aperta non chiusa
+        let mut depth = 0;
+        while let Some(previous) = stripped.last() {
+            match previous {
+                TokenType::Ket | TokenType::SKet => depth += 1,
+                TokenType::Bra | TokenType::SBra if depth == 0 => break,
+                TokenType::Bra | TokenType::SBra => depth -= 1,
+                token_ty if depth > 0 || condition_token(token_ty) => {}
+                _ => break,
+            }
+            stripped.pop();
+        }
+        // il ramo then, fino ai `:` corrispondenti
+        let mut depth = 0;
+        let mut nested = 0;
+        while position < rhs.len() {
+            match rhs[position] {
+                TokenType::Bra | TokenType::SBra => depth += 1,
+                TokenType::Ket | TokenType::SKet => depth -= 1,
+                TokenType::Question if depth == 0 && ends_operand(rhs[position - 1]) => nested += 1,
+                TokenType::Colon if depth == 0 && nested == 0 => {
+                    position += 1;
+                    break;
+                }
+                TokenType::Colon if depth == 0 => nested -= 1,
+                _ => {}
+            }
+            position += 1;
+        }
+    }
+    stripped
+}
+
+// Definizioni `procedure f(x, y) { S; return e }`, tolte dai token del programma: le intestazioni
+// sono dichiarate prima di ridurre i corpi, così le procedure possono chiamarsi anche ricorsivamente
+fn parse_procedures(any_vec: &mut AnyVec) {
+    let mut definitions = Vec::new();
+    let mut index = 0;
+    while index < any_vec.nodes.len() {
+        let span = match &any_vec.nodes[index] {
+            Any::Token(token) if token.token_ty == TokenType::Procedure => token.span,
+            _ => {
+                index += 1;
+                continue;
+            }
+        };
+        // la definizione finisce con la graffa che chiude il corpo
+        let mut end = index;
+        let mut depth = 0;
+        while end < any_vec.nodes.len() {
+            if let Any::Token(token) = &any_vec.nodes[end] {
+                match token.token_ty {
+                    TokenType::CBra => depth += 1,
+                    TokenType::Cket => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            end += 1;
+        }
+        if end == any_vec.nodes.len() {
+            unreachable!("Errore di parsing: corpo della procedura a {} non chiuso.", span);
+        }
+        let tokens: Vec<Token> = any_vec
+            .nodes
+            .drain(index..=end)
+            .map(|node| match node {
+                Any::Token(token) => token,
+                other => unreachable!("Errore di parsing: {} inatteso in una procedura.", other),
+            })
+            .collect();
+        // il ';' che separa la definizione dal resto del programma
+        if let Some(Any::Token(token)) = any_vec.nodes.get(index) {
+            if token.token_ty == TokenType::Semicolon {
+                any_vec.nodes.remove(index);
+            }
+        }
+        definitions.push(tokens);
+    }
+    let headers: Vec<(String, Vec<String>, usize)> =
+        definitions.iter().map(|tokens| procedure_header(tokens)).collect();
+    for (tokens, (name, parameters, _)) in definitions.iter().zip(&headers) {
+        procedures::declare(name, parameters.len(), tokens[0].span);
+    }
+    for (tokens, (name, parameters, body_start)) in definitions.into_iter().zip(headers) {
+        let span = tokens[0].span;
+        let mut body = tokens[body_start..tokens.len() - 1].to_vec();
+        // il return chiude il corpo
+        let mut depth = 0;
+        let mut return_index = None;
+        for (i, token) in body.iter().enumerate() {
+            match token.token_ty {
+                TokenType::CBra => depth += 1,
+                TokenType::Cket => depth -= 1,
+                TokenType::Return if depth == 0 => return_index = Some(i),
+                _ => {}
+            }
+        }
+        let Some(return_index) = return_index else {
+            syntax_error(format!("procedure {} declared at {} without a return", name, span));
+        };
+        let mut result: Vec<Token> = body.split_off(return_index);
+        let return_span = result.remove(0).span;
+        for part in [&mut body, &mut result] {
+            if part.last().map(|token| &token.token_ty) == Some(&TokenType::Semicolon) {
+                part.pop();
+            }
+        }
+        let mut body_vec = AnyVec::new();
+        for token in body {
+            body_vec.push_token(token);
+        }
+        parse_passes(&mut body_vec);
+        let body = match body_vec.nodes.as_slice() {
+            [] => None,
+            [Any::Statement(statement)] => Some(statement.clone_box()),
+            _ => unreachable!("Errore di parsing: corpo non valido per la procedura {} a {}.", name, span),
+        };
+        let result = match parse_operand(result.into_iter().map(Any::Token).collect()) {
+            Some(expr) => expr,
+            None => unreachable!("Errore di parsing: valore di ritorno non valido per la procedura {} a {}.", name, span),
+        };
+        procedures::define(Procedure {
+            name,
+            parameters,
+            body,
+            result,
+            span,
+            return_span,
+        });
+    }
+}
+
+// Nome e parametri di `procedure f(x, y) {`, con la posizione del primo token del corpo
+fn procedure_header(tokens: &[Token]) -> (String, Vec<String>, usize) {
+    let span = tokens[0].span;
+    let name = match tokens.get(1).map(|token| &token.token_ty) {
+        Some(TokenType::Identifier(name)) => name.clone(),
+        _ => unreachable!("Errore di parsing: nome mancante per la procedura a {}.", span),
+    };
+    if tokens.get(2).map(|token| &token.token_ty) != Some(&TokenType::Bra) {
+        unreachable!("Errore di parsing: attesa '(' dopo il nome della procedura {} a {}.", name, span);
+    }
+    let mut parameters = Vec::new();
+    let mut index = 3;
+    loop {
+        match tokens.get(index).map(|token| &token.token_ty) {
+            Some(TokenType::Ket) if parameters.is_empty() => break,
+            Some(TokenType::Identifier(parameter)) => {
+                if parameters.contains(parameter) {
+                    syntax_error(format!(
+                        "parameter {} repeated in procedure {} declared at {}",
+                        parameter, name, span
+                    ));
+                }
+                parameters.push(parameter.clone());
+            }
+            _ => unreachable!("Errore di parsing: parametro atteso nella procedura {} a {}.", name, span),
+        }
+        index += 1;
+        match tokens.get(index).map(|token| &token.token_ty) {
+            Some(TokenType::Comma) => index += 1,
+            Some(TokenType::Ket) => break,
+            _ => unreachable!("Errore di parsing: attesa ',' o ')' nella procedura {} a {}.", name, span),
+        }
+    }
+    index += 1;
+    if tokens.get(index).map(|token| &token.token_ty) != Some(&TokenType::CBra) {
+        unreachable!("Errore di parsing: attesa '{{' nella procedura {} a {}.", name, span);
+    }
+    (name, parameters, index + 1)
+}
+
+// Parses a standalone guard, e.g. the postcondition given on the command line
+pub fn parse_guard(text: &str) -> Box<dyn BooleanExpression<Q = AbstractInterval>> {
+    let tokens = Lexer::tokenize(text.trim().to_string());
+    match parse_condition(tokens.into_iter().map(Any::from_token).collect()) {
+        Some(guard) => guard,
+        None => unreachable!("Errore di parsing: {:?} non è una espressione booleana.", text),
+    }
+}
+
+// Whether `text` is a guard parse_guard accepts: comparisons of arithmetic expressions, boolean
+// variables and constants, joined by !, && and || and grouped by parentheses
+pub fn is_guard(text: &str) -> bool {
+    let tokens = Lexer::tokenize(text.trim().to_string());
+    let kinds: Vec<&TokenType> = tokens.iter().map(|token| &token.token_ty).collect();
+    guard_end(&kinds, 0) == Some(kinds.len())
+}
+
+// Position after the guard that starts in `start`, a disjunction of conjunctions
+fn guard_end(kinds: &[&TokenType], start: usize) -> Option<usize> {
+    let mut next = conjunction_end(kinds, start)?;
+    while kinds.get(next) == Some(&&TokenType::Or) {
+        next = conjunction_end(kinds, next + 1)?;
+    }
+    Some(next)
+}
+
+fn conjunction_end(kinds: &[&TokenType], start: usize) -> Option<usize> {
+    let mut next = literal_end(kinds, start)?;
+    while kinds.get(next) == Some(&&TokenType::And) {
+        next = literal_end(kinds, next + 1)?;
+    }
+    Some(next)
+}
+
+// A negation, a comparison, a guard between parentheses or a boolean operand
+fn literal_end(kinds: &[&TokenType], start: usize) -> Option<usize> {
+    if kinds.get(start) == Some(&&TokenType::Not) {
+        return literal_end(kinds, start + 1);
+    }
+    let compared = arithmetic_end(kinds, start).and_then(|left| match kinds.get(left) {
+        Some(token_ty) if is_comparison(token_ty) => arithmetic_end(kinds, left + 1),
+        _ => None,
+    });
+    if compared.is_some() {
+        return compared;
+    }
+    match kinds.get(start)? {
+        TokenType::Bra => guard_end(kinds, start + 1)
+            .filter(|&end| kinds.get(end) == Some(&&TokenType::Ket))
+            .map(|end| end + 1),
+        TokenType::True | TokenType::False | TokenType::Identifier(_) => Some(start + 1),
+        _ => None,
+    }
+}
+
+// Position after the arithmetic expression that starts in `start`
+fn arithmetic_end(kinds: &[&TokenType], start: usize) -> Option<usize> {
+    let mut next = term_end(kinds, start)?;
+    while kinds.get(next).is_some_and(|token_ty| arithmetic_precedence(token_ty).is_some()) {
+        next = term_end(kinds, next + 1)?;
+    }
+    Some(next)
+}
+
+// A negated term, a number, a variable or array element, or an expression between parentheses
+fn term_end(kinds: &[&TokenType], start: usize) -> Option<usize> {
+    match kinds.get(start)? {
+        TokenType::Minus => term_end(kinds, start + 1),
+        TokenType::Number(_) => Some(start + 1),
+        TokenType::Identifier(_) if kinds.get(start + 1) == Some(&&TokenType::SBra) => {
+            arithmetic_end(kinds, start + 2)
+                .filter(|&end| kinds.get(end) == Some(&&TokenType::SKet))
+                .map(|end| end + 1)
+        }
+        TokenType::Identifier(_) => Some(start + 1),
+        TokenType::Bra => arithmetic_end(kinds, start + 1)
+            .filter(|&end| kinds.get(end) == Some(&&TokenType::Ket))
+            .map(|end| end + 1),
+        _ => None,
+    }
+}
+
+// Backward analysis of every goal asked for, after the forward one reached `final_state`
+fn analyze_backward(
+    statement: &dyn Statement<Q = AbstractInterval>,
+    final_state: &abstract_state::AbstractState<AbstractInterval>,
+    assertions: &[Span],
+) {
+    let mut goals = Vec::new();
+    if *BACKWARD.lock().expect("Failed to lock backward flag") {
+        goals.extend(assertions.iter().map(|span| Goal::AssertionFailure(*span)));
+    }
+    if let Some(guard) = POSTCONDITION.lock().expect("Failed to lock postcondition").clone() {
+        goals.push(Goal::Postcondition(guard));
+    }
+    for goal in goals {
+        let post = match &goal {
+            // nothing needs to hold at the end, the assertion is the target
+            Goal::AssertionFailure(_) => final_state.bottom(),
+            Goal::Postcondition(guard) => parse_guard(guard).abs_evaluate(&mut final_state.clone(), false),
+        };
+        let precondition = statement.abs_backward(&post, goal.assertion());
+        let result = backward::finish(goal, precondition);
+        if text_output() {
+            println!("NECESSARY PRECONDITION ({}): {}", result.goal, result.precondition);
+            for (span, state) in &result.points {
+                println!("    BEFORE {}: {}", span, state);
+            }
+        }
+    }
+}
+
+// `path` names the program in the reports that point back to the source
+pub fn analyze(program: String, path: &str) {
     //cleaning the input from whitespaces
     let cleanp = program.trim();
     //----------------------------------------------------------------------------------------------------------------------------------------------------
@@ -2087,6 +2556,40 @@ pub fn analyze(program: String) {
 
     //let the lexer work (string->AnyVec)
     let tokens = Lexer::tokenize(cleanp.to_owned());
+    let (tokens, program_inputs) = inputs::extract(tokens);
+    inputs::declare(&program_inputs);
+    let (tokens, program_annotations) = annotations::extract(tokens);
+    for annotation in &program_annotations {
+        match annotation.name.as_str() {
+            "thresholds" => thresholds::add(&annotation.args),
+            "unroll" => match annotation.args.as_slice() {
+                [iterations] if *iterations >= 0 => {
+                    unrolling::set(annotation.target, *iterations as u64)
+                }
+                _ => syntax_error(format!(
+                    "@unroll at {} expects one non-negative number of iterations",
+                    annotation.span
+                )),
+            },
+            _ => eprintln!(
+                "warning: unknown annotation @{} at {}",
+                annotation.name, annotation.span
+            ),
+        }
+    }
+    if *INFER_THRESHOLDS.lock().expect("Failed to lock threshold inference flag") {
+        thresholds::add(&thresholds::infer(&tokens));
+    }
+    // the thresholds only matter to the abstract analysis
+    let analysis_type = *ANALYSIS_FLAG.lock().expect("Failed to lock analysis flag");
+    if text_output() && analysis_type != 1 {
+        println!("WIDENING THRESHOLDS: {:?}", thresholds::all());
+    }
+    let assertions: Vec<Span> = tokens
+        .iter()
+        .filter(|token| token.token_ty == TokenType::Assert)
+        .map(|token| token.span)
+        .collect();
     let tokenized_program = TokenVec { tokens };
 
     //let's build the ast! (AnyVec->Statement)
@@ -2103,82 +2606,192 @@ pub fn analyze(program: String) {
     //     j = j + 1;
     // }
 
-    let mut index: usize;
     //----------------------------------------------------------------------------------------------------------------------------------------------------
     //PARSING SECTION
     //----------------------------------------------------------------------------------------------------------------------------------------------------
-    println!("********PARSING********\n");
-    index = 0;
-    parse_atomic(&mut any_vec, &mut index);
-    index = 0;
-    parse_arithmetic_unop(&mut any_vec, &mut index);
-    index = 0;
-    parse_bool_unop(&mut any_vec, &mut index);
-    // println!("before bool parsing ");
-    // let mut j = 0;
-    // while j < any_vec.nodes.len() {
-    //     println!("{:?}", any_vec.nodes[j]);
-
-    //     j = j + 1;
-    // }
-    //arithmetic expressions
-    index = 0;
-    parse_arithmetic_expression(&mut any_vec, &mut index);
-    index = 0;
-    parse_bool_expression(&mut any_vec, &mut index);
-    index = 0;
-    parse_bool_expression(&mut any_vec, &mut index);
-    // println!("bool parsed");
-    // let mut j = 0;
-    // while j < any_vec.nodes.len() {
-    //     println!("{:?}", any_vec.nodes[j]);
-
-    //     j = j + 1;
-    // }
-    index = 0;
-    parse_assignment(&mut any_vec, &mut index);
-    //statements
-    index = 0;
-    parse_statement(&mut any_vec, &mut index);
-    clean_from_void(&mut any_vec);
+    if text_output() {
+        println!("********PARSING********\n");
+    }
+    declare_booleans(&any_vec);
+    parse_procedures(&mut any_vec);
+    parse_passes(&mut any_vec);
 
     //println!("statements parsed: ");
-    let mut j = 0;
-    while j < any_vec.nodes.len() {
-        println!("{}", any_vec.nodes[j].to_string());
+    if text_output() {
+        let mut j = 0;
+        while j < any_vec.nodes.len() {
+            println!("{}", any_vec.nodes[j].to_string());
 
-        j = j + 1;
+            j = j + 1;
+        }
+        println!("********EVALUATION********\n");
     }
-    println!("********EVALUATION********\n");
     //----------------------------------------------------------------------------------------------------------------------------------------------------
     //EVALUATING SECTION
     //----------------------------------------------------------------------------------------------------------------------------------------------------
     // evaluate the final statement
-    let mut abs_state = abstract_state::AbstractState::new();
-    let mut state = State::new();
-    println!("INITIAL PROGRAM STATE : {:#?}", state.clone());
-    println!("INITIAL ABSTRACT PROGRAM STATE : {}", abs_state);
+    // the inputs are the initial state, the run not chosen starts from the empty one
+    let (mut state, mut abs_state) = if analysis_type == 1 {
+        (inputs::concrete_state(), abstract_state::AbstractState::new())
+    } else {
+        (State::new(), inputs::abstract_state())
+    };
+    let mut partitions = Vec::new();
+    if text_output() {
+        println!("INITIAL PROGRAM STATE : {:#?}", state.clone());
+        println!("INITIAL ABSTRACT PROGRAM STATE : {}", abs_state);
+    }
+    if let Some(statement) = any_vec.nodes.last().and_then(|node| node.as_statement()) {
+        for read in initialisation::check(statement.as_ref(), inputs::names()) {
+            if text_output() {
+                println!(
+                    "UNINITIALISED READ ({}) OF {} AT {}",
+                    read.severity(),
+                    read.variable,
+                    read.span
+                );
+            }
+        }
+    }
 
-    let analysis_type = *ANALYSIS_FLAG.lock().expect("Failed to lock analysis flag");
+    let output_format = *OUTPUT_FORMAT.lock().expect("Failed to lock output format");
+    let start = Instant::now();
 
     if analysis_type == 1 {
-        println!("STARTING DENOTATIONAL SEMANTICS ANALYSIS");
+        if text_output() {
+            println!("STARTING DENOTATIONAL SEMANTICS ANALYSIS");
+        }
         if let Some(last_node) = any_vec.nodes.last() {
             if let Some(statement) = last_node.as_statement() {
                 let new_state = statement.evaluate(&mut state);
-                println!(
-                    "state printing after code evaluation {:?}",
-                    new_state.clone()
-                );
+                state = new_state.clone();
+                if text_output() {
+                    println!(
+                        "state printing after code evaluation {:?}",
+                        new_state.clone()
+                    );
+                }
             }
         }
     } else {
-        println!("STARTING ABSTRACT SEMANTICS ANALYSIS");
+        if text_output() {
+            println!("STARTING ABSTRACT SEMANTICS ANALYSIS");
+        }
         if let Some(last_node) = any_vec.nodes.last() {
             if let Some(statement) = last_node.as_statement() {
-                let new_state = statement.abs_evaluate(&mut abs_state);
-                println!("state printing after code evaluation {}", new_state.clone());
+                let forward = |partitions: &mut Vec<Partition<AbstractInterval>>| {
+                    procedures::reset();
+                    if partitioning::enabled() {
+                        // the end of the program is a merge point
+                        *partitions = statement
+                            .abs_evaluate_partitioned(vec![Partition::new(abs_state.clone())]);
+                        if text_output() {
+                            for partition in partitions.iter() {
+                                println!("PARTITION {:?}: {}", partition.trace, partition.state);
+                            }
+                        }
+                        partitioning::join(partitions)
+                    } else {
+                        statement.abs_evaluate(&mut abs_state.clone())
+                    }
+                };
+                let mut new_state = forward(&mut partitions);
+                let rounds = *REFINEMENT_ROUNDS.lock().expect("Failed to lock refinement rounds");
+                if rounds > 0 {
+                    // the alarms are those of every execution, not only of the refined ones
+                    let raised = alarms::snapshot();
+                    let mut refinement = Refinement { rounds: 0, stable: false };
+                    while refinement.rounds < rounds {
+                        // backward from the end of the program, the assertions holding
+                        statement.abs_backward(&new_state, None);
+                        let constrained = backward::constrain();
+                        let refined = forward(&mut partitions);
+                        refinement.rounds += 1;
+                        if text_output() {
+                            println!("REFINEMENT ROUND {}: {}", refinement.rounds, refined);
+                        }
+                        refinement.stable = refined == new_state && !constrained;
+                        new_state = refined;
+                        if refinement.stable {
+                            break;
+                        }
+                    }
+                    backward::release();
+                    alarms::restore(raised);
+                    backward::record_refinement(refinement);
+                }
+                initialisation::report_alarms();
+                for summary in procedures::summaries() {
+                    if text_output() {
+                        let arguments: Vec<String> =
+                            summary.arguments.iter().map(|argument| argument.to_string()).collect();
+                        println!(
+                            "SUMMARY OF {}({}): {}",
+                            summary.procedure,
+                            arguments.join(", "),
+                            summary.result
+                        );
+                    }
+                }
+                for unreachable in reachability::check(statement.as_ref()) {
+                    if text_output() {
+                        println!(
+                            "UNREACHABLE AT {}: {} ({})",
+                            unreachable.span, unreachable.statement, unreachable.cause
+                        );
+                    }
+                }
+                for termination in termination::check(statement.as_ref()) {
+                    if text_output() {
+                        println!(
+                            "TERMINATION OF THE LOOP AT {}: {}",
+                            termination.span, termination.verdict
+                        );
+                    }
+                }
+                let bounds = cost::check(statement.as_ref());
+                for bound in &bounds {
+                    if text_output() {
+                        println!("ITERATIONS OF THE LOOP AT {}: {}", bound.span, bound.iterations);
+                    }
+                }
+                // a program without loops has no cost to report
+                if text_output() && !bounds.is_empty() {
+                    println!("COST (EXECUTIONS OF LOOP BODIES): {}", cost::total());
+                }
+                for printed in output::printed() {
+                    if text_output() {
+                        let values: Vec<String> =
+                            printed.values.iter().map(|value| value.to_string()).collect();
+                        println!("ABSTRACT OUTPUT AT {}: {}", printed.span, values.join(", "));
+                    }
+                }
+                abs_state = new_state.clone();
+                if text_output() {
+                    println!("state printing after code evaluation {}", new_state.clone());
+                }
+                analyze_backward(statement.as_ref(), &abs_state, &assertions);
             }
         }
     }
+
+    if output_format == OutputFormat::Annotated {
+        if let Some(statement) = any_vec.nodes.last().and_then(|node| node.as_statement()) {
+            println!("{}", Listing::of_program(statement.as_ref()));
+        }
+    }
+    if output_format == OutputFormat::Json {
+        if let Some(statement) = any_vec.nodes.last().and_then(|node| node.as_statement()) {
+            let duration_ms = start.elapsed().as_millis();
+            let report = if analysis_type == 1 {
+                json::concrete_report(statement.as_ref(), &state, duration_ms)
+            } else {
+                json::abstract_report(statement.as_ref(), &abs_state, &partitions, duration_ms)
+            };
+            println!("{}", report.pretty());
+        }
+    }
+    if output_format == OutputFormat::Sarif {
+        println!("{}", sarif::report(path).pretty());
+    }
 }
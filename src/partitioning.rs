@@ -0,0 +1,151 @@
+// Trace partitioning (--partition block|loop)
+//
+// The states reaching a point through different branches of an if-then-else, or through different
+// exits of a loop, are kept apart as partitions tagged by the branches taken. They are joined at
+// the merge point: the end of the enclosing block (block), or the end of the enclosing loop body
+// and of the program (loop).
+
+use crate::abstract_domain::AbstractDomainOps;
+use crate::abstract_state::AbstractState;
+use crate::alarms;
+use crate::ast::statement::Statement;
+use crate::program_points;
+use crate::{Partitioning, PARTITIONING};
+
+// Abstract state of the traces that took the branches in `trace`, e.g. ["then@3:1", "exit@5:1#2"]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partition<Q: AbstractDomainOps + Clone> {
+    pub trace: Vec<String>,
+    pub state: AbstractState<Q>,
+}
+
+impl<Q: AbstractDomainOps + Clone> Partition<Q> {
+    pub fn new(state: AbstractState<Q>) -> Self {
+        Partition {
+            trace: Vec::new(),
+            state,
+        }
+    }
+
+    // The same traces in another state
+    pub fn with_state(&self, state: AbstractState<Q>) -> Self {
+        Partition {
+            trace: self.trace.clone(),
+            state,
+        }
+    }
+
+    // The traces that went on through the branch `tag`
+    pub fn tagged(&self, tag: String, state: AbstractState<Q>) -> Self {
+        let mut trace = self.trace.clone();
+        trace.push(tag);
+        Partition { trace, state }
+    }
+}
+
+pub fn mode() -> Partitioning {
+    *PARTITIONING.lock().expect("failed to lock partitioning mode")
+}
+
+pub fn enabled() -> bool {
+    mode() != Partitioning::Off
+}
+
+// Join of the partitions, the merge point
+pub fn join<Q: AbstractDomainOps + Clone>(partitions: &[Partition<Q>]) -> AbstractState<Q> {
+    partitions
+        .iter()
+        .map(|partition| partition.state.clone())
+        .reduce(|joined, state| joined.state_lub(&state))
+        .unwrap_or_else(|| AbstractState::new().bottom())
+}
+
+// Runs `run` on every partition as if it was the only one: the program points, invariants and
+// alarms recorded by the runs are joined instead of the last run overwriting the others
+pub fn for_each<Q: AbstractDomainOps + Clone>(
+    partitions: Vec<Partition<Q>>,
+    run: impl Fn(Partition<Q>) -> Vec<Partition<Q>>,
+) -> Vec<Partition<Q>> {
+    if partitions.len() == 1 {
+        let partition = partitions.into_iter().next().expect("one partition");
+        return normalize(run(partition));
+    }
+    let points = program_points::snapshot();
+    let raised = alarms::snapshot();
+    let mut point_runs = Vec::new();
+    let mut alarm_runs = Vec::new();
+    let mut results = Vec::new();
+    for partition in partitions {
+        program_points::restore(points.clone());
+        alarms::restore(raised.clone());
+        results.extend(run(partition));
+        point_runs.push(program_points::snapshot());
+        alarm_runs.push(alarms::snapshot());
+    }
+    program_points::restore(program_points::merge(point_runs));
+    alarms::restore(alarms::merge(alarm_runs));
+    normalize(results)
+}
+
+// Unreachable partitions are dropped, unless every partition is unreachable
+fn normalize<Q: AbstractDomainOps + Clone>(partitions: Vec<Partition<Q>>) -> Vec<Partition<Q>> {
+    if partitions.iter().all(|partition| partition.state.is_bottom()) {
+        return partitions.into_iter().take(1).collect();
+    }
+    partitions
+        .into_iter()
+        .filter(|partition| !partition.state.is_bottom())
+        .collect()
+}
+
+// A block that is a merge point whatever the mode, e.g. a loop body: its partitions are joined
+pub fn block<Q: AbstractDomainOps + Clone + PartialEq + std::fmt::Debug>(
+    body: &dyn Statement<Q = Q>,
+    state: AbstractState<Q>,
+) -> AbstractState<Q> {
+    if !enabled() {
+        return body.abs_evaluate(&mut state.clone());
+    }
+    join(&body.abs_evaluate_partitioned(vec![Partition::new(state)]))
+}
+
+// Branch `tag` of `partition` running `body` from `state`: the branch is a block, merged at its
+// end in block mode, while in loop mode the partitions made inside it carry on
+pub fn branch<Q: AbstractDomainOps + Clone + PartialEq + std::fmt::Debug>(
+    body: &dyn Statement<Q = Q>,
+    partition: &Partition<Q>,
+    tag: String,
+    state: AbstractState<Q>,
+) -> Vec<Partition<Q>> {
+    match mode() {
+        Partitioning::Loop => body.abs_evaluate_partitioned(vec![partition.tagged(tag, state)]),
+        _ => vec![partition.tagged(tag, block(body, state))],
+    }
+}
+
+// Continues `partition` through the tagged `branches` it splits into (e.g. loop exits), untagged
+// when only one of them is reachable
+pub fn split<Q: AbstractDomainOps + Clone>(
+    partition: &Partition<Q>,
+    branches: Vec<Partition<Q>>,
+) -> Vec<Partition<Q>> {
+    let reachable: Vec<Partition<Q>> = branches
+        .iter()
+        .filter(|branch| !branch.state.is_bottom())
+        .cloned()
+        .collect();
+    if reachable.len() <= 1 {
+        return vec![partition.with_state(join(&branches))];
+    }
+    reachable
+        .into_iter()
+        .map(|branch| {
+            let mut trace = partition.trace.clone();
+            trace.extend(branch.trace);
+            Partition {
+                trace,
+                state: branch.state,
+            }
+        })
+        .collect()
+}
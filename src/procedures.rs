@@ -0,0 +1,305 @@
+// Procedures: `procedure f(x, y) { S; return e }` declared before the program, and calls `f(a, b)`
+//
+// Arguments are passed by value and a procedure sees only its parameters. The concrete call runs
+// the body in a fresh state binding the parameters to the values of the arguments. The abstract
+// call follows the functional approach: the body is analysed once per calling context (the
+// intervals of the arguments) and the result is kept in a summary that later calls in the same
+// context reuse. A call to a procedure already being analysed is recursive: its context is joined
+// into the one of the outermost active call of the procedure (the head), and it returns the
+// current approximation of the head's result. The head is analysed again, widening its context
+// and its result at every round past the widening delay, until both are stable. The summaries of
+// calls analysed under an approximation are not kept, they are recomputed at the next round.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
+use crate::abstract_domain::AbstractDomain;
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::ast::arithmetic::ArithmeticExpression;
+use crate::ast::statement::Statement;
+use crate::ast::State;
+use crate::initialisation;
+use crate::lexer::Span;
+use crate::program_points;
+use crate::{runtime_error, syntax_error, MAX_ITERATIONS, WIDENING_DELAY, WIDENING_FLAG};
+
+// Nested concrete calls beyond this are reported as a runtime error instead of overflowing the stack
+const MAX_CALL_DEPTH: usize = 1000;
+
+#[derive(Debug)]
+pub struct Procedure {
+    pub name: String,
+    pub parameters: Vec<String>,
+    pub body: Option<Box<dyn Statement<Q = AbstractInterval>>>, // None when it only returns
+    pub result: Box<dyn ArithmeticExpression<Q = AbstractInterval>>,
+    pub span: Span,        // the `procedure` keyword
+    pub return_span: Span, // the `return` keyword
+}
+
+// Result of a procedure called with arguments in the given intervals
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub procedure: String,
+    pub arguments: Vec<AbstractInterval>,
+    pub result: AbstractInterval,
+}
+
+// Abstract call being analysed
+struct Frame {
+    procedure: String,
+    context: Vec<AbstractInterval>,
+    result: AbstractInterval,                  // approximation returned to the recursive calls
+    recursive: Option<Vec<AbstractInterval>>,  // contexts of the recursive calls not included
+    reentered: bool,                           // whether a recursive call used the approximation
+    approximated: bool,                        // whether a call below used the approximation
+}
+
+// Definitions hold trait objects, which cannot be shared between threads: the registry lives in
+// thread local storage rather than behind a Mutex
+thread_local! {
+    static HEADERS: RefCell<BTreeMap<String, (usize, Span)>> = const { RefCell::new(BTreeMap::new()) };
+    static PROCEDURES: RefCell<BTreeMap<String, Rc<Procedure>>> = const { RefCell::new(BTreeMap::new()) };
+    static SUMMARIES: RefCell<Vec<Summary>> = const { RefCell::new(Vec::new()) };
+    static FRAMES: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+    // procedures analysed in the current forward run
+    static ANALYSED: RefCell<BTreeSet<String>> = const { RefCell::new(BTreeSet::new()) };
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+// Name and arity of a procedure, known before the bodies are parsed so that they can call it
+pub fn declare(name: &str, arity: usize, span: Span) {
+    HEADERS.with(|headers| {
+        if let Some((_, first)) = headers.borrow_mut().insert(name.to_string(), (arity, span)) {
+            syntax_error(format!(
+                "procedure {} declared at {} and again at {}",
+                name, first, span
+            ));
+        }
+    });
+}
+
+pub fn declared(name: &str) -> bool {
+    HEADERS.with(|headers| headers.borrow().contains_key(name))
+}
+
+pub fn arity(name: &str) -> usize {
+    HEADERS.with(|headers| match headers.borrow().get(name) {
+        Some((arity, _)) => *arity,
+        None => unreachable!("procedure {} called without being declared", name),
+    })
+}
+
+pub fn define(procedure: Procedure) {
+    PROCEDURES.with(|procedures| {
+        procedures
+            .borrow_mut()
+            .insert(procedure.name.clone(), Rc::new(procedure));
+    });
+}
+
+fn get(name: &str) -> Rc<Procedure> {
+    PROCEDURES.with(|procedures| match procedures.borrow().get(name) {
+        Some(procedure) => procedure.clone(),
+        None => unreachable!("procedure {} called without being defined", name),
+    })
+}
+
+// Procedures in source order
+pub fn all() -> Vec<Rc<Procedure>> {
+    let mut procedures: Vec<Rc<Procedure>> =
+        PROCEDURES.with(|procedures| procedures.borrow().values().cloned().collect());
+    procedures.sort_by_key(|procedure| procedure.span);
+    procedures
+}
+
+// Concrete call by value
+pub fn call(name: &str, arguments: &[i64]) -> i64 {
+    let procedure = get(name);
+    let depth = DEPTH.with(|depth| {
+        depth.set(depth.get() + 1);
+        depth.get()
+    });
+    if depth > MAX_CALL_DEPTH {
+        runtime_error(format!(
+            "more than {} nested calls, the last one to procedure {}",
+            MAX_CALL_DEPTH, name
+        ));
+    }
+    let mut state: State = procedure
+        .parameters
+        .iter()
+        .cloned()
+        .zip(arguments.iter().copied())
+        .collect();
+    if let Some(body) = &procedure.body {
+        state = body.evaluate(&mut state);
+    }
+    let result = procedure.result.evaluate(&mut state);
+    DEPTH.with(|depth| depth.set(depth.get() - 1));
+    result
+}
+
+// The summaries are those of one forward run, a new run (e.g. a refinement round) starts afresh
+pub fn reset() {
+    SUMMARIES.with(|summaries| summaries.borrow_mut().clear());
+    ANALYSED.with(|analysed| analysed.borrow_mut().clear());
+}
+
+// Summaries in the order they were computed
+pub fn summaries() -> Vec<Summary> {
+    SUMMARIES.with(|summaries| summaries.borrow().clone())
+}
+
+fn summary(name: &str, arguments: &[AbstractInterval]) -> Option<AbstractInterval> {
+    SUMMARIES.with(|summaries| {
+        summaries
+            .borrow()
+            .iter()
+            .find(|summary| summary.procedure == name && summary.arguments == arguments)
+            .map(|summary| summary.result)
+    })
+}
+
+fn included(context: &[AbstractInterval], other: &[AbstractInterval]) -> bool {
+    context
+        .iter()
+        .zip(other)
+        .all(|(value, bound)| value.int_lub(bound) == *bound)
+}
+
+fn join(context: &[AbstractInterval], other: &[AbstractInterval]) -> Vec<AbstractInterval> {
+    context
+        .iter()
+        .zip(other)
+        .map(|(value, other)| value.int_lub(other))
+        .collect()
+}
+
+// Abstract call: the summary of the context, the approximation of the head for a recursive call,
+// otherwise the analysis of the body
+pub fn abs_call(name: &str, arguments: Vec<AbstractInterval>) -> AbstractInterval {
+    if arguments.iter().any(|argument| argument.is_bottom()) {
+        return AbstractInterval::Bottom;
+    }
+    if let Some(result) = summary(name, &arguments) {
+        return result;
+    }
+    let recursive = FRAMES.with(|frames| {
+        let mut frames = frames.borrow_mut();
+        let head = frames.iter().position(|frame| frame.procedure == name)?;
+        for frame in frames.iter_mut().skip(head + 1) {
+            frame.approximated = true;
+        }
+        let frame = &mut frames[head];
+        frame.reentered = true;
+        if !included(&arguments, &frame.context) {
+            frame.recursive = Some(match &frame.recursive {
+                Some(recursive) => join(recursive, &arguments),
+                None => arguments.clone(),
+            });
+        }
+        Some(frame.result)
+    });
+    if let Some(result) = recursive {
+        return result;
+    }
+    let procedure = get(name);
+    // flags are copied out: the body locks them again
+    let widening = *WIDENING_FLAG.lock().expect("failed to read widening flag");
+    let delay = *WIDENING_DELAY.lock().expect("failed to read widening delay");
+    let cap = *MAX_ITERATIONS.lock().expect("failed to read iteration cap");
+    FRAMES.with(|frames| {
+        frames.borrow_mut().push(Frame {
+            procedure: name.to_string(),
+            context: arguments.clone(),
+            result: AbstractInterval::Bottom,
+            recursive: None,
+            reentered: false,
+            approximated: false,
+        })
+    });
+    // the points of the body hold the states of every calling context of the run, not only of
+    // this one
+    let earlier = ANALYSED
+        .with(|analysed| !analysed.borrow_mut().insert(name.to_string()))
+        .then(program_points::snapshot);
+    let mut rounds: u64 = 0;
+    let frame = loop {
+        rounds += 1;
+        let context = FRAMES.with(|frames| frames.borrow().last().map(|frame| frame.context.clone()));
+        let result = analyse(&procedure, &context.unwrap_or_default());
+        let stable = FRAMES.with(|frames| {
+            let mut frames = frames.borrow_mut();
+            let frame = frames.last_mut().expect("the frame of the call is on the stack");
+            if !frame.reentered {
+                frame.result = result;
+                return true;
+            }
+            let extrapolate = |old: &AbstractInterval, new: &AbstractInterval| {
+                let joined = old.int_lub(new);
+                if (widening && rounds > delay) || rounds > cap {
+                    program_points::update_statistics(|statistics| statistics.widenings += 1);
+                    old.int_widening(&joined)
+                } else {
+                    joined
+                }
+            };
+            let mut stable = true;
+            if let Some(recursive) = frame.recursive.take() {
+                frame.context = frame
+                    .context
+                    .iter()
+                    .zip(&recursive)
+                    .map(|(old, new)| extrapolate(old, new))
+                    .collect();
+                stable = false;
+            }
+            if result.int_lub(&frame.result) != frame.result {
+                frame.result = extrapolate(&frame.result, &result);
+                stable = false;
+            }
+            frame.reentered = false;
+            stable
+        });
+        if stable {
+            break FRAMES.with(|frames| frames.borrow_mut().pop())
+                .expect("the frame of the call is on the stack");
+        }
+    };
+    if let Some(earlier) = earlier {
+        program_points::restore(program_points::merge(vec![earlier, program_points::snapshot()]));
+    }
+    if !frame.approximated {
+        SUMMARIES.with(|summaries| {
+            summaries.borrow_mut().push(Summary {
+                procedure: name.to_string(),
+                arguments,
+                result: frame.result,
+            })
+        });
+    }
+    frame.result
+}
+
+// Result of the body run from the parameters bound to `context`
+fn analyse(procedure: &Procedure, context: &[AbstractInterval]) -> AbstractInterval {
+    let mut state = AbstractState::new();
+    for (parameter, value) in procedure.parameters.iter().zip(context) {
+        state
+            .variables
+            .insert(parameter.clone(), AbstractDomain::new(*value));
+    }
+    if let Some(body) = &procedure.body {
+        state = body.abs_evaluate(&mut state);
+    }
+    if state.is_bottom() {
+        return AbstractInterval::Bottom;
+    }
+    initialisation::report_return_alarms(procedure.return_span, &state);
+    procedure
+        .result
+        .check_alarms(&mut state.clone(), procedure.return_span);
+    procedure.result.abs_evaluate(&mut state)
+}
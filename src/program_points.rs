@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::lexer::Span;
+
+// Abstract states seen before and after a statement during its last visit
+#[derive(Debug, Clone)]
+pub struct PointStates {
+    pub pre: AbstractState<AbstractInterval>,
+    pub post: AbstractState<AbstractInterval>,
+}
+
+// Counters of the work done by the abstract interpreter
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Statistics {
+    pub statements: u64,      // statement evaluations, loop bodies count once per iteration
+    pub loop_iterations: u64, // ascending and descending iterations of every loop
+    pub widenings: u64,
+    pub narrowings: u64,
+}
+
+// Loop head states: one for each unrolled iteration, in order, and the invariant of the others
+#[derive(Debug, Clone)]
+pub struct LoopInvariant {
+    pub unrolled: Vec<AbstractState<AbstractInterval>>,
+    pub invariant: AbstractState<AbstractInterval>,
+}
+
+// One descending iteration of a loop: the variables whose interval it shrank, before and after
+#[derive(Debug, Clone)]
+pub struct DescendingStep {
+    pub refined: Vec<(String, AbstractInterval, AbstractInterval)>,
+}
+
+impl DescendingStep {
+    pub fn between(
+        before: &AbstractState<AbstractInterval>,
+        after: &AbstractState<AbstractInterval>,
+    ) -> DescendingStep {
+        let refined = after
+            .sorted_variables()
+            .into_iter()
+            .filter_map(|(name, domain)| {
+                let old = before.variables.get(name)?.value;
+                (old != domain.value).then(|| (name.clone(), old, domain.value))
+            })
+            .collect();
+        DescendingStep { refined }
+    }
+}
+
+lazy_static! {
+    // Program points are identified by the span of the statement's leading token
+    static ref PROGRAM_POINTS: Mutex<HashMap<Span, PointStates>> = Mutex::new(HashMap::new());
+    // Loop invariants, identified by the span of the loop keyword
+    static ref INVARIANTS: Mutex<HashMap<Span, LoopInvariant>> = Mutex::new(HashMap::new());
+    static ref STATISTICS: Mutex<Statistics> = Mutex::new(Statistics::default());
+    // Descending iterations of every loop that made progress, identified by the loop keyword
+    static ref DESCENDING: Mutex<HashMap<Span, Vec<DescendingStep>>> = Mutex::new(HashMap::new());
+    // Loops that reached the iteration cap
+    static ref CAPPED_LOOPS: Mutex<Vec<Span>> = Mutex::new(Vec::new());
+}
+
+// Program points and loop invariants recorded so far
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    points: HashMap<Span, PointStates>,
+    invariants: HashMap<Span, LoopInvariant>,
+}
+
+pub fn snapshot() -> Snapshot {
+    Snapshot {
+        points: PROGRAM_POINTS.lock().expect("failed to lock program points").clone(),
+        invariants: INVARIANTS.lock().expect("failed to lock loop invariants").clone(),
+    }
+}
+
+pub fn restore(snapshot: Snapshot) {
+    *PROGRAM_POINTS.lock().expect("failed to lock program points") = snapshot.points;
+    *INVARIANTS.lock().expect("failed to lock loop invariants") = snapshot.invariants;
+}
+
+// Records of runs over different partitions of the same states, joined point by point
+pub fn merge(runs: Vec<Snapshot>) -> Snapshot {
+    let mut merged = Snapshot {
+        points: HashMap::new(),
+        invariants: HashMap::new(),
+    };
+    for run in runs {
+        for (span, states) in run.points {
+            match merged.points.get_mut(&span) {
+                Some(joined) => {
+                    joined.pre = joined.pre.state_lub(&states.pre);
+                    joined.post = joined.post.state_lub(&states.post);
+                }
+                None => {
+                    merged.points.insert(span, states);
+                }
+            }
+        }
+        for (span, invariant) in run.invariants {
+            match merged.invariants.get_mut(&span) {
+                Some(joined) => {
+                    joined.invariant = joined.invariant.state_lub(&invariant.invariant);
+                    for (i, head) in invariant.unrolled.into_iter().enumerate() {
+                        match joined.unrolled.get_mut(i) {
+                            Some(joined_head) => *joined_head = joined_head.state_lub(&head),
+                            None => joined.unrolled.push(head),
+                        }
+                    }
+                }
+                None => {
+                    merged.invariants.insert(span, invariant);
+                }
+            }
+        }
+    }
+    merged
+}
+
+// Records the states of a statement, later visits (e.g. the last loop iteration) overwrite earlier ones
+pub fn record(
+    span: Span,
+    pre: &AbstractState<AbstractInterval>,
+    post: &AbstractState<AbstractInterval>,
+) {
+    update_statistics(|statistics| statistics.statements += 1);
+    if span.is_synthetic() {
+        return;
+    }
+    PROGRAM_POINTS
+        .lock()
+        .expect("failed to lock program points")
+        .insert(
+            span,
+            PointStates {
+                pre: pre.clone(),
+                post: post.clone(),
+            },
+        );
+}
+
+pub fn lookup(span: Span) -> Option<PointStates> {
+    PROGRAM_POINTS
+        .lock()
+        .expect("failed to lock program points")
+        .get(&span)
+        .cloned()
+}
+
+// Program points in source order
+pub fn all() -> Vec<(Span, PointStates)> {
+    let mut points: Vec<(Span, PointStates)> = PROGRAM_POINTS
+        .lock()
+        .expect("failed to lock program points")
+        .iter()
+        .map(|(span, states)| (*span, states.clone()))
+        .collect();
+    points.sort_by_key(|(span, _)| *span);
+    points
+}
+
+pub fn record_invariant(
+    span: Span,
+    unrolled: &[AbstractState<AbstractInterval>],
+    invariant: &AbstractState<AbstractInterval>,
+) {
+    if span.is_synthetic() {
+        return;
+    }
+    INVARIANTS.lock().expect("failed to lock loop invariants").insert(
+        span,
+        LoopInvariant {
+            unrolled: unrolled.to_vec(),
+            invariant: invariant.clone(),
+        },
+    );
+}
+
+pub fn invariant(span: Span) -> Option<LoopInvariant> {
+    INVARIANTS
+        .lock()
+        .expect("failed to lock loop invariants")
+        .get(&span)
+        .cloned()
+}
+
+// Loop invariants in source order
+pub fn invariants() -> Vec<(Span, LoopInvariant)> {
+    let mut invariants: Vec<(Span, LoopInvariant)> = INVARIANTS
+        .lock()
+        .expect("failed to lock loop invariants")
+        .iter()
+        .map(|(span, invariant)| (*span, invariant.clone()))
+        .collect();
+    invariants.sort_by_key(|(span, _)| *span);
+    invariants
+}
+
+// The descending steps of the last visit of a loop replace those of the earlier ones
+pub fn record_descending(span: Span, steps: Vec<DescendingStep>) {
+    if span.is_synthetic() {
+        return;
+    }
+    DESCENDING
+        .lock()
+        .expect("failed to lock descending steps")
+        .insert(span, steps);
+}
+
+// Descending steps of every loop in source order
+pub fn descending() -> Vec<(Span, Vec<DescendingStep>)> {
+    let mut descending: Vec<(Span, Vec<DescendingStep>)> = DESCENDING
+        .lock()
+        .expect("failed to lock descending steps")
+        .iter()
+        .map(|(span, steps)| (*span, steps.clone()))
+        .collect();
+    descending.sort_by_key(|(span, _)| *span);
+    descending
+}
+
+pub fn update_statistics(update: impl FnOnce(&mut Statistics)) {
+    update(&mut STATISTICS.lock().expect("failed to lock statistics"));
+}
+
+pub fn record_cap_hit(span: Span) {
+    let mut capped = CAPPED_LOOPS.lock().expect("failed to lock capped loops");
+    if !capped.contains(&span) {
+        capped.push(span);
+    }
+}
+
+pub fn capped_loops() -> Vec<Span> {
+    let mut capped = CAPPED_LOOPS.lock().expect("failed to lock capped loops").clone();
+    capped.sort();
+    capped
+}
+
+pub fn statistics() -> Statistics {
+    *STATISTICS.lock().expect("failed to lock statistics")
+}
@@ -0,0 +1,69 @@
+// Nondeterminism: the expressions `?` and `random(a, b)`, the statement `either { S1 } or { S2 }`
+//
+// The abstract semantics covers every outcome: `?` is any value, `random(a, b)` any value from the
+// least of a to the greatest of b, and both branches of a choice are analysed and joined. A
+// concrete run takes one outcome: the values of `?` and `random` come in order from the script
+// given with --random, then from a pseudo-random generator seeded with --seed (0 by default), which
+// also picks the branch of every choice. The same seed and script give the same run.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use crate::runtime_error;
+
+// Range of the values drawn for `?` in a concrete run
+const ANY_LOWER: i64 = i32::MIN as i64;
+const ANY_UPPER: i64 = i32::MAX as i64;
+
+lazy_static! {
+    static ref GENERATOR: Mutex<u64> = Mutex::new(0);
+    static ref SCRIPT: Mutex<VecDeque<i64>> = Mutex::new(VecDeque::new());
+}
+
+pub fn seed(seed: u64) {
+    *GENERATOR.lock().expect("failed to lock random generator") = seed;
+}
+
+pub fn script(values: &[i64]) {
+    SCRIPT
+        .lock()
+        .expect("failed to lock random script")
+        .extend(values);
+}
+
+// splitmix64, enough for reproducible test runs
+fn next() -> u64 {
+    let mut state = GENERATOR.lock().expect("failed to lock random generator");
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// Value of `expression` in [lower, upper], the next scripted one if any
+pub fn draw(lower: i64, upper: i64, expression: &str) -> i64 {
+    if lower > upper {
+        runtime_error(format!("empty range [{}, {}] of {}", lower, upper, expression));
+    }
+    if let Some(value) = SCRIPT.lock().expect("failed to lock random script").pop_front() {
+        if value < lower || value > upper {
+            runtime_error(format!(
+                "scripted value {} outside the range [{}, {}] of {}",
+                value, lower, upper, expression
+            ));
+        }
+        return value;
+    }
+    let width = (upper as i128 - lower as i128 + 1) as u128;
+    (lower as i128 + (next() as u128 % width) as i128) as i64
+}
+
+pub fn any(expression: &str) -> i64 {
+    draw(ANY_LOWER, ANY_UPPER, expression)
+}
+
+// Branch of a choice, true for the first one
+pub fn choose() -> bool {
+    next() & 1 == 0
+}
@@ -0,0 +1,110 @@
+// Unreachable code: statements whose entry state is ⊥ in the abstract run
+//
+// The checker walks the program after the forward analysis, using the states recorded at every
+// program point. A statement is unreachable when its entry state is ⊥; the cause is the guard
+// that filters every state out (an if branch, a loop body) or the statement before it that never
+// completes (a loop that never terminates, an assertion that always fails).
+
+use std::sync::Mutex;
+
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::ast::boolean::BooleanExpression;
+use crate::ast::statement::Statement;
+use crate::lexer::Span;
+use crate::procedures;
+use crate::program_points;
+
+// Whether the states reaching a point can be non-⊥, and otherwise why not
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reachability {
+    Reachable,
+    Unreachable(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unreachable {
+    pub span: Span,
+    pub statement: String,
+    pub cause: String,
+}
+
+lazy_static! {
+    static ref UNREACHABLE: Mutex<Vec<Unreachable>> = Mutex::new(Vec::new());
+}
+
+// Entry of `statement`, reached as `reached`: reports it when it is unreachable and returns the
+// cause, None when it is reachable
+pub fn enter<S: Statement + ?Sized>(
+    statement: &S,
+    reached: &Reachability,
+    reports: &mut Vec<Unreachable>,
+) -> Option<String> {
+    let span = statement.span();
+    let cause = match reached {
+        Reachability::Unreachable(cause) => cause.clone(),
+        Reachability::Reachable => match program_points::lookup(span) {
+            Some(point) if point.pre.is_bottom() => "the entry state is ⊥".to_string(),
+            _ => return None,
+        },
+    };
+    if !span.is_synthetic() {
+        reports.push(Unreachable {
+            span,
+            statement: statement.to_string(),
+            cause: cause.clone(),
+        });
+    }
+    Some(cause)
+}
+
+// Exit of the reachable statement at `span`: unreachable because of `cause` when its exit state
+// is ⊥
+pub fn exit(span: Span, cause: impl FnOnce() -> String) -> Reachability {
+    match program_points::lookup(span) {
+        Some(point) if point.post.is_bottom() => Reachability::Unreachable(cause()),
+        _ => Reachability::Reachable,
+    }
+}
+
+// Statements that run from the states of `state` satisfying `guard` (or its negation) are
+// unreachable when no state does, because of the guard of the `construct` at `span`
+pub fn filter(
+    state: &AbstractState<AbstractInterval>,
+    guard: &dyn BooleanExpression<Q = AbstractInterval>,
+    negated: bool,
+    construct: &str,
+    span: Span,
+) -> Reachability {
+    if state.is_bottom() || !guard.abs_evaluate(&mut state.clone(), negated).is_bottom() {
+        return Reachability::Reachable;
+    }
+    Reachability::Unreachable(format!(
+        "the guard {} of the {} at {} is never {}",
+        guard.to_string(),
+        construct,
+        span,
+        if negated { "false" } else { "true" }
+    ))
+}
+
+// Checks the program, returns the unreachable statements in source order
+pub fn check(program: &dyn Statement<Q = AbstractInterval>) -> Vec<Unreachable> {
+    let mut reports = Vec::new();
+    program.check_reachability(&Reachability::Reachable, &mut reports);
+    for procedure in procedures::all() {
+        if let Some(body) = &procedure.body {
+            body.check_reachability(&Reachability::Reachable, &mut reports);
+        }
+    }
+    reports.sort_by_key(|report| report.span);
+    *UNREACHABLE.lock().expect("failed to lock unreachable statements") = reports.clone();
+    reports
+}
+
+pub fn all() -> Vec<Unreachable> {
+    UNREACHABLE
+        .lock()
+        .expect("failed to lock unreachable statements")
+        .clone()
+}
@@ -0,0 +1,107 @@
+// SARIF 2.1.0 log of the alarms (--format sarif), for code-scanning viewers
+//
+// One run with one rule per alarm kind. Definite alarms have level "error", possible ones
+// "warning"; the region starts at the leading token of the statement raising the alarm and
+// the message carries the abstract state the alarm was found in.
+
+use crate::alarms::{self, Alarm, AlarmKind, Severity};
+use crate::json::Json;
+use crate::listing::Listing;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const RULES: [AlarmKind; 6] = [
+    AlarmKind::DivisionByZero,
+    AlarmKind::Overflow,
+    AlarmKind::Assertion,
+    AlarmKind::Uninitialised,
+    AlarmKind::OutOfBounds,
+    AlarmKind::InvalidShift,
+];
+
+fn rule(kind: AlarmKind) -> Json {
+    Json::object(vec![
+        ("id", Json::str(kind.rule_id())),
+        (
+            "shortDescription",
+            Json::object(vec![("text", Json::str(kind.description()))]),
+        ),
+        (
+            "defaultConfiguration",
+            Json::object(vec![("level", Json::str("warning"))]),
+        ),
+    ])
+}
+
+fn result(alarm: &Alarm, artifact: &str) -> Json {
+    let level = match alarm.severity {
+        Severity::Definite => "error",
+        Severity::Possible => "warning",
+    };
+    let text = format!(
+        "{} {} in {}, abstract state {}",
+        alarm.severity,
+        alarm.kind.description(),
+        alarm.expression,
+        Listing::format_state(&alarm.state)
+    );
+    let rule_index = RULES
+        .iter()
+        .position(|kind| *kind == alarm.kind)
+        .expect("alarm kind without a SARIF rule");
+    Json::object(vec![
+        ("ruleId", Json::str(alarm.kind.rule_id())),
+        ("ruleIndex", Json::Int(rule_index as i64)),
+        ("level", Json::str(level)),
+        ("message", Json::object(vec![("text", Json::Str(text))])),
+        (
+            "locations",
+            Json::Array(vec![Json::object(vec![(
+                "physicalLocation",
+                Json::object(vec![
+                    (
+                        "artifactLocation",
+                        Json::object(vec![("uri", Json::str(artifact))]),
+                    ),
+                    (
+                        "region",
+                        Json::object(vec![
+                            ("startLine", Json::Int(alarm.span.line as i64)),
+                            ("startColumn", Json::Int(alarm.span.column as i64)),
+                        ]),
+                    ),
+                ]),
+            )])]),
+        ),
+        (
+            "properties",
+            Json::object(vec![
+                ("severity", Json::Str(alarm.severity.to_string())),
+                ("state", Json::state(&alarm.state)),
+            ]),
+        ),
+    ])
+}
+
+// `artifact` is the analyzed file as given on the command line
+pub fn report(artifact: &str) -> Json {
+    let results = alarms::all()
+        .iter()
+        .map(|alarm| result(alarm, artifact))
+        .collect();
+    let driver = Json::object(vec![
+        ("name", Json::str(env!("CARGO_PKG_NAME"))),
+        ("version", Json::str(env!("CARGO_PKG_VERSION"))),
+        ("rules", Json::Array(RULES.iter().map(|kind| rule(*kind)).collect())),
+    ]);
+    Json::object(vec![
+        ("$schema", Json::str(SCHEMA)),
+        ("version", Json::str("2.1.0")),
+        (
+            "runs",
+            Json::Array(vec![Json::object(vec![
+                ("tool", Json::object(vec![("driver", driver)])),
+                ("results", Json::Array(results)),
+            ])]),
+        ),
+    ])
+}
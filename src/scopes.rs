@@ -0,0 +1,86 @@
+// Block-scoped locals: `var x := e in { S }`
+//
+// The block assigns e to a fresh x, runs S and drops x at its exit: what x was outside the block,
+// a value or nothing, is back after it, so a local shadows a variable of the same name without
+// changing it. Every walk of the block (concrete run, abstract run, definite assignment, iteration
+// changes) puts the outer x back, also in the values a break or continue of the block sends to the
+// enclosing loop. The initialisation is an assignment, `var b := x < y in { .. }` declares a
+// boolean local.
+
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::ast::statement::Statement;
+use crate::ast::State;
+use crate::initialisation::Assigned;
+use crate::termination::Changes;
+
+// Values whose entry for the local can be put back as it was outside the block
+pub trait Scoped {
+    // `self` with `variable` as in `outer`
+    fn drop_local(&self, variable: &str, outer: &Self) -> Self;
+}
+
+impl Scoped for State {
+    fn drop_local(&self, variable: &str, outer: &Self) -> Self {
+        let mut state = self.clone();
+        match outer.get(variable) {
+            Some(value) => state.insert(variable.to_string(), *value),
+            None => state.remove(variable),
+        };
+        state
+    }
+}
+
+impl Scoped for AbstractState<AbstractInterval> {
+    fn drop_local(&self, variable: &str, outer: &Self) -> Self {
+        let mut state = self.clone();
+        match outer.variables.get(variable) {
+            Some(domain) => state.variables.insert(variable.to_string(), *domain),
+            None => state.variables.remove(variable),
+        };
+        // relations of the local do not hold for the outer variable
+        state.forget(variable);
+        state
+    }
+}
+
+impl Scoped for Assigned {
+    fn drop_local(&self, variable: &str, outer: &Self) -> Self {
+        let mut assigned = self.clone();
+        for (set, outer) in [
+            (&mut assigned.must, &outer.must),
+            (&mut assigned.may, &outer.may),
+        ] {
+            if outer.contains(variable) {
+                set.insert(variable.to_string());
+            } else {
+                set.remove(variable);
+            }
+        }
+        assigned
+    }
+}
+
+impl Scoped for Changes {
+    fn drop_local(&self, variable: &str, outer: &Self) -> Self {
+        let mut changes = self.clone();
+        match outer.get(variable) {
+            Some(change) => changes.insert(variable.to_string(), *change),
+            None => changes.remove(variable),
+        };
+        changes
+    }
+}
+
+// The local declared by the initialisation of a block
+pub fn local(init: &dyn Statement<Q = AbstractInterval>) -> String {
+    let assigned = init.check_initialisation(&Assigned::entry(Vec::new()), &mut Vec::new());
+    let mut variables = assigned.may.into_iter();
+    match (variables.next(), variables.next()) {
+        (Some(variable), None) => variable,
+        _ => unreachable!(
+            "the initialisation {} of a block does not declare one variable",
+            init.to_string()
+        ),
+    }
+}
@@ -0,0 +1,71 @@
+// Switch: `switch (e) { case 1: S1; case 2: S2; default: S }`
+//
+// The labels are distinct constant expressions. The cases do not fall through: the one whose label
+// is the value of e runs, the default (skip when missing) when no label is, and a break in a case
+// leaves the enclosing loop as anywhere else. The abstract run refines the variables of e in every
+// branch: to the states where e is the label of the case, and to those where e is none of the
+// labels for the default. An interval cannot leave a hole, so the default only loses the labels at
+// the ends of the values of e, e.g. x in [0, 10] with cases 0, 1 and 5 defaults to x in [2, 10].
+
+use std::collections::HashMap;
+
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::ast::arithmetic::ArithmeticExpression;
+use crate::{M, N};
+
+// States of `state` where `selector` evaluates within `target`, refining its variables
+pub fn refine(
+    state: &AbstractState<AbstractInterval>,
+    selector: &dyn ArithmeticExpression<Q = AbstractInterval>,
+    target: AbstractInterval,
+) -> AbstractState<AbstractInterval> {
+    let mut var_leaves = HashMap::new();
+    let tree = selector.to_ast(&mut state.clone(), &mut var_leaves);
+    if !tree.backward_analysis(target.intersect(&tree.get_value()), &mut var_leaves) {
+        return state.bottom();
+    }
+    let mut refined = state.clone();
+    for (variable, value) in var_leaves {
+        refined = refined.update_interval(&variable, value);
+    }
+    refined
+}
+
+// States of `state` selecting the case `label`
+pub fn case(
+    state: &AbstractState<AbstractInterval>,
+    selector: &dyn ArithmeticExpression<Q = AbstractInterval>,
+    label: i64,
+) -> AbstractState<AbstractInterval> {
+    refine(state, selector, AbstractInterval::new(label, label))
+}
+
+// States of `state` selecting the default, where `selector` is none of the `labels`
+pub fn default(
+    state: &AbstractState<AbstractInterval>,
+    selector: &dyn ArithmeticExpression<Q = AbstractInterval>,
+    labels: &[i64],
+) -> AbstractState<AbstractInterval> {
+    let value = selector.abs_evaluate(&mut state.clone());
+    refine(state, selector, complement(value, labels))
+}
+
+// Values of `value` that are none of the `labels`, as far as an interval goes: the labels at its
+// finite ends are cut off until none is left there
+fn complement(value: AbstractInterval, labels: &[i64]) -> AbstractInterval {
+    let m = *M.lock().expect("failed to lock m mutex");
+    let n = *N.lock().expect("failed to lock n mutex");
+    let mut value = value;
+    while let AbstractInterval::Bounded { lower, upper } = value {
+        let cut = if lower > m && labels.contains(&lower) {
+            AbstractInterval::new(lower + 1, upper)
+        } else if upper < n && labels.contains(&upper) {
+            AbstractInterval::new(lower, upper - 1)
+        } else {
+            break;
+        };
+        value = cut;
+    }
+    value
+}
@@ -0,0 +1,485 @@
+// Termination of loops with linear ranking functions
+//
+// A linear expression f over the program variables is a ranking function of a loop when it is
+// bounded from below in every state the body is entered in (the loop heads of the forward run
+// satisfying the guard), and every iteration decreases it by at least 1. The candidates are the
+// distances between the two sides of the comparisons of the guard, and the guard variables. The
+// change of every variable over one iteration is computed by walking the body with the states
+// recorded at its program points: `x := x + e` changes x by the value of e, any other assignment
+// by an unknown amount.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::abstract_interval::AbstractInterval;
+use crate::abstract_state::AbstractState;
+use crate::ast::arithmetic::*;
+use crate::ast::boolean::BooleanExpression;
+use crate::ast::statement::Statement;
+use crate::initialisation::Assigned;
+use crate::lexer::Span;
+use crate::procedures;
+use crate::{M, N};
+
+// Interval with possibly infinite bounds (None), wide enough for sums of i64 products
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub lower: Option<i128>,
+    pub upper: Option<i128>,
+}
+
+impl Bounds {
+    pub fn exactly(value: i128) -> Self {
+        Bounds {
+            lower: Some(value),
+            upper: Some(value),
+        }
+    }
+
+    pub fn unknown() -> Self {
+        Bounds {
+            lower: None,
+            upper: None,
+        }
+    }
+
+    // Bounds equal to m and n stand for -∞ and +∞
+    pub fn of(interval: AbstractInterval) -> Self {
+        let m = *M.lock().expect("failed to lock m mutex");
+        let n = *N.lock().expect("failed to lock n mutex");
+        match interval {
+            AbstractInterval::Bounded { lower, upper } => Bounds {
+                lower: (lower != m).then_some(lower as i128),
+                upper: (upper != n).then_some(upper as i128),
+            },
+            _ => Bounds::unknown(),
+        }
+    }
+
+    pub fn add(&self, other: &Bounds) -> Self {
+        Bounds {
+            lower: self.lower.zip(other.lower).map(|(a, b)| a + b),
+            upper: self.upper.zip(other.upper).map(|(a, b)| a + b),
+        }
+    }
+
+    pub fn scale(&self, factor: i64) -> Self {
+        let factor = factor as i128;
+        let (lower, upper) = (self.lower.map(|b| b * factor), self.upper.map(|b| b * factor));
+        if factor < 0 {
+            Bounds {
+                lower: upper,
+                upper: lower,
+            }
+        } else {
+            Bounds { lower, upper }
+        }
+    }
+
+    pub fn join(&self, other: &Bounds) -> Self {
+        Bounds {
+            lower: self.lower.zip(other.lower).map(|(a, b)| a.min(b)),
+            upper: self.upper.zip(other.upper).map(|(a, b)| a.max(b)),
+        }
+    }
+}
+
+// Linear expression c1*x1 + ... + ck*xk + constant
+#[derive(Debug, Clone, PartialEq)]
+pub struct Linear {
+    pub coefficients: BTreeMap<String, i64>,
+    pub constant: i64,
+}
+
+impl Linear {
+    pub fn constant(constant: i64) -> Self {
+        Linear {
+            coefficients: BTreeMap::new(),
+            constant,
+        }
+    }
+
+    // The expression as a linear one, None when it is not linear or the coefficients overflow
+    pub fn of(expression: &dyn ArithmeticExpression<Q = AbstractInterval>) -> Option<Linear> {
+        let any = expression.as_any();
+        if let Some(numeral) = any.downcast_ref::<Numeral>() {
+            Some(Linear::constant(numeral.0))
+        } else if let Some(variable) = any.downcast_ref::<Variable>() {
+            Some(Linear {
+                coefficients: BTreeMap::from([(variable.value.clone(), 1)]),
+                constant: 0,
+            })
+        } else if let Some(add) = any.downcast_ref::<Add>() {
+            Linear::of(add.left.as_ref())?.plus(&Linear::of(add.right.as_ref())?, 1)
+        } else if let Some(minus) = any.downcast_ref::<Minus>() {
+            Linear::of(minus.left.as_ref())?.plus(&Linear::of(minus.right.as_ref())?, -1)
+        } else if let Some(uminus) = any.downcast_ref::<Uminus>() {
+            Linear::of(uminus.right.as_ref())?.times(-1)
+        } else if let Some(product) = any.downcast_ref::<Product>() {
+            let (left, right) = (
+                Linear::of(product.left.as_ref())?,
+                Linear::of(product.right.as_ref())?,
+            );
+            if left.coefficients.is_empty() {
+                right.times(left.constant)
+            } else if right.coefficients.is_empty() {
+                left.times(right.constant)
+            } else {
+                None
+            }
+        } else {
+            None
+        }
+    }
+
+    // self + factor * other
+    pub fn plus(&self, other: &Linear, factor: i64) -> Option<Linear> {
+        let mut sum = self.clone();
+        for (name, coefficient) in &other.coefficients {
+            let term = coefficient.checked_mul(factor)?;
+            let entry = sum.coefficients.entry(name.clone()).or_insert(0);
+            *entry = entry.checked_add(term)?;
+        }
+        sum.coefficients.retain(|_, coefficient| *coefficient != 0);
+        sum.constant = sum.constant.checked_add(other.constant.checked_mul(factor)?)?;
+        Some(sum)
+    }
+
+    pub fn times(&self, factor: i64) -> Option<Linear> {
+        Linear::constant(0).plus(self, factor)
+    }
+
+    pub fn coefficient(&self, variable: &str) -> i64 {
+        self.coefficients.get(variable).copied().unwrap_or(0)
+    }
+
+    // Value of the expression in `state`
+    pub fn evaluate(&self, state: &AbstractState<AbstractInterval>) -> Bounds {
+        self.coefficients.iter().fold(
+            Bounds::exactly(self.constant as i128),
+            |sum, (name, coefficient)| {
+                let value = state
+                    .variables
+                    .get(name)
+                    .map_or_else(Bounds::unknown, |domain| Bounds::of(domain.value));
+                sum.add(&value.scale(*coefficient))
+            },
+        )
+    }
+
+    // The expression with the variables that have a single value in `state` replaced by it
+    pub fn substitute(&self, state: &AbstractState<AbstractInterval>) -> Linear {
+        let mut substituted = Linear::constant(self.constant);
+        for (name, coefficient) in &self.coefficients {
+            let value = state
+                .variables
+                .get(name)
+                .map(|domain| Bounds::of(domain.value))
+                .filter(|bounds| bounds.lower.is_some() && bounds.lower == bounds.upper)
+                .and_then(|bounds| i64::try_from(bounds.lower?).ok());
+            let term = match value {
+                Some(value) => value.checked_mul(*coefficient).map(Linear::constant),
+                None => Some(Linear {
+                    coefficients: BTreeMap::from([(name.clone(), *coefficient)]),
+                    constant: 0,
+                }),
+            };
+            match term.and_then(|term| substituted.plus(&term, 1)) {
+                Some(sum) => substituted = sum,
+                None => return self.clone(),
+            }
+        }
+        substituted
+    }
+
+    // Change of the expression over changes of its variables
+    pub fn change(&self, changes: &Changes) -> Bounds {
+        self.coefficients
+            .iter()
+            .fold(Bounds::exactly(0), |sum, (name, coefficient)| {
+                sum.add(&change_of(changes, name).scale(*coefficient))
+            })
+    }
+}
+
+impl fmt::Display for Linear {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // positive terms first and the constant before negative ones: n - i, 10 - j
+        let mut terms: Vec<(&String, &i64)> = self.coefficients.iter().collect();
+        terms.sort_by_key(|(_, coefficient)| **coefficient < 0);
+        let leading = terms.first().is_some_and(|(_, coefficient)| **coefficient < 0);
+        let mut text = if leading && self.constant != 0 {
+            self.constant.to_string()
+        } else {
+            String::new()
+        };
+        for (name, coefficient) in terms {
+            let sign = if *coefficient < 0 { "-" } else { "+" };
+            let magnitude = match coefficient.unsigned_abs() {
+                1 => name.clone(),
+                magnitude => format!("{}*{}", magnitude, name),
+            };
+            if text.is_empty() {
+                text = if *coefficient < 0 { format!("-{}", magnitude) } else { magnitude };
+            } else {
+                text = format!("{} {} {}", text, sign, magnitude);
+            }
+        }
+        if text.is_empty() {
+            return write!(f, "{}", self.constant);
+        }
+        match self.constant {
+            _ if leading => write!(f, "{}", text),
+            0 => write!(f, "{}", text),
+            constant if constant < 0 => write!(f, "{} - {}", text, constant.unsigned_abs()),
+            constant => write!(f, "{} + {}", text, constant),
+        }
+    }
+}
+
+// Change of the variables since the start of a loop iteration, the missing ones are unchanged;
+// None where no execution gets to
+pub type Changes = BTreeMap<String, Bounds>;
+
+pub fn change_of(changes: &Changes, variable: &str) -> Bounds {
+    changes
+        .get(variable)
+        .copied()
+        .unwrap_or(Bounds::exactly(0))
+}
+
+pub fn join(left: Option<Changes>, right: Option<Changes>) -> Option<Changes> {
+    match (left, right) {
+        (Some(left), Some(right)) => {
+            let mut joined = Changes::new();
+            for name in left.keys().chain(right.keys()) {
+                joined.insert(
+                    name.clone(),
+                    change_of(&left, name).join(&change_of(&right, name)),
+                );
+            }
+            Some(joined)
+        }
+        (left, None) => left,
+        (None, right) => right,
+    }
+}
+
+// Changes after `variable := expression` run in `pre`
+pub fn assign(
+    changes: Changes,
+    variable: &str,
+    expression: &dyn ArithmeticExpression<Q = AbstractInterval>,
+    pre: &AbstractState<AbstractInterval>,
+) -> Changes {
+    let mut changes = changes;
+    let change = match Linear::of(expression) {
+        // x := x + rest changes x by the value of rest
+        Some(linear) if linear.coefficient(variable) == 1 => {
+            let mut rest = linear.clone();
+            rest.coefficients.remove(variable);
+            change_of(&changes, variable).add(&rest.evaluate(pre))
+        }
+        _ => Bounds::unknown(),
+    };
+    changes.insert(variable.to_string(), change);
+    changes
+}
+
+// Changes after `i++` or `i--`, other increments change nothing
+pub fn increment(
+    changes: Changes,
+    increment: &dyn ArithmeticExpression<Q = AbstractInterval>,
+) -> Changes {
+    let any = increment.as_any();
+    let (variable, step) = if let Some(plus_plus) = any.downcast_ref::<PlusPlus>() {
+        (plus_plus.var.as_variable(), 1)
+    } else if let Some(minus_minus) = any.downcast_ref::<MinusMinus>() {
+        (minus_minus.var.as_variable(), -1)
+    } else {
+        (None, 0)
+    };
+    let mut changes = changes;
+    if let Some(variable) = variable {
+        let change = change_of(&changes, &variable.value).add(&Bounds::exactly(step));
+        changes.insert(variable.value.clone(), change);
+    }
+    changes
+}
+
+// Changes after a statement whose effect is not followed, e.g. a nested loop: every variable it
+// may assign changes by an unknown amount
+pub fn opaque<S: Statement + ?Sized>(changes: Changes, statement: &S) -> Changes {
+    let assigned = statement.check_initialisation(&Assigned::entry(Vec::new()), &mut Vec::new());
+    let mut changes = changes;
+    for name in assigned.may {
+        changes.insert(name, Bounds::unknown());
+    }
+    changes
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Terminates(String), // the ranking function
+    MayNotTerminate,
+    Unknown,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Terminates(ranking) => write!(f, "terminates (ranking function {})", ranking),
+            Verdict::MayNotTerminate => write!(f, "may not terminate"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Termination {
+    pub span: Span,
+    pub verdict: Verdict,
+}
+
+lazy_static! {
+    static ref VERDICTS: Mutex<Vec<Termination>> = Mutex::new(Vec::new());
+}
+
+// Verdict on the loop at `span` that iterates while `guard` holds, whose body is entered in the
+// states of `heads` satisfying the guard and changes the variables by `changes` (None when the
+// body never completes). `exits` tells whether the forward run found states leaving the loop.
+pub fn verdict(
+    guard: &dyn BooleanExpression<Q = AbstractInterval>,
+    heads: &AbstractState<AbstractInterval>,
+    changes: Option<Changes>,
+    exits: bool,
+) -> Verdict {
+    // neither a ranking function nor a body that never completes proves anything about a loop the
+    // forward run never leaves
+    if !exits && !heads.is_bottom() {
+        return Verdict::MayNotTerminate;
+    }
+    let entry = guard.abs_evaluate(&mut heads.clone(), false);
+    // no iteration completes, any constant is a ranking function
+    let Some(changes) = changes.filter(|_| !entry.is_bottom()) else {
+        return Verdict::Terminates("0".to_string());
+    };
+    if let Some(ranking) = ranking(guard, heads, &changes) {
+        return Verdict::Terminates(ranking.function.to_string());
+    }
+    // the guard keeps holding when the body changes none of its variables, or the loop heads go
+    // to infinity in the direction the body moves a guard variable
+    let unchanged = guard
+        .extract_variables()
+        .iter()
+        .all(|variable| change_of(&changes, &variable.value) == Bounds::exactly(0));
+    if unchanged || diverges(guard, heads, &changes) {
+        Verdict::MayNotTerminate
+    } else {
+        Verdict::Unknown
+    }
+}
+
+// Whether a guard variable the body always decreases (increases) has -∞ (+∞) among its values at
+// the loop heads
+fn diverges(
+    guard: &dyn BooleanExpression<Q = AbstractInterval>,
+    heads: &AbstractState<AbstractInterval>,
+    changes: &Changes,
+) -> bool {
+    guard.extract_variables().iter().any(|variable| {
+        let change = change_of(changes, &variable.value);
+        let at_heads = heads
+            .variables
+            .get(&variable.value)
+            .map_or_else(Bounds::unknown, |domain| Bounds::of(domain.value));
+        (change.upper.is_some_and(|upper| upper < 0) && at_heads.lower.is_none())
+            || (change.lower.is_some_and(|lower| lower > 0) && at_heads.upper.is_none())
+    })
+}
+
+// Ranking function of a loop, at least `lower` in every state the body is entered in and decreased
+// by at least `decrease` by every iteration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ranking {
+    pub function: Linear,
+    pub lower: i128,
+    pub decrease: i128,
+}
+
+// First candidate that is a ranking function of the loop iterating while `guard` holds, from the
+// loop heads `heads` with the body changing the variables by `changes`
+pub fn ranking(
+    guard: &dyn BooleanExpression<Q = AbstractInterval>,
+    heads: &AbstractState<AbstractInterval>,
+    changes: &Changes,
+) -> Option<Ranking> {
+    let entry = guard.abs_evaluate(&mut heads.clone(), false);
+    candidates(guard).into_iter().find_map(|(candidate, implied)| {
+        // filtering moves a bound at -∞ by the constants of the guard, x != 1 takes [-∞, 8] to
+        // [-998, 8] with bounds -1000 and 1000: the states entering the body bound the candidate
+        // only where the heads do
+        let entered = candidate.evaluate(heads).lower.and(candidate.evaluate(&entry).lower);
+        let lower = match (entered, implied) {
+            (Some(lower), Some(implied)) => lower.max(implied as i128),
+            (lower, implied) => lower.or(implied.map(i128::from))?,
+        };
+        let decrease = -candidate.change(changes).upper?;
+        (decrease >= 1).then_some(Ranking {
+            function: candidate,
+            lower,
+            decrease,
+        })
+    })
+}
+
+// Candidate ranking functions: upper - lower for the sides of every comparison of the guard, with
+// the least value the guard implies for it, then every guard variable and its opposite
+fn candidates(guard: &dyn BooleanExpression<Q = AbstractInterval>) -> Vec<(Linear, Option<i64>)> {
+    let mut candidates: Vec<(Linear, Option<i64>)> = guard
+        .ranking_sides()
+        .into_iter()
+        .filter_map(|(lower, upper, implied)| {
+            Some((Linear::of(upper)?.plus(&Linear::of(lower)?, -1)?, implied))
+        })
+        .collect();
+    for variable in guard.extract_variables() {
+        let linear = Linear::of(variable).expect("a variable is linear");
+        candidates.push((linear.clone(), None));
+        candidates.extend(linear.times(-1).map(|opposite| (opposite, None)));
+    }
+    candidates
+}
+
+pub fn record(span: Span, verdict: Verdict) {
+    VERDICTS
+        .lock()
+        .expect("failed to lock termination verdicts")
+        .push(Termination { span, verdict });
+}
+
+// Checks every loop of the program, returns the verdicts in source order
+pub fn check(program: &dyn Statement<Q = AbstractInterval>) -> Vec<Termination> {
+    VERDICTS
+        .lock()
+        .expect("failed to lock termination verdicts")
+        .clear();
+    program.check_termination();
+    for procedure in procedures::all() {
+        if let Some(body) = &procedure.body {
+            body.check_termination();
+        }
+    }
+    let mut verdicts = all();
+    verdicts.sort_by_key(|termination| termination.span);
+    *VERDICTS.lock().expect("failed to lock termination verdicts") = verdicts.clone();
+    verdicts
+}
+
+pub fn all() -> Vec<Termination> {
+    VERDICTS
+        .lock()
+        .expect("failed to lock termination verdicts")
+        .clone()
+}
@@ -0,0 +1,100 @@
+use crate::lexer::{Token, TokenType};
+use crate::{M, N, THRESHOLDS};
+
+// Adds widening thresholds, values outside [m, n] are dropped since the bounds are always there
+pub fn add(values: &[i64]) {
+    let m = *M.lock().expect("failed to lock m mutex");
+    let n = *N.lock().expect("failed to lock n mutex");
+    let mut thresholds = THRESHOLDS.lock().expect("failed to lock thresholds");
+    for value in values {
+        if m <= *value && *value <= n && !thresholds.contains(value) {
+            thresholds.push(*value);
+        }
+    }
+    thresholds.sort();
+}
+
+pub fn all() -> Vec<i64> {
+    THRESHOLDS.lock().expect("failed to lock thresholds").clone()
+}
+
+// Threshold inference: the constants c of every loop guard and of the assignment right before
+// the loop (its initialisation) give the thresholds c - 1, c and c + 1
+pub fn infer(tokens: &[Token]) -> Vec<i64> {
+    let mut inferred = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let guard = match token.token_ty {
+            TokenType::While | TokenType::For | TokenType::Until => {
+                parenthesized(tokens, i + 1)
+            }
+            _ => continue,
+        };
+        let mut constants = literals(tokens, guard);
+        if token.token_ty != TokenType::Until {
+            if let Some(assignment) = preceding_assignment(tokens, i) {
+                constants.extend(literals(tokens, assignment));
+            }
+        }
+        for c in constants {
+            inferred.extend([c.saturating_sub(1), c, c.saturating_add(1)]);
+        }
+    }
+    inferred
+}
+
+// Token range of the parenthesized guard starting at `start`
+fn parenthesized(tokens: &[Token], start: usize) -> (usize, usize) {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        match token.token_ty {
+            TokenType::Bra => depth += 1,
+            TokenType::Ket => {
+                depth -= 1;
+                if depth == 0 {
+                    return (start, i);
+                }
+            }
+            _ => {}
+        }
+    }
+    (start, start)
+}
+
+// Token range of the assignment closed by the `;` right before the loop keyword at `keyword`
+fn preceding_assignment(tokens: &[Token], keyword: usize) -> Option<(usize, usize)> {
+    if keyword == 0 || tokens[keyword - 1].token_ty != TokenType::Semicolon {
+        return None;
+    }
+    let end = keyword - 1;
+    let start = tokens[..end]
+        .iter()
+        .rposition(|token| {
+            matches!(
+                token.token_ty,
+                TokenType::Semicolon | TokenType::CBra | TokenType::Cket
+            )
+        })
+        .map_or(0, |i| i + 1);
+    tokens[start..end]
+        .iter()
+        .any(|token| token.token_ty == TokenType::Assign)
+        .then_some((start, end))
+}
+
+// Integer literals in the token range, a '-' that cannot be a binary minus negates the literal
+fn literals(tokens: &[Token], (start, end): (usize, usize)) -> Vec<i64> {
+    let mut values = Vec::new();
+    for i in start..end {
+        if let TokenType::Number(value) = tokens[i].token_ty {
+            let negated = i >= 1
+                && tokens[i - 1].token_ty == TokenType::Minus
+                && (i < 2
+                    || !matches!(
+                        tokens[i - 2].token_ty,
+                        TokenType::Number(_) | TokenType::Identifier(_) | TokenType::Ket
+                    ));
+            values.push(if negated { -value } else { value });
+        }
+    }
+    values
+}
@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::lexer::Span;
+use crate::UNROLL;
+
+lazy_static! {
+    // Per-loop unrolling from `@unroll(k)` annotations, identified by the span of the loop keyword
+    static ref LOOP_UNROLLING: Mutex<HashMap<Span, u64>> = Mutex::new(HashMap::new());
+}
+
+pub fn set(span: Span, iterations: u64) {
+    LOOP_UNROLLING
+        .lock()
+        .expect("failed to lock loop unrolling")
+        .insert(span, iterations);
+}
+
+// Iterations of the loop at `span` analysed one by one before its fixpoint, --unroll by default
+pub fn iterations(span: Span) -> u64 {
+    let annotated = LOOP_UNROLLING
+        .lock()
+        .expect("failed to lock loop unrolling")
+        .get(&span)
+        .copied();
+    annotated.unwrap_or_else(|| *UNROLL.lock().expect("failed to lock unroll"))
+}
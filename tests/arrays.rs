@@ -0,0 +1,117 @@
+// Fixed-size arrays: element reads and writes in both semantics, the smashed abstract value and
+// the out-of-bounds checker
+mod common;
+
+use common::*;
+
+#[test]
+fn elements_in_the_concrete_run() {
+    let run = execute("a[3];\na[1] := 4;\nx := a[1] + a[0]\n", &[]);
+    assert_eq!(final_concrete(&run, "x"), 4);
+    assert_eq!(final_concrete(&run, "a[1]"), 4);
+    assert_eq!(final_concrete(&run, "a[2]"), 0);
+}
+
+#[test]
+fn concrete_access_out_of_bounds_is_a_runtime_error() {
+    let output = run("a[3];\ni := 3;\nx := a[i]\n", &["--analysis", "concrete"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "**RUNTIME ERROR, index 3 out of the bounds [0, 2] of array a**\n"
+    );
+}
+
+#[test]
+fn negative_indexes_are_out_of_bounds() {
+    for program in ["a[3];\nx := a[-1]\n", "a[3];\na[-1] := 2\n"] {
+        let output = run(program, &["--analysis", "concrete"]);
+        assert_eq!(output.status.code(), Some(1), "{}", program);
+        assert_eq!(
+            stderr(&output),
+            "**RUNTIME ERROR, index -1 out of the bounds [0, 2] of array a**\n"
+        );
+    }
+    let report: String = analyze("a[3];\nx := a[-1]\n", &["--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(report.contains(
+        "{\"kind\":\"out-of-bounds\",\"severity\":\"definite\",\
+         \"span\":{\"line\":2,\"column\":1},\"expression\":\"a[-1]\""
+    ));
+    // the leading minus of an index binds tighter than the binary operators
+    let run = execute("a[3];\ni := 2;\na[-i + 3] := 7;\nx := a[1]\n", &[]);
+    assert_eq!(final_concrete(&run, "x"), 7);
+}
+
+#[test]
+fn elements_are_smashed_into_one_interval() {
+    // weak update: the elements not written keep their 0
+    let report = analyze("input i in [0, 9];\na[10];\na[i] := 5;\nx := a[i]\n", &[]);
+    assert_eq!(final_value(&report, "a"), "[0, 5]");
+    assert_eq!(final_value(&report, "x"), "[0, 5]");
+    // an array of one element is updated strongly
+    let report = analyze("a[1];\na[0] := 7;\nx := a[0]\n", &[]);
+    assert_eq!(final_value(&report, "x"), "[7, 7]");
+}
+
+#[test]
+fn index_intervals_against_the_bounds() {
+    let within = analyze(
+        "a[10];\nfor (i := 0; i < 10; i++) { a[i] := i }\n",
+        &["--format", "json"],
+    );
+    assert!(within.contains("\"alarms\": []"), "{}", within);
+    let past_the_end: String = analyze(
+        "a[10];\nfor (i := 0; i <= 10; i++) { a[i] := i }\n",
+        &["--format", "json"],
+    )
+    .split_whitespace()
+    .collect();
+    assert!(past_the_end.contains(
+        "{\"kind\":\"out-of-bounds\",\"severity\":\"possible\",\
+         \"span\":{\"line\":2,\"column\":30},\"expression\":\"a[i]\""
+    ));
+    let outside: String = analyze("a[3];\na[3] := 1\n", &["--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(outside.contains(
+        "{\"kind\":\"out-of-bounds\",\"severity\":\"definite\",\
+         \"span\":{\"line\":2,\"column\":1},\"expression\":\"a[3]\""
+    ));
+    // only the indexes within the array go on
+    let report = analyze("input i in [0, 10];\na[10];\na[i] := 5\n", &[]);
+    assert_eq!(final_value(&report, "i"), "[0, 9]");
+}
+
+#[test]
+fn element_assignment_is_located_at_the_array() {
+    let report = analyze("a[2];\n  a[1] := 7", &["--format", "json"]);
+    assert!(
+        report.contains(
+            "\"kind\": \"array-assign\",\n      \"span\": {\n        \"line\": 2,\n        \"column\": 3"
+        ),
+        "{}",
+        report
+    );
+}
+
+#[test]
+fn bad_declarations_are_errors() {
+    let cases = [
+        ("x := b[1]\n", "array b used at 1:6 without being declared"),
+        (
+            "a[0];\nx := 1\n",
+            "array a declared at 1:1 with size 0, sizes are positive",
+        ),
+        ("a[2];\na[2];\nx := 1\n", "array a declared again at 2:1"),
+    ];
+    for (program, message) in cases {
+        let output = run(
+            program,
+            &["--analysis", "abstract", "--bounds", "-10", "10"],
+        );
+        assert_eq!(output.status.code(), Some(2), "{}", program);
+        assert_eq!(stderr(&output), format!("**SYNTAX ERROR, {}**\n", message));
+    }
+}
@@ -0,0 +1,124 @@
+// Backward analysis (necessary preconditions of assertion failures and postconditions) and the
+// forward-backward refinement
+mod common;
+
+use common::*;
+
+// State that the backward analysis computes before the statement at `point`
+fn before<'a>(report: &'a str, point: &str) -> &'a str {
+    let prefix = format!("    BEFORE {}: ", point);
+    report
+        .lines()
+        .find_map(|line| line.strip_prefix(prefix.as_str()))
+        .unwrap_or_else(|| panic!("no state before {} in {}", point, report))
+}
+
+// x is [0, +∞] after the first loop, the counter k bounds only itself
+const COUNTED: &str = "x := 0;\nk := 0;\nwhile (k <= 9) { k := k + 1; x := x + 1 };\n";
+
+#[test]
+fn values_that_make_the_assertion_fail() {
+    let program = format!("{}y := x + 3;\nassert(y <= 10)\n", COUNTED);
+    let report = analyze(&program, &["--backward"]);
+    assert_eq!(
+        lines(&report, "NECESSARY PRECONDITION"),
+        ["NECESSARY PRECONDITION (assertion at 5:1 fails): {}"]
+    );
+    assert!(before(&report, "4:1").contains("x:   [8, "), "{}", report);
+}
+
+#[test]
+fn postcondition_through_a_loop() {
+    let program = format!("{}while (x > 10) {{\n  x := x - 3\n}};\ny := x\n", COUNTED);
+    let report = analyze(&program, &["--backward", "--postcondition", "y >= 9"]);
+    assert_eq!(
+        lines(&report, "NECESSARY PRECONDITION"),
+        ["NECESSARY PRECONDITION (postcondition y >= 9 holds): {}"]
+    );
+    assert!(
+        before(&report, "4:1").contains("x:   [9, +∞]"),
+        "{}",
+        report
+    );
+    assert!(
+        before(&report, "5:3").contains("x:   [12, +∞]"),
+        "{}",
+        report
+    );
+    assert!(
+        before(&report, "7:1").contains("x:   [9, 10]"),
+        "{}",
+        report
+    );
+}
+
+#[test]
+fn malformed_postconditions_are_usage_errors() {
+    let program = format!("{}y := x\n", COUNTED);
+    for guard in ["y > 15 +", "y >", "(y > 1", "y + 1", "y > 1 &&"] {
+        let output = run(
+            &program,
+            &["--analysis", "abstract", "--bounds", "-10", "10", "--postcondition", guard],
+        );
+        assert_eq!(output.status.code(), Some(2), "{}", guard);
+        assert_eq!(
+            stderr(&output),
+            format!("--postcondition {:?} is not a guard\n", guard)
+        );
+    }
+    let report = analyze(&program, &["--postcondition", "!(y > 1) || y < 2 && x != 3"]);
+    assert!(!lines(&report, "NECESSARY PRECONDITION").is_empty());
+}
+
+#[test]
+fn unreachable_postcondition_has_bottom_precondition() {
+    let program = format!("{}y := x * 2;\nassert(y < 10)\n", COUNTED);
+    let report = analyze(&program, &["--backward", "--postcondition", "y > 15"]);
+    assert!(report.contains("NECESSARY PRECONDITION (postcondition y > 15 holds): Bottom ⊥"));
+}
+
+#[test]
+fn backward_results_in_the_json_report() {
+    let program = format!("{}y := x + 3;\nassert(y <= 10)\n", COUNTED);
+    let report: String = analyze(&program, &["--backward", "--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(report.contains("\"backward\":[{\"goal\":\"assertionat5:1fails\""));
+    assert!(report.contains(
+        "{\"span\":{\"line\":4,\"column\":1},\"state\":{\"bottom\":false,\
+         \"variables\":{\"k\":{\"lower\":10,\"upper\":10},\"x\":{\"lower\":8,\"upper\":"
+    ));
+}
+
+// the assertion bounds y, the backward pass carries the bound to x and z
+const ASSERTED_COPY: &str =
+    "x := 0;\nk := 0;\nwhile (k <= 9) { k := k + 1; x := x + 1 };\ny := x;\nassert(y <= 10);\nz := x\n";
+
+#[test]
+fn refinement_tightens_the_forward_invariants() {
+    let forward = analyze(ASSERTED_COPY, &[]);
+    assert_eq!(final_value(&forward, "x"), "[0, +∞]");
+    assert_eq!(final_value(&forward, "z"), "[0, +∞]");
+    let refined = analyze(ASSERTED_COPY, &["--refine", "3"]);
+    assert!(!lines(&refined, "REFINEMENT ROUND 1").is_empty());
+    assert_eq!(final_value(&refined, "x"), "[0, 10]");
+    assert_eq!(final_value(&refined, "y"), "[0, 10]");
+    assert_eq!(final_value(&refined, "z"), "[0, 10]");
+}
+
+#[test]
+fn refinement_stops_when_stable() {
+    let report: String = analyze(ASSERTED_COPY, &["--refine", "10", "--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(report.contains("\"refinement\":{\"rounds\":3,\"stable\":true}"));
+    let listing = analyze(ASSERTED_COPY, &["--refine", "10", "--format", "annotated"]);
+    assert!(listing.ends_with("z := x\n/* {k: [10, 10], x: [0, 10], y: [0, 10], z: [0, 10]} */\n"));
+}
+
+#[test]
+fn refinement_keeps_the_alarms_of_every_execution() {
+    let program = format!("{}y := 10 / x;\nassert(x >= 1)\n", COUNTED);
+    let report = analyze(&program, &["--refine", "2", "--format", "json"]);
+    assert!(report.contains("division-by-zero"));
+}
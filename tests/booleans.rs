@@ -0,0 +1,41 @@
+// Boolean variables: `b := x < y` and `b` as a guard, related to the comparison it was assigned
+mod common;
+
+use common::*;
+
+#[test]
+fn booleans_are_one_or_zero_in_the_concrete_run() {
+    let run = execute("x := 3;\ny := 5;\nb := x < y;\nc := !b\n", &[]);
+    assert_eq!(final_concrete(&run, "b"), 1);
+    assert_eq!(final_concrete(&run, "c"), 0);
+}
+
+#[test]
+fn testing_a_boolean_refines_like_its_comparison() {
+    let report = analyze(
+        "x := random(0, 10);\ny := 5;\nb := x < y;\nif b then { z := x } else { z := 0 - x }\n",
+        &[],
+    );
+    assert_eq!(final_value(&report, "b"), "[0, 1]");
+    // [0, 4] in the then branch, [-10, -5] in the else branch
+    assert_eq!(final_value(&report, "z"), "[-10, 4]");
+}
+
+#[test]
+fn assigning_a_read_variable_drops_the_relation() {
+    let report = analyze(
+        "x := random(0, 10);\nb := x < 5;\nx := 7;\nif b then { z := x } else { z := 0 }\n",
+        &[],
+    );
+    assert_eq!(final_value(&report, "z"), "[0, 7]");
+}
+
+#[test]
+fn booleans_hold_only_booleans() {
+    let output = run("b := 1 < 2;\nb := 3\n", &["--analysis", "concrete"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "**SYNTAX ERROR, boolean variable b assigned something other than a boolean at 2:1**\n"
+    );
+}
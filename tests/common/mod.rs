@@ -0,0 +1,113 @@
+// Helpers of the integration tests: every test runs the analyzer binary on a program, as from
+// the command line, so that the global settings of one run do not leak into another
+#![allow(dead_code)]
+
+use std::fs;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static PROGRAMS: AtomicUsize = AtomicUsize::new(0);
+
+// Writes `program` to a fresh file and runs the analyzer on it with `args`, stdin closed so that
+// a missing setting fails instead of waiting for an answer
+pub fn run(program: &str, args: &[&str]) -> Output {
+    let path = std::env::temp_dir().join(format!(
+        "softver-test-{}-{}.txt",
+        std::process::id(),
+        PROGRAMS.fetch_add(1, Ordering::SeqCst)
+    ));
+    fs::write(&path, program).expect("failed to write the program");
+    let output = Command::new(env!("CARGO_BIN_EXE_softver"))
+        .arg(&path)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .expect("failed to run the analyzer");
+    fs::remove_file(&path).expect("failed to remove the program");
+    output
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+// Abstract analysis with bounds -1000 and 1000, widening and narrowing, then `extra`
+pub fn analyze(program: &str, extra: &[&str]) -> String {
+    let mut args = vec![
+        "--analysis",
+        "abstract",
+        "--bounds",
+        "-1000",
+        "1000",
+        "--widening",
+        "y",
+        "--narrowing",
+        "y",
+    ];
+    args.extend(extra);
+    succeed(run(program, &args))
+}
+
+// Concrete run with `extra`
+pub fn execute(program: &str, extra: &[&str]) -> String {
+    let mut args = vec!["--analysis", "concrete"];
+    args.extend(extra);
+    succeed(run(program, &args))
+}
+
+fn succeed(output: Output) -> String {
+    assert!(
+        output.status.success(),
+        "the analyzer failed:\n{}{}",
+        stdout(&output),
+        stderr(&output)
+    );
+    stdout(&output)
+}
+
+// Value of `variable` in the final abstract state printed by a text report, e.g. "[0, 7]"
+pub fn final_value(report: &str, variable: &str) -> String {
+    let state = report
+        .lines()
+        .find_map(|line| line.strip_prefix("state printing after code evaluation "))
+        .expect("no final state in the report");
+    let entries = state.split_once('{').map_or("", |(_, entries)| entries);
+    entries
+        .trim_end_matches('}')
+        .split(" , ")
+        .find_map(|entry| {
+            let (name, value) = entry.split_once(':')?;
+            (name.trim() == variable).then(|| value.trim().to_string())
+        })
+        .unwrap_or_else(|| panic!("{} is not in the final state {}", variable, state))
+}
+
+// Lines of a text report starting with `prefix`
+pub fn lines<'a>(report: &'a str, prefix: &str) -> Vec<&'a str> {
+    report
+        .lines()
+        .filter(|line| line.starts_with(prefix))
+        .collect()
+}
+
+// Value of `variable` in the final state printed by a concrete run
+pub fn final_concrete(report: &str, variable: &str) -> i64 {
+    let state = report
+        .lines()
+        .find_map(|line| line.strip_prefix("state printing after code evaluation "))
+        .expect("no final state in the report");
+    let key = format!("\"{}\": ", variable);
+    let start = state
+        .find(&key)
+        .unwrap_or_else(|| panic!("{} is not in the final state {}", variable, state))
+        + key.len();
+    let digits: String = state[start..]
+        .chars()
+        .take_while(|character| *character == '-' || character.is_ascii_digit())
+        .collect();
+    digits.parse().expect("not an integer value")
+}
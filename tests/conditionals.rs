@@ -0,0 +1,84 @@
+// Conditional expressions `b ? e1 : e2` and the short-circuit semantics of `&&` and `||`
+mod common;
+
+use common::*;
+
+#[test]
+fn guarded_division_raises_no_alarm() {
+    let program = "x := random(0, 10) - 5; \
+                   if x != 0 && 10 / x > 1 then { y := 1 } else { y := 0 }; \
+                   z := x = 0 || 10 / x < 3 ? 1 : 2";
+    let report = analyze(program, &["--format", "json"]);
+    assert!(!report.contains("division-by-zero"), "{}", report);
+    for seed in ["1", "2", "3", "4", "5", "6"] {
+        let run = execute(program, &["--seed", seed]);
+        assert!(!run.contains("RUNTIME ERROR"), "{}", run);
+    }
+    let unguarded = analyze("x := random(0, 10) - 5; y := 10 / x", &["--format", "json"]);
+    assert!(unguarded.contains("division-by-zero"));
+}
+
+#[test]
+fn right_operand_only_runs_when_needed() {
+    // a division by zero in the right operand would stop the concrete run
+    let program = "x := 0; if x != 0 && 10 / x > 1 then { y := 1 } else { y := 2 }; \
+                   if x = 0 || 10 / x > 1 then { z := 1 } else { z := 2 }";
+    let report = execute(program, &[]);
+    assert_eq!(final_concrete(&report, "y"), 2);
+    assert_eq!(final_concrete(&report, "z"), 1);
+}
+
+#[test]
+fn false_branch_of_a_conjunction() {
+    let program = "x := 3; b := x > 1; if b && x > 2 then { y := 1 } else { y := 2 }";
+    assert_eq!(final_value(&analyze(program, &[]), "y"), "[1, 1]");
+}
+
+#[test]
+fn conditional_splits_the_state() {
+    let program = "x := random(0, 10) - 5; y := x > 0 ? x : 0 - x; z := x != 0 ? 10 / x : 0";
+    let report = analyze(program, &[]);
+    assert_eq!(final_value(&report, "y"), "[0, 5]");
+    assert_eq!(final_value(&report, "z"), "[-10, 10]");
+    let json = analyze(program, &["--format", "json"]);
+    assert!(!json.contains("division-by-zero"), "{}", json);
+}
+
+#[test]
+fn concrete_conditional_picks_one_branch() {
+    let program = "x := 4; y := x > 3 ? x * 2 : 10 / 0; z := x < 3 ? 1 : x = 4 ? 2 : 3";
+    let report = execute(program, &[]);
+    assert_eq!(final_concrete(&report, "y"), 8);
+    assert_eq!(final_concrete(&report, "z"), 2);
+}
+
+#[test]
+fn conditional_in_a_larger_expression() {
+    let program = "x := 2; y := (x > 0 ? x : 0 - x) + 1; b := (x > 0 ? 1 : 2) > 0; \
+                   if b then { z := 1 } else { z := 2 }";
+    let report = execute(program, &[]);
+    assert_eq!(final_concrete(&report, "y"), 3);
+    assert_eq!(final_concrete(&report, "z"), 1);
+    // y holds an integer although its right-hand side has a comparison
+    assert_eq!(final_value(&analyze(program, &[]), "y"), "[3, 3]");
+}
+
+#[test]
+fn negative_operands_in_the_branches() {
+    let program = "x := random(0, 10) - 5; b := x > 0; y := b ? -1 : 1; z := b ? 1 : -1; \
+                   w := x < 0 ? -x : x";
+    let report = execute(program, &["--random", "1"]);
+    let values = ["y", "z", "w"].map(|name| final_concrete(&report, name));
+    assert_eq!(values, [1, -1, 4]);
+    let report = analyze(program, &[]);
+    let values = ["y", "z", "w"].map(|name| final_value(&report, name));
+    assert_eq!(values, ["[-1, 1]", "[-1, 1]", "[0, 5]"]);
+}
+
+#[test]
+fn question_mark_without_operand_is_any_value() {
+    let program = "x := ?; y := x > 0 ? 1 : 0";
+    let report = analyze(program, &[]);
+    assert_eq!(final_value(&report, "x"), "[-∞, +∞]");
+    assert_eq!(final_value(&report, "y"), "[0, 1]");
+}
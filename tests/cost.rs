@@ -0,0 +1,92 @@
+// Loop bounds: the iterations of each loop, as an interval and a symbolic bound, and the cost of
+// the whole program
+mod common;
+
+use common::*;
+
+#[test]
+fn numeric_and_symbolic_bounds() {
+    let report = analyze("x := 0;\nwhile (x < 10) { x := x + 1 }\n", &[]);
+    assert_eq!(
+        lines(&report, "ITERATIONS"),
+        ["ITERATIONS OF THE LOOP AT 2:1: [1, 10], bound 10"]
+    );
+    let report = analyze("input n in [0, 20];\nwhile (n > 0) { n := n - 1 }\n", &[]);
+    assert_eq!(
+        lines(&report, "ITERATIONS"),
+        ["ITERATIONS OF THE LOOP AT 2:1: [0, 20], bound max(0, n)"]
+    );
+    assert_eq!(
+        lines(&report, "COST"),
+        ["COST (EXECUTIONS OF LOOP BODIES): [0, 20], bound max(0, n)"]
+    );
+}
+
+#[test]
+fn decrementing_for_loop_runs_eleven_times() {
+    let report = analyze("x := 0;\nfor (i := 11; i > 0; i--) { x := x + 1 }\n", &[]);
+    assert_eq!(
+        lines(&report, "ITERATIONS"),
+        ["ITERATIONS OF THE LOOP AT 2:1: [1, 11], bound 11"]
+    );
+}
+
+#[test]
+fn loops_that_are_never_left_are_unbounded() {
+    let report = analyze("input x in [0, 5];\nwhile (x >= 0) { x := x + 1 }\n", &[]);
+    assert_eq!(
+        lines(&report, "ITERATIONS"),
+        ["ITERATIONS OF THE LOOP AT 2:1: [1, +∞], unbounded"]
+    );
+    // 11 is beyond the bounds, the forward run never leaves the loop and bounds nothing
+    let output = run(
+        "x := 0;\nfor (i := 11; i > 0; i--) { x := x + 1 }\n",
+        &["--analysis", "abstract", "--bounds", "-5", "5"],
+    );
+    assert_eq!(
+        lines(&stdout(&output), "ITERATIONS"),
+        ["ITERATIONS OF THE LOOP AT 2:1: [1, +∞], unbounded"]
+    );
+}
+
+#[test]
+fn stepping_over_the_disequality_is_unbounded() {
+    // the loop heads go down to -∞, no finite bound comes from the sentinel moved by the guard
+    for program in [
+        "x := 8;\nwhile (x != 1) { x := x - 2 }\n",
+        "x := 10;\nwhile (x != 0) { x := x - 3 }\n",
+    ] {
+        let report = analyze(program, &[]);
+        assert_eq!(
+            lines(&report, "ITERATIONS"),
+            ["ITERATIONS OF THE LOOP AT 2:1: [1, +∞], unbounded"],
+            "{}",
+            program
+        );
+        assert_eq!(
+            lines(&report, "COST"),
+            ["COST (EXECUTIONS OF LOOP BODIES): [1, +∞], unbounded"]
+        );
+    }
+}
+
+#[test]
+fn nested_loops_multiply() {
+    let program = "for (i := 0; i < 3; i++) { j := 0; while (j < 4) { j := j + 1 } }\n";
+    let report = analyze(program, &[]);
+    assert_eq!(
+        lines(&report, "COST"),
+        ["COST (EXECUTIONS OF LOOP BODIES): [2, 15], bound 3 + 4 * 3"]
+    );
+    let report: String = analyze(program, &["--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(report.contains("\"total\":{\"lower\":2,\"upper\":15,\"bound\":\"3+4*3\"}"));
+}
+
+#[test]
+fn no_cost_without_loops() {
+    let report = analyze("x := 1;\ny := x + 2\n", &[]);
+    assert!(lines(&report, "COST").is_empty());
+    assert!(lines(&report, "ITERATIONS").is_empty());
+}
@@ -0,0 +1,54 @@
+// Parsing of guards: comparisons first, then `!`, `&&` and `||` by precedence, with parentheses
+// grouping any of them
+mod common;
+
+use common::*;
+
+#[test]
+fn connective_with_comparison_on_the_right() {
+    let program = "x := 3; b := x > 1; if b && x > 2 then { y := 1 } else { y := 2 }";
+    assert_eq!(final_concrete(&execute(program, &[]), "y"), 1);
+    assert!(analyze(program, &[]).contains("b && x > 2"));
+}
+
+#[test]
+fn parenthesised_operands() {
+    let program = "x := 3; b := x > 1; \
+                   if x > 0 && (x > 2) then { y := 1 } else { y := 2 }; \
+                   if b && (x > 5 || x < 4) then { z := 1 } else { z := 2 }";
+    let report = execute(program, &[]);
+    assert_eq!(final_concrete(&report, "y"), 1);
+    assert_eq!(final_concrete(&report, "z"), 1);
+}
+
+#[test]
+fn and_binds_tighter_than_or() {
+    // true || (false && false) is true, (true || false) && false would be false
+    let program = "x := 1; if x = 1 || x = 2 && x = 3 then { y := 1 } else { y := 2 }";
+    assert_eq!(final_concrete(&execute(program, &[]), "y"), 1);
+    let grouped = "x := 1; if (x = 1 || x = 2) && x = 3 then { y := 1 } else { y := 2 }";
+    assert_eq!(final_concrete(&execute(grouped, &[]), "y"), 2);
+}
+
+#[test]
+fn negation_of_a_group() {
+    let program = "x := random(0, 10); if !(x > 2) then { y := x } else { y := 0 }";
+    assert_eq!(final_value(&analyze(program, &[]), "y"), "[0, 2]");
+    let program = "x := 5; if !(x > 2 && x < 4) then { y := 1 } else { y := 2 }";
+    assert_eq!(final_concrete(&execute(program, &[]), "y"), 1);
+}
+
+#[test]
+fn printed_guards_keep_the_grouping() {
+    let program = "x := 1; if (x > 1 || x < 0) && x != 5 then { skip } else { skip }";
+    let report = analyze(program, &[]);
+    assert!(report.contains("(x > 1 || x < 0) && x != 5"), "{}", report);
+}
+
+#[test]
+fn postcondition_with_connectives() {
+    let program = "x := random(0, 10)";
+    let report = analyze(program, &["--postcondition", "x > 2 && (x < 4 || x > 8)"]);
+    let precondition = lines(&report, "NECESSARY PRECONDITION");
+    assert_eq!(precondition.len(), 1, "{}", report);
+}
@@ -0,0 +1,62 @@
+// Definite assignment: reads of variables that may be unassigned, and the runtime error of the
+// concrete run on such a read
+mod common;
+
+use common::*;
+
+#[test]
+fn reads_unassigned_on_some_path() {
+    let program = "if y > 0 then { x := 1 } else { skip };\nz := x;\nw := q + 1\n";
+    let report = analyze(program, &[]);
+    assert_eq!(
+        lines(&report, "UNINITIALISED READ"),
+        [
+            "UNINITIALISED READ (definite) OF y AT 1:1",
+            "UNINITIALISED READ (possible) OF x AT 2:1",
+            "UNINITIALISED READ (definite) OF q AT 3:1",
+        ]
+    );
+}
+
+#[test]
+fn loops_may_run_zero_times() {
+    let program = "input n in [0, 3];\nwhile (n > 0) { s := n; n := n - 1 };\nt := s;\n\
+                   x := 1;\nif n = 0 then { x := 2 } else { x := 3 };\nk := x\n";
+    let report = analyze(program, &[]);
+    assert_eq!(
+        lines(&report, "UNINITIALISED READ"),
+        ["UNINITIALISED READ (possible) OF s AT 3:1"]
+    );
+    let report = analyze("for (i := 0; i < 3; i++) { u := i };\nv := u\n", &[]);
+    assert_eq!(
+        lines(&report, "UNINITIALISED READ"),
+        ["UNINITIALISED READ (possible) OF u AT 2:1"]
+    );
+}
+
+#[test]
+fn inputs_are_assigned() {
+    let report = analyze("input x in [0, 5];\ny := x + 1\n", &[]);
+    assert!(lines(&report, "UNINITIALISED READ").is_empty());
+}
+
+#[test]
+fn reads_are_alarms_of_the_json_report() {
+    let report: String = analyze("z := 1;\ny := x\n", &["--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(report.contains(
+        "{\"kind\":\"uninitialised-read\",\"severity\":\"definite\",\
+         \"span\":{\"line\":2,\"column\":1},\"expression\":\"x\""
+    ));
+}
+
+#[test]
+fn concrete_read_is_a_runtime_error() {
+    let output = run("z := 1;\ny := x + z\n", &["--analysis", "concrete"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "**RUNTIME ERROR, variable x read before being assigned while applying denotational semantics**\n"
+    );
+}
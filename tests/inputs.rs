@@ -0,0 +1,82 @@
+// Input declarations `input x in [a, b];` and `input x;`, and --input on the command line
+mod common;
+
+use common::*;
+
+const DOUBLE: &str = "input x in [0, 100];\ny := x * 2\n";
+
+#[test]
+fn declared_range_is_the_initial_state() {
+    let report = analyze(DOUBLE, &[]);
+    assert_eq!(final_value(&report, "x"), "[0, 100]");
+    assert_eq!(final_value(&report, "y"), "[0, 200]");
+}
+
+#[test]
+fn declarations_do_not_depend_on_blanks() {
+    let report = analyze("input x in[-5,5];input y;\nz := x", &[]);
+    assert_eq!(final_value(&report, "z"), "[-5, 5]");
+    assert_eq!(final_value(&report, "y"), "[-∞, +∞]");
+}
+
+#[test]
+fn command_line_inputs() {
+    let report = analyze(DOUBLE, &["--input", "x=3..4"]);
+    assert_eq!(final_value(&report, "y"), "[6, 8]");
+    let run = execute(DOUBLE, &["--input", "x=7"]);
+    assert_eq!(final_concrete(&run, "y"), 14);
+}
+
+#[test]
+fn concrete_run_needs_a_value_in_range() {
+    let missing = run(DOUBLE, &["--analysis", "concrete"]);
+    assert_eq!(missing.status.code(), Some(2));
+    assert_eq!(
+        stderr(&missing),
+        "the concrete run needs a value for input x, give it with --input x=v\n"
+    );
+    let outside = run(DOUBLE, &["--analysis", "concrete", "--input", "x=700"]);
+    assert_eq!(outside.status.code(), Some(2));
+    assert_eq!(
+        stderr(&outside),
+        "input x=700 is out of its declared range [0, 100]\n"
+    );
+}
+
+#[test]
+fn malformed_declarations_are_errors() {
+    let expected = "is not `input x;` or `input x in [a, b];`, found";
+    let cases = [
+        (
+            "input x in [0 100]; y := x",
+            format!("input declaration at 1:1 {} \"100\" at 1:15", expected),
+        ),
+        (
+            "y := 1;\ninput 3;",
+            format!("input declaration at 2:1 {} \"3\" at 2:7", expected),
+        ),
+        (
+            "input x in [a, 3]; y := 1",
+            format!("input declaration at 1:1 {} \"a\" at 1:13", expected),
+        ),
+        (
+            "input x",
+            format!(
+                "input declaration at 1:1 {} the end of the program",
+                expected
+            ),
+        ),
+        (
+            "input x in [5, 1]; y := x",
+            "input declaration at 1:1 has the empty range [5, 1]".to_string(),
+        ),
+    ];
+    for (program, message) in cases {
+        let output = run(
+            program,
+            &["--analysis", "abstract", "--bounds", "-10", "10"],
+        );
+        assert_eq!(output.status.code(), Some(2), "{}", program);
+        assert_eq!(stderr(&output), format!("**SYNTAX ERROR, {}**\n", message));
+    }
+}
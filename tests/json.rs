@@ -0,0 +1,63 @@
+// JSON report of the abstract analysis, compared without whitespace
+mod common;
+
+use common::*;
+
+fn json(program: &str, extra: &[&str]) -> String {
+    let mut args = vec!["--format", "json"];
+    args.extend(extra);
+    analyze(program, &args).split_whitespace().collect()
+}
+
+#[test]
+fn final_state_lists_variables_in_sorted_order() {
+    let report = json("z := 3; a := 7; m := a + z", &[]);
+    assert!(report.starts_with("{\"analysis\":\"abstract\",\"bounds\":{\"m\":-1000,\"n\":1000}"));
+    assert!(report.contains(
+        "\"final_state\":{\"bottom\":false,\"variables\":{\
+         \"a\":{\"lower\":7,\"upper\":7},\
+         \"m\":{\"lower\":10,\"upper\":10},\
+         \"z\":{\"lower\":3,\"upper\":3}}}"
+    ));
+}
+
+#[test]
+fn infinite_bounds_are_null() {
+    let report = json(
+        "x := 0; while (x >= 0) { x := x + 1 }",
+        &["--narrowing", "n"],
+    );
+    assert!(report.contains("\"invariants\":[{\"span\":{\"line\":1,\"column\":9}"));
+    assert!(report.contains(
+        "\"state\":{\"bottom\":false,\"variables\":{\"x\":{\"lower\":0,\"upper\":null}}}}]"
+    ));
+}
+
+#[test]
+fn division_by_zero_is_an_alarm() {
+    let report = json("x := 0;\ny := 10 / x", &[]);
+    assert!(report.contains(
+        "\"alarms\":[{\"kind\":\"division-by-zero\",\"severity\":\"definite\",\
+         \"span\":{\"line\":2,\"column\":1},\"expression\":\"(10/x)\""
+    ));
+    let safe = json("x := 2;\ny := 10 / x", &[]);
+    assert!(safe.contains("\"alarms\":[]"));
+}
+
+#[test]
+fn program_tree_and_points() {
+    let report = json("x := 1; if x > 5 then { y := 2 } else { y := 3 }", &[]);
+    assert!(report.contains("\"program\":{\"kind\":\"sequence\""));
+    assert!(report.contains("\"kind\":\"if\""));
+    assert!(report.contains("\"points\":[{\"span\":{\"line\":1,\"column\":1},\"pre\":"));
+}
+
+#[test]
+fn assignment_is_located_at_its_target() {
+    let report = json("x := 5;\n  y := x", &[]);
+    assert!(
+        report.contains("\"kind\":\"assign\",\"span\":{\"line\":2,\"column\":3}"),
+        "{}",
+        report
+    );
+}
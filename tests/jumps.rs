@@ -0,0 +1,65 @@
+// `break` and `continue`: the innermost loop exits or goes on, the break states join the exit
+mod common;
+
+use common::*;
+
+const FIRST_SEVEN: &str =
+    "x := 0;\nwhile (x < 100) { x := x + 1; if x >= 7 then { break } else { skip } }\n";
+
+const SKIP_FIVE: &str = "s := 0;\n\
+                         for (i := 0; i < 10; i++) { if i < 5 then { continue } else { skip }; s := s + 1 }\n";
+
+#[test]
+fn break_leaves_the_innermost_loop() {
+    assert_eq!(final_concrete(&execute(FIRST_SEVEN, &[]), "x"), 7);
+    // the break states join the states filtered by the negated guard, which the loop never
+    // reaches here
+    let report = analyze(FIRST_SEVEN, &[]);
+    assert_eq!(
+        lines(&report, "CYCLE POSTCONDITION"),
+        ["CYCLE POSTCONDITION: {x:   [7, 7] }"]
+    );
+    let program =
+        "x := 0;\nrepeat { x := x + 2; if x >= 6 then { break } else { skip } } until (x > 100)\n";
+    assert_eq!(final_concrete(&execute(program, &[]), "x"), 6);
+    assert_eq!(final_value(&analyze(program, &[]), "x"), "[6, 7]");
+    // the outer loop runs all its iterations
+    let nested = "n := 0;\nfor (i := 0; i < 3; i++) {\n\
+                  j := 0;\n\
+                  while (j < 10) { j := j + 1; if j >= 2 then { break } else { skip } };\n\
+                  n := n + j\n\
+                  }\n";
+    assert_eq!(final_concrete(&execute(nested, &[]), "n"), 6);
+}
+
+#[test]
+fn continue_goes_on_with_the_next_iteration() {
+    let run = execute(SKIP_FIVE, &[]);
+    assert_eq!(final_concrete(&run, "s"), 5);
+    assert_eq!(final_concrete(&run, "i"), 10);
+    // the increment runs after a continue too
+    let report = analyze(SKIP_FIVE, &[]);
+    assert_eq!(final_value(&report, "i"), "[10, 10]");
+    assert_eq!(
+        lines(&report, "TERMINATION"),
+        ["TERMINATION OF THE LOOP AT 2:1: terminates (ranking function 10 - i)"]
+    );
+}
+
+#[test]
+fn jumps_outside_of_loops_are_errors() {
+    for (program, message) in [
+        ("x := 1;\nbreak\n", "break at 2:1 outside of a loop body"),
+        (
+            "if 1 > 0 then { continue } else { skip }\n",
+            "continue at 1:17 outside of a loop body",
+        ),
+    ] {
+        let output = run(
+            program,
+            &["--analysis", "abstract", "--bounds", "-10", "10"],
+        );
+        assert_eq!(output.status.code(), Some(2), "{}", program);
+        assert_eq!(stderr(&output), format!("**SYNTAX ERROR, {}**\n", message));
+    }
+}
@@ -0,0 +1,61 @@
+// Source listing annotated with the abstract state before and after every statement
+mod common;
+
+use common::*;
+
+#[test]
+fn statements_are_listed_between_their_states() {
+    let listing = analyze("x := 5;\ny := x + 1", &["--format", "annotated"]);
+    assert_eq!(
+        listing,
+        "/* {} */\n\
+         x := 5;\n\
+         /* {x: [5, 5]} */\n\
+         y := (x + 1)\n\
+         /* {x: [5, 5], y: [6, 6]} */\n"
+    );
+}
+
+#[test]
+fn loop_bodies_are_indented_with_their_states() {
+    let program = "y := 1;\nwhile (y < 10) {\n  y := y + 1\n}\n";
+    let listing = analyze(program, &["--format", "annotated"]);
+    let code: Vec<&str> = listing
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("/*"))
+        .collect();
+    assert_eq!(
+        code,
+        ["y := 1;", "while (y < 10) {", "    y := (y + 1)", "}"]
+    );
+    assert!(
+        listing.contains("while (y < 10) {\n    /* {y: "),
+        "{}",
+        listing
+    );
+    assert!(
+        listing.contains("    y := (y + 1)\n    /* {y: "),
+        "{}",
+        listing
+    );
+}
+
+#[test]
+fn missing_else_is_not_listed() {
+    let program = "x := 5;\nif x > 10 then {\n  x := 0\n};\nz := x\n";
+    let listing = analyze(program, &["--format", "annotated"]);
+    assert!(listing.contains("if x > 10 then {\n    /* "));
+    assert!(!listing.contains("else"));
+    assert!(!listing.contains("skip"));
+    assert!(listing.contains("};\n/* "));
+}
+
+#[test]
+fn variables_are_sorted() {
+    let listing = analyze("b := 1; a := 2", &["--format", "annotated"]);
+    assert!(
+        listing.ends_with("/* {a: [2, 2], b: [1, 1]} */\n"),
+        "{}",
+        listing
+    );
+}
@@ -0,0 +1,99 @@
+// Modulo, bitwise and shift operators: concrete values, forward and backward interval operators
+// and their alarms
+mod common;
+
+use common::*;
+
+#[test]
+fn concrete_values() {
+    let run = execute(
+        "x := 29;\na := x % 8;\nb := x & 12;\nc := x | 3;\nd := x ^ 5;\ne := x << 2;\n\
+         f := x >> 1;\ng := (0 - x) % 8;\nh := (0 - x) >> 1\n",
+        &[],
+    );
+    let values = ["a", "b", "c", "d", "e", "f", "g", "h"].map(|name| final_concrete(&run, name));
+    // the remainder takes the sign of the dividend, the right shift is arithmetic
+    assert_eq!(values, [5, 12, 31, 24, 116, 14, -5, -15]);
+}
+
+#[test]
+fn parenthesised_unary_minus() {
+    let program = "x := 4;\ny := (-x);\nz := (-(x + 1));\nw := -x % 3;\nv := (-x) << 1\n";
+    let run = execute(program, &[]);
+    let values = ["y", "z", "w", "v"].map(|name| final_concrete(&run, name));
+    assert_eq!(values, [-4, -5, -1, -8]);
+    let report = analyze(program, &[]);
+    let values = ["y", "z", "w", "v"].map(|name| final_value(&report, name));
+    assert_eq!(values, ["[-4, -4]", "[-5, -5]", "[-1, -1]", "[-8, -8]"]);
+}
+
+#[test]
+fn remainder_of_a_non_negative_value() {
+    let report = analyze("x := random(0, 100);\ny := x % 8\n", &[]);
+    assert_eq!(final_value(&report, "y"), "[0, 7]");
+    let report = analyze("x := random(0, 100) - 50;\ny := x % 8\n", &[]);
+    assert_eq!(final_value(&report, "y"), "[-7, 7]");
+}
+
+#[test]
+fn forward_interval_operators() {
+    let cases = [
+        ("x & 12", "[0, 12]"),
+        ("x | 3", "[3, 127]"),
+        ("x ^ 5", "[0, 127]"),
+        ("x << 2", "[0, 400]"),
+        ("x >> 1", "[0, 50]"),
+    ];
+    for (expression, value) in cases {
+        let report = analyze(&format!("x := random(0, 100);\ny := {}\n", expression), &[]);
+        assert_eq!(final_value(&report, "y"), value, "{}", expression);
+    }
+}
+
+#[test]
+fn backward_interval_operators() {
+    let cases = [
+        ("x % 4", "y < 2", "[2, 10]"),
+        ("x >> 1", "y < 3", "[6, 10]"),
+        ("x << 2", "y < 12", "[3, 10]"),
+    ];
+    for (expression, guard, before) in cases {
+        let program = format!(
+            "x := random(0, 10);\ny := {};\nassert({})\n",
+            expression, guard
+        );
+        let report = analyze(&program, &["--backward"]);
+        assert!(
+            report.contains(&format!("    BEFORE 2:1: {{x:   {} }}\n", before)),
+            "{}",
+            report
+        );
+    }
+}
+
+#[test]
+fn remainder_by_zero_and_invalid_shifts_are_alarms() {
+    let report: String = analyze("x := random(0, 100);\ny := 7 % x\n", &["--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(report.contains(
+        "{\"kind\":\"division-by-zero\",\"severity\":\"possible\",\
+         \"span\":{\"line\":2,\"column\":1},\"expression\":\"(7%x)\""
+    ));
+    let report: String = analyze("x := random(60, 70);\ny := 1 << x\n", &["--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(report.contains(
+        "{\"kind\":\"invalid-shift\",\"severity\":\"possible\",\
+         \"span\":{\"line\":2,\"column\":1},\"expression\":\"(1<<x)\""
+    ));
+    let output = run(
+        "x := random(60, 70);\ny := 1 << x\n",
+        &["--analysis", "concrete", "--random", "65"],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "**RUNTIME ERROR, shift amount 65 outside [0, 63] in (1 << x)**\n"
+    );
+}
@@ -0,0 +1,76 @@
+// The statement `print(e1, e2, ...)`: the values a concrete run writes and the intervals the
+// abstract run records for them
+mod common;
+
+use common::*;
+
+const PRINTS: &str =
+    "x := random(1, 3);\nprint(x, x * 2);\nfor (i := 0; i < 3; i++) { print(i) }\n";
+
+#[test]
+fn concrete_run_writes_every_print_executed() {
+    let run = execute(PRINTS, &["--random", "2"]);
+    assert_eq!(
+        lines(&run, "OUTPUT AT"),
+        [
+            "OUTPUT AT 2:1: 2, 4",
+            "OUTPUT AT 3:28: 0",
+            "OUTPUT AT 3:28: 1",
+            "OUTPUT AT 3:28: 2",
+        ]
+    );
+}
+
+#[test]
+fn abstract_output_covers_the_concrete_one() {
+    let report = analyze(PRINTS, &[]);
+    assert_eq!(
+        lines(&report, "ABSTRACT OUTPUT"),
+        [
+            "ABSTRACT OUTPUT AT 2:1: [1, 3], [2, 6]",
+            "ABSTRACT OUTPUT AT 3:28: [0, 2]",
+        ]
+    );
+    // prints never reached are left out
+    let report = analyze(
+        "x := 1;\nif x > 5 then { print(x) } else { print(0 - x) }\n",
+        &[],
+    );
+    assert_eq!(
+        lines(&report, "ABSTRACT OUTPUT"),
+        ["ABSTRACT OUTPUT AT 2:35: [-1, -1]"]
+    );
+}
+
+#[test]
+fn negative_first_argument() {
+    let program = "x := random(1, 3);\nprint(-x, 2)\n";
+    let run = execute(program, &["--random", "2"]);
+    assert_eq!(lines(&run, "OUTPUT AT"), ["OUTPUT AT 2:1: -2, 2"]);
+    let report = analyze(program, &[]);
+    assert_eq!(
+        lines(&report, "ABSTRACT OUTPUT"),
+        ["ABSTRACT OUTPUT AT 2:1: [-3, -1], [2, 2]"]
+    );
+}
+
+#[test]
+fn outputs_in_the_json_report() {
+    let run: String = execute(PRINTS, &["--random", "2", "--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(run.contains(
+        "\"output\":[{\"span\":{\"line\":2,\"column\":1},\"values\":[2,4]},\
+         {\"span\":{\"line\":3,\"column\":28},\"values\":[0]},\
+         {\"span\":{\"line\":3,\"column\":28},\"values\":[1]},\
+         {\"span\":{\"line\":3,\"column\":28},\"values\":[2]}]"
+    ));
+    let report: String = analyze(PRINTS, &["--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(report.contains(
+        "\"output\":[{\"span\":{\"line\":2,\"column\":1},\
+         \"values\":[{\"lower\":1,\"upper\":3},{\"lower\":2,\"upper\":6}]},\
+         {\"span\":{\"line\":3,\"column\":28},\"values\":[{\"lower\":0,\"upper\":2}]}]"
+    ));
+}
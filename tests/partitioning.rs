@@ -0,0 +1,59 @@
+// Trace partitioning: the states of the branches of an if stay apart up to a merge point
+mod common;
+
+use common::*;
+
+// x is [-5, +∞] after the loop and y = 1 only when x > 0, the division is safe
+const GUARDED_BY_A_FLAG: &str = "x := 0 - 5;\n\
+                                 k := 0;\n\
+                                 while (k <= 9) { k := k + 1; x := x + 1 };\n\
+                                 if x > 0 then { y := 1 } else { y := 0 };\n\
+                                 if y >= 1 then { z := 10 / x } else { z := 0 }\n";
+
+#[test]
+fn partitioning_removes_the_false_division_alarm() {
+    let merged = analyze(GUARDED_BY_A_FLAG, &["--format", "json"]);
+    assert!(merged.contains("division-by-zero"));
+    let partitioned = analyze(
+        GUARDED_BY_A_FLAG,
+        &["--partition", "block", "--format", "json"],
+    );
+    assert!(!partitioned.contains("division-by-zero"), "{}", partitioned);
+}
+
+#[test]
+fn partitions_are_tagged_by_the_branches_taken() {
+    let report: String = analyze(
+        GUARDED_BY_A_FLAG,
+        &["--partition", "block", "--format", "json"],
+    )
+    .split_whitespace()
+    .collect();
+    assert!(report.contains(
+        "\"partitions\":[{\"trace\":[\"then@4:1\",\"then@5:1\"],\"state\":{\"bottom\":false,\
+         \"variables\":{\"k\":{\"lower\":10,\"upper\":10},\"x\":{\"lower\":1,\"upper\":null},\
+         \"y\":{\"lower\":1,\"upper\":1},\"z\":{\"lower\":0,\"upper\":10}}}},\
+         {\"trace\":[\"else@4:1\",\"else@5:1\"],\"state\":{\"bottom\":false,\
+         \"variables\":{\"k\":{\"lower\":10,\"upper\":10},\"x\":{\"lower\":-5,\"upper\":0},\
+         \"y\":{\"lower\":0,\"upper\":0},\"z\":{\"lower\":0,\"upper\":0}}}}]"
+    ));
+    // the end of the program is a merge point
+    let report = analyze(GUARDED_BY_A_FLAG, &["--partition", "block"]);
+    assert_eq!(lines(&report, "PARTITION").len(), 2);
+    assert_eq!(final_value(&report, "z"), "[0, 10]");
+}
+
+#[test]
+fn merge_point_is_configurable() {
+    // the flag is set in a nested block, whose end merges the partitions of the inner if
+    let program = "x := 0 - 5;\n\
+                   k := 0;\n\
+                   while (k <= 9) { k := k + 1; x := x + 1 };\n\
+                   w := 1;\n\
+                   if w = 1 then { if x > 0 then { y := 1 } else { y := 0 } } else { y := 0 };\n\
+                   if y >= 1 then { z := 10 / x } else { z := 0 }\n";
+    let block = analyze(program, &["--partition", "block", "--format", "json"]);
+    assert!(block.contains("division-by-zero"));
+    let enclosing_loop = analyze(program, &["--partition", "loop", "--format", "json"]);
+    assert!(!enclosing_loop.contains("division-by-zero"));
+}
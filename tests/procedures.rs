@@ -0,0 +1,162 @@
+// Procedures: call by value in the concrete run, summaries per calling context and recursion in
+// the abstract one
+mod common;
+
+use common::*;
+
+const FACTORIAL: &str = "procedure fact(n) {\n\
+                         if n <= 1 then { r := 1 } else { r := n * fact(n - 1) };\n\
+                         return r\n\
+                         }\n\
+                         y := fact(5)\n";
+
+#[test]
+fn calls_by_value_in_the_concrete_run() {
+    assert_eq!(final_concrete(&execute(FACTORIAL, &[]), "y"), 120);
+    // the procedure sees only its parameters, its y is not the caller's
+    let run = execute(
+        "procedure f(x) { y := 7; return x }\ny := 1;\nz := f(y)\n",
+        &[],
+    );
+    assert_eq!(final_concrete(&run, "y"), 1);
+    assert_eq!(final_concrete(&run, "z"), 1);
+}
+
+#[test]
+fn negative_arguments() {
+    let program = "procedure double(x) { return x * 2 }\ny := double(-3)\n";
+    assert_eq!(final_concrete(&execute(program, &[]), "y"), -6);
+    assert_eq!(final_value(&analyze(program, &[]), "y"), "[-6, -6]");
+}
+
+#[test]
+fn summaries_per_calling_context() {
+    let report = analyze(
+        "input r in [0, 4];\nprocedure sq(x) { return x * x }\na := sq(3);\nb := sq(r);\nc := sq(3)\n",
+        &[],
+    );
+    assert_eq!(
+        lines(&report, "SUMMARY"),
+        [
+            "SUMMARY OF sq([3, 3]): [9, 9]",
+            "SUMMARY OF sq([0, 4]): [0, 16]"
+        ]
+    );
+    assert_eq!(final_value(&report, "a"), "[9, 9]");
+    assert_eq!(final_value(&report, "b"), "[0, 16]");
+    assert_eq!(final_value(&report, "c"), "[9, 9]");
+}
+
+#[test]
+fn recursion_is_widened_to_a_sound_result() {
+    let report = analyze(FACTORIAL, &[]);
+    assert_eq!(final_value(&report, "y"), "[1, +∞]");
+    let report = analyze(
+        "procedure down(n) { if n <= 0 then { r := 0 } else { r := down(n - 1) }; return r }\n\
+         y := down(10)\n",
+        &[],
+    );
+    assert_eq!(
+        lines(&report, "SUMMARY"),
+        ["SUMMARY OF down([10, 10]): [0, 0]"]
+    );
+}
+
+#[test]
+fn bodies_are_checked() {
+    let program = "procedure f(n) { if n > 10 then { s := 1 } else { skip }; return s }\n\
+                   y := f(3)\n";
+    let report = analyze(program, &[]);
+    assert_eq!(
+        lines(&report, "UNINITIALISED"),
+        ["UNINITIALISED READ (possible) OF s AT 1:59"]
+    );
+    assert_eq!(
+        lines(&report, "UNREACHABLE"),
+        ["UNREACHABLE AT 1:35: s := 1 (the guard n > 10 of the if at 1:18 is never true)"]
+    );
+    let report: String = analyze(program, &["--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(report.contains(
+        "{\"kind\":\"uninitialised-read\",\"severity\":\"possible\",\
+         \"span\":{\"line\":1,\"column\":59},\"expression\":\"s\""
+    ));
+    // the points of the body join the calling contexts, the then branch runs in f(20)
+    let report = analyze(&program.replace("f(3)", "f(3);\nz := f(20)"), &[]);
+    assert!(lines(&report, "UNREACHABLE").is_empty(), "{}", report);
+    let report = analyze(
+        "procedure f(n) { i := 0; while (i < n) { i := i + 1 }; return i }\ny := f(5)\n",
+        &[],
+    );
+    assert_eq!(
+        lines(&report, "TERMINATION"),
+        ["TERMINATION OF THE LOOP AT 1:26: terminates (ranking function n - i)"]
+    );
+    assert_eq!(
+        lines(&report, "ITERATIONS"),
+        ["ITERATIONS OF THE LOOP AT 1:26: [1, 5], bound 5"]
+    );
+}
+
+#[test]
+fn summaries_in_the_json_report() {
+    let report: String = analyze(
+        "procedure sq(x) { return x * x }\na := sq(3)\n",
+        &["--format", "json"],
+    )
+    .split_whitespace()
+    .collect();
+    assert!(report.contains(
+        "\"procedures\":[{\"name\":\"sq\",\"parameters\":[\"x\"],\"span\":{\"line\":1,\"column\":1},\
+         \"body\":null,\"return\":\"(x*x)\",\"summaries\":[{\"arguments\":[{\"lower\":3,\"upper\":3}],\
+         \"result\":{\"lower\":9,\"upper\":9}}]}]"
+    ));
+}
+
+#[test]
+fn runaway_recursion_is_a_runtime_error() {
+    let output = run(
+        "procedure f(x) { return f(x + 1) }\ny := f(1)\n",
+        &["--analysis", "concrete"],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "**RUNTIME ERROR, more than 1000 nested calls, the last one to procedure f**\n"
+    );
+}
+
+#[test]
+fn bad_declarations_and_calls_are_errors() {
+    let cases = [
+        (
+            "procedure inc(x) { return x + 1 }\nprocedure inc(y) { return y }\nz := inc(1)\n",
+            "procedure inc declared at 1:1 and again at 2:1",
+        ),
+        (
+            "procedure inc(x) { return x + 1 }\ny := inc(4, 5)\n",
+            "procedure inc called at 2:6 with 2 arguments instead of 1",
+        ),
+        (
+            "procedure f(a, a) { return a }\ny := f(1, 2)\n",
+            "parameter a repeated in procedure f declared at 1:1",
+        ),
+        (
+            "procedure f(a) { x := a }\ny := f(1)\n",
+            "procedure f declared at 1:1 without a return",
+        ),
+        (
+            "procedure f(a) { return a }\ny := g(1)\n",
+            "procedure g not declared",
+        ),
+    ];
+    for (program, message) in cases {
+        let output = run(
+            program,
+            &["--analysis", "abstract", "--bounds", "-10", "10"],
+        );
+        assert_eq!(output.status.code(), Some(2), "{}", program);
+        assert_eq!(stderr(&output), format!("**SYNTAX ERROR, {}**\n", message));
+    }
+}
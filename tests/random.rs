@@ -0,0 +1,86 @@
+// Nondeterminism: `?`, `random(a, b)` and `either { S1 } or { S2 }` in both semantics
+mod common;
+
+use common::*;
+
+const DRAWS: &str = "x := random(2, 5);\ny := random(0, 100);\neither { z := 1 } or { z := 10 }\n";
+
+#[test]
+fn abstract_run_covers_every_outcome() {
+    let report = analyze(
+        "x := ?;\ny := random(2, 5);\neither { z := 1 } or { z := 10 }\n",
+        &[],
+    );
+    assert_eq!(final_value(&report, "x"), "[-∞, +∞]");
+    assert_eq!(final_value(&report, "y"), "[2, 5]");
+    assert_eq!(final_value(&report, "z"), "[1, 10]");
+}
+
+#[test]
+fn scripted_values_come_first() {
+    let run = execute(DRAWS, &["--random", "4,77"]);
+    assert_eq!(final_concrete(&run, "x"), 4);
+    assert_eq!(final_concrete(&run, "y"), 77);
+}
+
+#[test]
+fn same_seed_same_run() {
+    let values = |report: &str| ["x", "y", "z"].map(|variable| final_concrete(report, variable));
+    let first = values(&execute(DRAWS, &["--seed", "7"]));
+    assert_eq!(first, values(&execute(DRAWS, &["--seed", "7"])));
+    assert!((2..=5).contains(&first[0]));
+    assert!((0..=100).contains(&first[1]));
+    assert!(first[2] == 1 || first[2] == 10);
+}
+
+#[test]
+fn draws_out_of_range_are_runtime_errors() {
+    let cases = [
+        (
+            "x := random(2, 5)\n",
+            "9",
+            "scripted value 9 outside the range [2, 5] of random(2, 5)",
+        ),
+        (
+            "x := random(5, 2)\n",
+            "",
+            "empty range [5, 2] of random(5, 2)",
+        ),
+    ];
+    for (program, script, message) in cases {
+        let mut args = vec!["--analysis", "concrete"];
+        if !script.is_empty() {
+            args.extend(["--random", script]);
+        }
+        let output = run(program, &args);
+        assert_eq!(output.status.code(), Some(1), "{}", program);
+        assert_eq!(stderr(&output), format!("**RUNTIME ERROR, {}**\n", message));
+    }
+    // no value at all in the abstract run
+    let report = analyze("x := random(5, 2)\n", &[]);
+    assert_eq!(final_value(&report, "x"), "Bottom ┴");
+}
+
+#[test]
+fn negative_bounds() {
+    let program = "x := random(-5, 5)\n";
+    assert_eq!(final_value(&analyze(program, &[]), "x"), "[-5, 5]");
+    assert_eq!(final_concrete(&execute(program, &["--random", "-3"]), "x"), -3);
+    let x = final_concrete(&execute(program, &["--seed", "7"]), "x");
+    assert!((-5..=5).contains(&x));
+}
+
+#[test]
+fn random_takes_two_arguments() {
+    for (program, count) in [("x := random(1, 2, 3)\n", 3), ("x := random(1)\n", 1)] {
+        let output = run(program, &["--analysis", "abstract", "--bounds", "-10", "10"]);
+        assert_eq!(output.status.code(), Some(2), "{}", program);
+        assert_eq!(
+            stderr(&output),
+            format!(
+                "**SYNTAX ERROR, random called at 1:6 with {} arguments instead of 2**\n",
+                count
+            )
+        );
+    }
+}
@@ -0,0 +1,64 @@
+// Unreachable statements: those whose entry state is ⊥, with the guard that makes them so
+mod common;
+
+use common::*;
+
+#[test]
+fn dead_branches_loop_bodies_and_code_after_a_loop() {
+    let program = "input x in [0, 5];\n\
+                   if x > 10 then { y := 1 } else { y := 2 };\n\
+                   while (x > 20) { x := x - 1 };\n\
+                   while (x >= 0) { x := x + 1 };\n\
+                   z := 3\n";
+    let report = analyze(program, &[]);
+    assert_eq!(
+        lines(&report, "UNREACHABLE AT"),
+        [
+            "UNREACHABLE AT 2:18: y := 1 (the guard x > 10 of the if at 2:1 is never true)",
+            "UNREACHABLE AT 3:18: x := (x - 1) (the guard x > 20 of the loop at 3:1 is never true)",
+            "UNREACHABLE AT 5:1: z := 3 (the loop at 4:1 never exits, its guard x >= 0 is never false)",
+        ]
+    );
+}
+
+#[test]
+fn else_branch_never_taken() {
+    let report = analyze("x := 1;\nif x > 0 then { y := 1 } else { y := 2 }\n", &[]);
+    assert_eq!(
+        lines(&report, "UNREACHABLE AT"),
+        ["UNREACHABLE AT 2:33: y := 2 (the guard x > 0 of the if at 2:1 is never false)"]
+    );
+}
+
+#[test]
+fn reachable_code_is_not_reported() {
+    let program = "input x in [0, 5];\nif x > 2 then { y := 1 } else { y := 2 };\n\
+                   while (x > 0) { x := x - 1 }\n";
+    let report = analyze(program, &[]);
+    assert!(lines(&report, "UNREACHABLE AT").is_empty());
+    let json = analyze(program, &["--format", "json"]);
+    assert!(json.contains("\"unreachable\": []"));
+}
+
+#[test]
+fn unreachable_statements_in_the_json_report() {
+    let report: String = analyze(
+        "x := 1;\nif x > 5 then { y := 1 } else { skip }\n",
+        &["--format", "json"],
+    )
+    .split_whitespace()
+    .collect();
+    assert!(report.contains(
+        "\"unreachable\":[{\"span\":{\"line\":2,\"column\":17},\"statement\":\"y:=1\",\
+         \"cause\":\"theguardx>5oftheifat2:1isnevertrue\"}]"
+    ));
+}
+
+#[test]
+fn statements_are_located_at_their_start() {
+    let report = analyze("x := 5;\nif x > 10 then {\n  x := 0\n}", &[]);
+    assert_eq!(
+        lines(&report, "UNREACHABLE AT"),
+        ["UNREACHABLE AT 3:3: x := 0 (the guard x > 10 of the if at 2:1 is never true)"]
+    );
+}
@@ -0,0 +1,56 @@
+// SARIF log of the alarms and the failure of assertions in the concrete run
+mod common;
+
+use common::*;
+
+fn sarif(program: &str) -> String {
+    analyze(program, &["--format", "sarif"])
+        .split_whitespace()
+        .collect()
+}
+
+// x grows past 5 in the loop but the analysis cannot rule out 0
+const COUNTER: &str =
+    "x := 0;\ny := 0;\nwhile (y <= 9) { y := y + 1; x := x + y };\nassert(x < 5);\n  z := 10 / x\n";
+
+#[test]
+fn alarms_carry_rule_severity_and_region() {
+    let log = sarif(COUNTER);
+    assert!(log.contains("\"version\":\"2.1.0\""));
+    assert!(log.contains(
+        "{\"ruleId\":\"assertion\",\"ruleIndex\":2,\"level\":\"warning\",\
+         \"message\":{\"text\":\"possibleassertionfailureinx<5,abstractstate{"
+    ));
+    assert!(log.contains(
+        "{\"ruleId\":\"division-by-zero\",\"ruleIndex\":0,\"level\":\"warning\",\
+         \"message\":{\"text\":\"possibledivisionbyzeroin(10/x),abstractstate{"
+    ));
+    // the region of an assignment starts at its target, not at `:=`
+    assert!(log.contains("\"region\":{\"startLine\":5,\"startColumn\":3}"));
+}
+
+#[test]
+fn definite_failure_is_an_error() {
+    let log = sarif("x := 0;\nassert(x > 20)\n");
+    assert!(log.contains("\"ruleId\":\"assertion\",\"ruleIndex\":2,\"level\":\"error\""));
+    assert!(log.contains("\"severity\":\"definite\""));
+}
+
+#[test]
+fn no_alarms_no_results() {
+    assert!(sarif("x := 3; y := 10 / x").contains("\"results\":[]"));
+}
+
+#[test]
+fn failed_assertion_stops_the_concrete_run() {
+    let output = run(
+        "x := 3;\nassert(x > 5);\ny := 1",
+        &["--analysis", "concrete"],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stderr(&output),
+        "**RUNTIME ERROR, assertion x > 5 failed at 2:1**\n"
+    );
+    assert!(!stdout(&output).contains("state printing after code evaluation"));
+}
@@ -0,0 +1,65 @@
+// Block-scoped locals `var x := e in { S }`: shadowing, and the local dropped at the block exit
+mod common;
+
+use common::*;
+
+const SHADOWED: &str = "x := 1;\nvar x := 10 in { y := x + 1 };\nz := x\n";
+
+#[test]
+fn a_local_shadows_without_changing_the_outer_variable() {
+    let run = execute(SHADOWED, &[]);
+    assert_eq!(final_concrete(&run, "y"), 11);
+    assert_eq!(final_concrete(&run, "x"), 1);
+    assert_eq!(final_concrete(&run, "z"), 1);
+    let report = analyze(SHADOWED, &[]);
+    assert_eq!(final_value(&report, "y"), "[11, 11]");
+    assert_eq!(final_value(&report, "z"), "[1, 1]");
+}
+
+#[test]
+fn locals_are_dropped_at_the_block_exit() {
+    let program = "var t := 5 in { y := t * 2 };\nw := y\n";
+    let report: String = analyze(program, &["--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(report.contains(
+        "\"final_state\":{\"bottom\":false,\"variables\":{\"w\":{\"lower\":10,\"upper\":10},\
+         \"y\":{\"lower\":10,\"upper\":10}}}"
+    ));
+    assert!(!execute(program, &[]).contains("\"t\""));
+    // the loop invariant leaves out the local of the body
+    let report: String = analyze(
+        "s := 0;\nfor (i := 0; i < 4; i++) { var d := i * 2 in { s := s + d } }\n",
+        &["--format", "json"],
+    )
+    .split_whitespace()
+    .collect();
+    assert!(report.contains(
+        "\"invariants\":[{\"span\":{\"line\":2,\"column\":1},\"unrolled\":[],\
+         \"state\":{\"bottom\":false,\"variables\":{\"i\":{\"lower\":0,\"upper\":4},\
+         \"s\":{\"lower\":0,\"upper\":null}}}}]"
+    ));
+}
+
+#[test]
+fn reading_a_dropped_local_is_uninitialised() {
+    let report = analyze("var t := 5 in { y := t };\nw := t\n", &[]);
+    assert_eq!(
+        lines(&report, "UNINITIALISED READ"),
+        ["UNINITIALISED READ (definite) OF t AT 2:1"]
+    );
+}
+
+#[test]
+fn jumps_out_of_a_block_drop_its_locals() {
+    let program = "x := 0;\n\
+                   while (x < 10) { var t := x in { if t > 3 then { break } else { skip } }; x := x + 1 }\n";
+    let run = execute(program, &[]);
+    assert_eq!(final_concrete(&run, "x"), 4);
+    assert!(!run.contains("\"t\""));
+    let report = analyze(program, &[]);
+    assert_eq!(
+        lines(&report, "CYCLE POSTCONDITION"),
+        ["CYCLE POSTCONDITION: {x:   [0, 10] }"]
+    );
+}
@@ -0,0 +1,68 @@
+// Switch: the case of the value of the selector runs, refined to its label, the default to the
+// values of none of the labels
+mod common;
+
+use common::*;
+
+const CASES: &str = "x := random(0, 10);\n\
+                     switch (x) { case 0: y := x; case 1: y := x + 10; case 5: y := x * 100; default: z := x }\n";
+
+#[test]
+fn the_case_of_the_value_runs() {
+    let run = execute(CASES, &["--random", "5"]);
+    assert_eq!(final_concrete(&run, "y"), 500);
+    assert!(!run.contains("\"z\""));
+    let run = execute(CASES, &["--random", "7"]);
+    assert_eq!(final_concrete(&run, "z"), 7);
+    assert!(!run.contains("\"y\""));
+}
+
+#[test]
+fn branches_are_refined_to_their_labels() {
+    let listing = analyze(CASES, &["--format", "annotated"]);
+    for refined in [
+        "    case 0:\n        /* {x: [0, 0]} */\n",
+        "    case 1:\n        /* {x: [1, 1]} */\n",
+        "    case 5:\n        /* {x: [5, 5]} */\n",
+        // 0 and 1 are at the end of [0, 10], 5 is a hole an interval cannot make
+        "    default:\n        /* {x: [2, 10]} */\n",
+    ] {
+        assert!(listing.contains(refined), "{}", listing);
+    }
+    assert!(listing.ends_with("}\n/* {x: [0, 10], y: [0, 500], z: [2, 10]} */\n"));
+}
+
+#[test]
+fn negative_labels() {
+    let program = "x := random(-2, 2);\n\
+                   switch (x) { case -1: y := x; case 1: y := 10 * x; default: y := 0 }\n";
+    assert_eq!(final_concrete(&execute(program, &["--random", "-1"]), "y"), -1);
+    let listing = analyze(program, &["--format", "annotated"]);
+    assert!(
+        listing.contains("    case -1:\n        /* {x: [-1, -1]} */\n"),
+        "{}",
+        listing
+    );
+    assert!(listing.ends_with("}\n/* {x: [-2, 2], y: [-1, 10]} */\n"));
+}
+
+#[test]
+fn repeated_labels_are_errors() {
+    for (program, message) in [
+        (
+            "x := 1;\nswitch (x) { case 1: y := 1; case 1: y := 2 }\n",
+            "case 1 repeated in the switch at 2:1",
+        ),
+        (
+            "x := 1;\nswitch (x) { case 1: y := 1; default: y := 2; default: y := 3 }\n",
+            "default repeated in the switch at 2:1",
+        ),
+    ] {
+        let output = run(
+            program,
+            &["--analysis", "abstract", "--bounds", "-10", "10"],
+        );
+        assert_eq!(output.status.code(), Some(2), "{}", program);
+        assert_eq!(stderr(&output), format!("**SYNTAX ERROR, {}**\n", message));
+    }
+}
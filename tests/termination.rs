@@ -0,0 +1,92 @@
+// Termination of loops: ranking functions, and the exit states of the forward run
+mod common;
+
+use common::*;
+
+#[test]
+fn ranking_functions_of_terminating_loops() {
+    let report = analyze("x := 0;\nwhile (x < 10) { x := x + 1 }\n", &[]);
+    assert_eq!(
+        lines(&report, "TERMINATION"),
+        ["TERMINATION OF THE LOOP AT 2:1: terminates (ranking function 10 - x)"]
+    );
+    let report = analyze(
+        "input n in [0, 100];\nrepeat { n := n - 2 } until (n <= 0)\n",
+        &[],
+    );
+    assert_eq!(
+        lines(&report, "TERMINATION"),
+        ["TERMINATION OF THE LOOP AT 2:1: terminates (ranking function n)"]
+    );
+}
+
+#[test]
+fn decrementing_for_loop_terminates() {
+    let report = analyze("x := 0;\nfor (i := 11; i > 0; i--) { x := x + 1 }\n", &[]);
+    assert_eq!(final_value(&report, "i"), "[0, 0]");
+    assert_eq!(
+        lines(&report, "TERMINATION"),
+        ["TERMINATION OF THE LOOP AT 2:1: terminates (ranking function i)"]
+    );
+}
+
+#[test]
+fn loops_that_are_never_left_may_not_terminate() {
+    let report = analyze("input x in [0, 5];\nwhile (x >= 0) { x := x + 1 }\n", &[]);
+    assert_eq!(
+        lines(&report, "TERMINATION"),
+        ["TERMINATION OF THE LOOP AT 2:1: may not terminate"]
+    );
+    // 11 is beyond the bounds, the forward run never leaves the loop and i proves nothing then
+    let output = run(
+        "x := 0;\nfor (i := 11; i > 0; i--) { x := x + 1 }\n",
+        &["--analysis", "abstract", "--bounds", "-5", "5"],
+    );
+    assert_eq!(
+        lines(&stdout(&output), "TERMINATION"),
+        ["TERMINATION OF THE LOOP AT 2:1: may not terminate"]
+    );
+}
+
+#[test]
+fn stepping_over_the_disequality_may_not_terminate() {
+    // x skips 1 and 0, the loop heads go down to -∞ and bound no candidate
+    for program in [
+        "x := 8;\nwhile (x != 1) { x := x - 2 }\n",
+        "x := 10;\nwhile (x != 0) { x := x - 3 }\n",
+    ] {
+        let report = analyze(program, &[]);
+        assert_eq!(
+            lines(&report, "TERMINATION"),
+            ["TERMINATION OF THE LOOP AT 2:1: may not terminate"],
+            "{}",
+            program
+        );
+    }
+}
+
+#[test]
+fn unknown_without_a_ranking_function() {
+    let report = analyze(
+        "input x in [-10, 10];\ninput d in [-1, 2];\nwhile (x < 10) { x := x + d }\n",
+        &[],
+    );
+    assert_eq!(
+        lines(&report, "TERMINATION"),
+        ["TERMINATION OF THE LOOP AT 3:1: unknown"]
+    );
+}
+
+#[test]
+fn verdicts_in_the_json_report() {
+    let report: String = analyze(
+        "x := 0;\nwhile (x < 10) { x := x + 1 }\n",
+        &["--format", "json"],
+    )
+    .split_whitespace()
+    .collect();
+    assert!(report.contains(
+        "\"termination\":[{\"span\":{\"line\":2,\"column\":1},\"verdict\":\"terminates\",\
+         \"ranking_function\":\"10-x\"}]"
+    ));
+}
@@ -0,0 +1,76 @@
+// Loop unrolling: the first iterations of a loop are analysed one at a time before its fixpoint
+mod common;
+
+use common::*;
+
+const FACTORIAL: &str = "f := 1; i := 1; while (i <= 4) { f := f * i; i := i + 1 }";
+
+#[test]
+fn unrolled_iterations_are_listed_apart() {
+    let report = analyze(FACTORIAL, &["--unroll", "2"]);
+    let invariants = lines(&report, "CYCLE INVARIANT");
+    assert_eq!(invariants.len(), 3);
+    assert!(invariants[0].starts_with("CYCLE INVARIANT (ITERATION 1): "));
+    assert!(invariants[1].starts_with("CYCLE INVARIANT (ITERATION 2): "));
+    assert!(invariants[2].starts_with("CYCLE INVARIANT (ITERATIONS > 2): "));
+    // the invariant of the remaining iterations starts after f := 1 * 2
+    assert!(invariants[2].contains("f:   [2, +∞]"), "{}", invariants[2]);
+    assert!(invariants[2].contains("i:   [3, 5]"), "{}", invariants[2]);
+    assert_eq!(final_value(&report, "i"), "[5, 5]");
+}
+
+#[test]
+fn no_unrolling_by_default() {
+    let report = analyze(FACTORIAL, &[]);
+    assert_eq!(lines(&report, "CYCLE INVARIANT").len(), 1);
+    assert!(report.contains("CYCLE INVARIANT: {"));
+}
+
+#[test]
+fn annotation_overrides_the_option_for_its_loop() {
+    let program = "x := 0; for (i := 0; i < 3; i++) { x := x + 2 }; \
+                   @unroll(3) while (x > 0) { x := x - 1 }";
+    let report = analyze(program, &["--unroll", "1"]);
+    let invariants = lines(&report, "CYCLE INVARIANT");
+    assert_eq!(invariants.len(), 2 + 4);
+    assert!(invariants[1].starts_with("CYCLE INVARIANT (ITERATIONS > 1): "));
+    assert!(invariants[5].starts_with("CYCLE INVARIANT (ITERATIONS > 3): "));
+    assert_eq!(final_value(&report, "x"), "[0, 0]");
+}
+
+#[test]
+fn unrolled_states_in_the_json_report() {
+    let report: String = analyze(FACTORIAL, &["--unroll", "1", "--format", "json"])
+        .split_whitespace()
+        .collect();
+    assert!(report.contains(
+        "\"unrolled\":[{\"iteration\":1,\"state\":{\"bottom\":false,\"variables\":{\
+         \"f\":{\"lower\":1,\"upper\":1},\"i\":{\"lower\":1,\"upper\":1}}}}]"
+    ));
+}
+
+#[test]
+fn malformed_unroll_annotations_are_errors() {
+    let cases = [
+        (
+            "@unroll x := 1",
+            "annotation at 1:1 is not @name(n, ...) with integer arguments, found \"x\" at 1:9",
+        ),
+        (
+            "@unroll(-2) x := 1",
+            "@unroll at 1:1 expects one non-negative number of iterations",
+        ),
+        (
+            "@unroll(1, 2) x := 1",
+            "@unroll at 1:1 expects one non-negative number of iterations",
+        ),
+    ];
+    for (program, message) in cases {
+        let output = run(
+            program,
+            &["--analysis", "abstract", "--bounds", "-10", "10"],
+        );
+        assert_eq!(output.status.code(), Some(2), "{}", program);
+        assert_eq!(stderr(&output), format!("**SYNTAX ERROR, {}**\n", message));
+    }
+}
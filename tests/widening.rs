@@ -0,0 +1,175 @@
+// Fixpoint iteration of loops: delayed widening, the iteration cap, widening thresholds and the
+// descending iterations
+mod common;
+
+use common::*;
+
+const COUNT_TO_TEN: &str = "x := 0; while (x <= 9) { x := x + 1 }";
+
+#[test]
+fn joins_alone_reach_the_fixpoint() {
+    let report = analyze(COUNT_TO_TEN, &["--widening", "n", "--narrowing", "n"]);
+    assert_eq!(
+        lines(&report, "CYCLE INVARIANT"),
+        ["CYCLE INVARIANT: {x:   [0, 10] }"]
+    );
+}
+
+#[test]
+fn delayed_widening_keeps_the_bound() {
+    let eager = analyze(COUNT_TO_TEN, &["--narrowing", "n"]);
+    assert_eq!(
+        lines(&eager, "CYCLE INVARIANT"),
+        ["CYCLE INVARIANT: {x:   [0, +∞] }"]
+    );
+    let delayed = analyze(
+        COUNT_TO_TEN,
+        &["--narrowing", "n", "--widening-delay", "20"],
+    );
+    assert_eq!(
+        lines(&delayed, "CYCLE INVARIANT"),
+        ["CYCLE INVARIANT: {x:   [0, 10] }"]
+    );
+}
+
+#[test]
+fn iteration_cap_is_reported() {
+    let output = run(
+        COUNT_TO_TEN,
+        &[
+            "--analysis",
+            "abstract",
+            "--bounds",
+            "-1000",
+            "1000",
+            "--widening",
+            "n",
+            "--narrowing",
+            "n",
+            "--max-iterations",
+            "3",
+        ],
+    );
+    assert!(output.status.success());
+    assert!(stderr(&output).contains("the loop at 1:9 did not stabilize in 3 iterations"));
+    assert_eq!(
+        lines(&stdout(&output), "CYCLE INVARIANT"),
+        ["CYCLE INVARIANT: {x:   [0, +∞] }"]
+    );
+}
+
+const COUNT_TO_HUNDRED: &str = "x := 0; while (x <= 99) { x := x + 1 }";
+
+#[test]
+fn thresholds_from_the_command_line() {
+    let report = analyze(
+        COUNT_TO_HUNDRED,
+        &["--narrowing", "n", "--thresholds", "100"],
+    );
+    assert_eq!(
+        lines(&report, "WIDENING THRESHOLDS"),
+        ["WIDENING THRESHOLDS: [-1000, 100, 1000]"]
+    );
+    assert_eq!(
+        lines(&report, "CYCLE INVARIANT"),
+        ["CYCLE INVARIANT: {x:   [0, 100] }"]
+    );
+}
+
+#[test]
+fn thresholds_from_an_annotation() {
+    let program = format!("@thresholds(100, -5)\n{}", COUNT_TO_HUNDRED);
+    let report = analyze(&program, &["--narrowing", "n"]);
+    assert_eq!(
+        lines(&report, "WIDENING THRESHOLDS"),
+        ["WIDENING THRESHOLDS: [-1000, -5, 100, 1000]"]
+    );
+    assert_eq!(
+        lines(&report, "CYCLE INVARIANT"),
+        ["CYCLE INVARIANT: {x:   [0, 100] }"]
+    );
+}
+
+#[test]
+fn inferred_thresholds_come_from_the_loop() {
+    let report = analyze(
+        &format!("y := 7; {}", COUNT_TO_HUNDRED),
+        &["--narrowing", "n", "--infer-thresholds"],
+    );
+    assert_eq!(
+        lines(&report, "WIDENING THRESHOLDS"),
+        ["WIDENING THRESHOLDS: [-1000, -1, 0, 1, 98, 99, 100, 1000]"]
+    );
+    assert!(lines(&report, "CYCLE INVARIANT")[0].contains("x:   [0, 100] "));
+}
+
+#[test]
+fn thresholds_are_not_printed_by_the_concrete_run() {
+    let report = execute(&format!("@thresholds(100) {}", COUNT_TO_HUNDRED), &[]);
+    assert!(lines(&report, "WIDENING THRESHOLDS").is_empty());
+    assert_eq!(final_concrete(&report, "x"), 100);
+}
+
+#[test]
+fn malformed_annotations_are_errors() {
+    let cases = [
+        ("@thresholds(a) x := 1", "annotation at 1:1 is not @name(n, ...) with integer arguments, found \"a\" at 1:13"),
+        ("x := 1;\n@thresholds(1, 2", "annotation at 2:1 is not @name(n, ...) with integer arguments, found the end of the program"),
+        ("@thresholds x := 1", "annotation at 1:1 is not @name(n, ...) with integer arguments, found \"x\" at 1:13"),
+    ];
+    for (program, message) in cases {
+        let output = run(
+            program,
+            &["--analysis", "abstract", "--bounds", "-10", "10"],
+        );
+        assert_eq!(output.status.code(), Some(2), "{}", program);
+        assert_eq!(stderr(&output), format!("**SYNTAX ERROR, {}**\n", message));
+    }
+}
+
+// z only gets its bound from y in the second descending step
+const SHIFT_REGISTER: &str =
+    "x := 0; y := 0; z := 0; while (x < 100) { z := y; y := x; x := x + 1 }";
+
+#[test]
+fn each_descending_step_is_recorded() {
+    let report = analyze(SHIFT_REGISTER, &["--descending-steps", "2"]);
+    assert_eq!(
+        lines(&report, "DESCENDING STEP"),
+        [
+            "DESCENDING STEP 1 (narrowing) AT 1:25: x [0, +∞] -> [0, 100]",
+            "DESCENDING STEP 1 (narrowing) AT 1:25: y [0, +∞] -> [0, 99]",
+            "DESCENDING STEP 2 (narrowing) AT 1:25: z [0, +∞] -> [0, 99]",
+        ]
+    );
+    assert_eq!(final_value(&report, "z"), "[0, 99]");
+}
+
+#[test]
+fn descending_steps_are_bounded() {
+    let report = analyze(SHIFT_REGISTER, &["--descending-steps", "1"]);
+    assert_eq!(lines(&report, "DESCENDING STEP").len(), 2);
+    assert_eq!(final_value(&report, "y"), "[0, 99]");
+    assert_eq!(final_value(&report, "z"), "[0, +∞]");
+    let none = analyze(SHIFT_REGISTER, &["--descending-steps", "0"]);
+    assert!(lines(&none, "DESCENDING STEP").is_empty());
+    assert_eq!(final_value(&none, "x"), "[100, +∞]");
+}
+
+#[test]
+fn decreasing_iterations_without_narrowing_operator() {
+    let report = analyze(SHIFT_REGISTER, &["--descending", "decreasing"]);
+    assert_eq!(
+        lines(&report, "DESCENDING STEP 2"),
+        ["DESCENDING STEP 2 (decreasing) AT 1:25: z [0, +∞] -> [0, 99]"]
+    );
+    assert_eq!(final_value(&report, "x"), "[100, 100]");
+}
+
+#[test]
+fn no_descending_phase_without_narrowing() {
+    for program in [SHIFT_REGISTER, "for (i := 0; i < 10; i++) { x := i }"] {
+        let report = analyze(program, &["--narrowing", "n"]);
+        assert!(lines(&report, "DESCENDING STEP").is_empty(), "{}", report);
+    }
+}